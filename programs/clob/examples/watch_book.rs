@@ -0,0 +1,49 @@
+//! Print a market's L2 level changes as they land.
+//!
+//! cargo run -p clob --example watch_book --features client -- \
+//!     <ws url> <market> <bids> <asks>
+use anchor_lang::prelude::Pubkey;
+use clob::client::subscribe_book_levels;
+use futures_util::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use std::str::FromStr;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [url, market, bids, asks] = args.as_slice() else {
+        return Err("usage: watch_book <ws url> <market> <bids> <asks>".into());
+    };
+    let market = Pubkey::from_str(market)?;
+    let bids = Pubkey::from_str(bids)?;
+    let asks = Pubkey::from_str(asks)?;
+
+    let pubsub = PubsubClient::new(url).await?;
+    let (mut updates, unsubscribe) = subscribe_book_levels(&pubsub, &market, &bids, &asks).await?;
+
+    let mut last_seq_num = None;
+    while let Some(update) = updates.next().await {
+        let update = update?;
+        if last_seq_num.is_some_and(|seq_num| update.book_seq_num < seq_num) {
+            eprintln!("book_seq_num went backwards; resubscribe from a fresh snapshot");
+            break;
+        }
+        last_seq_num = Some(update.book_seq_num);
+
+        for change in &update.changes {
+            println!(
+                "slot {} seq {} {:?} {}: {} -> {}",
+                update.slot,
+                update.book_seq_num,
+                update.side,
+                change.price,
+                change.old_quantity,
+                change.new_quantity
+            );
+        }
+    }
+
+    drop(updates);
+    unsubscribe().await;
+    Ok(())
+}
@@ -0,0 +1,212 @@
+//! Off-chain helpers for locating program accounts over RPC and following
+//! the book over pubsub
+pub use crate::errors::ErrorCode;
+use crate::state::{
+    book_levels, decode_book_side, diff_levels, BookLevels, LevelChange, Market, Side, UserBalance,
+    USER_BALANCE_MARKET_OFFSET, USER_BALANCE_OWNER_OFFSET, USER_BALANCE_SIZE,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use futures_util::future::{self, BoxFuture, FutureExt};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::pubsub_client::{PubsubClient, PubsubClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::Response,
+};
+
+/// Filters matching every UserBalance account of this program
+pub fn user_balance_filters() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(USER_BALANCE_SIZE as u64),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, UserBalance::DISCRIMINATOR)),
+    ]
+}
+
+/// Filters matching one owner's balances across all markets
+pub fn user_balances_by_owner_filters(owner: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = user_balance_filters();
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        USER_BALANCE_OWNER_OFFSET,
+        owner.as_ref(),
+    )));
+    filters
+}
+
+/// Filters matching every user's balance in one market
+pub fn user_balances_by_market_filters(market: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = user_balance_filters();
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        USER_BALANCE_MARKET_OFFSET,
+        market.as_ref(),
+    )));
+    filters
+}
+
+/// Run getProgramAccounts with the given filters and decode the results
+pub async fn fetch_user_balances(
+    client: &RpcClient,
+    filters: Vec<RpcFilterType>,
+) -> ClientResult<Vec<(Pubkey, UserBalance)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = client
+        .get_program_accounts_with_config(&crate::ID, config)
+        .await?;
+
+    accounts
+        .into_iter()
+        .map(|(address, account)| {
+            UserBalance::try_deserialize(&mut account.data.as_slice())
+                .map(|balance| (address, balance))
+                .map_err(|e| {
+                    ClientError::from(ClientErrorKind::Custom(format!(
+                        "failed to decode UserBalance {address}: {e}"
+                    )))
+                })
+        })
+        .collect()
+}
+
+/// The program's ErrorCode behind a failed RPC call, if it was one of ours
+pub fn error_code_from_client_error(error: &ClientError) -> Option<ErrorCode> {
+    ErrorCode::from_log(&error.to_string())
+}
+
+/// Level changes on one side of the book from a single account update
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BookLevelUpdate {
+    pub side: Side,
+    pub slot: u64,                 // Slot the book account was updated at
+    pub book_seq_num: u64,         // Market.book_seq_num as last reported
+    pub changes: Vec<LevelChange>, // Ascending by price
+}
+
+#[derive(Clone, Copy)]
+enum WatchedAccount {
+    Market,
+    Book,
+}
+
+// Each notification reduced to the account it came from, its slot and the
+// raw account data
+fn tag_updates<'a>(
+    updates: BoxStream<'a, Response<UiAccount>>,
+    account: WatchedAccount,
+) -> BoxStream<'a, (WatchedAccount, u64, Option<Vec<u8>>)> {
+    updates
+        .map(move |response| (account, response.context.slot, response.value.data.decode()))
+        .boxed()
+}
+
+// Last state seen for each subscribed account
+#[derive(Default)]
+struct BookWatch {
+    bids: BookLevels,
+    asks: BookLevels,
+    book_seq_num: u64,
+}
+
+impl BookWatch {
+    fn apply(
+        &mut self,
+        account: WatchedAccount,
+        slot: u64,
+        data: Option<Vec<u8>>,
+    ) -> Option<ClientResult<BookLevelUpdate>> {
+        let decode_error = |e: String| {
+            Some(Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "failed to decode book update at slot {slot}: {e}"
+            )))))
+        };
+        let Some(data) = data else {
+            return decode_error("unsupported account encoding".to_string());
+        };
+
+        match account {
+            WatchedAccount::Market => {
+                match Market::try_deserialize(&mut data.as_slice()) {
+                    Ok(market) => self.book_seq_num = market.book_seq_num,
+                    Err(e) => return decode_error(e.to_string()),
+                }
+                None
+            }
+            WatchedAccount::Book => {
+                let (side, orders) = match decode_book_side(&data) {
+                    Ok(decoded) => decoded,
+                    Err(e) => return decode_error(e.to_string()),
+                };
+                let levels = book_levels(&orders);
+                let previous = match side {
+                    Side::Bid => &mut self.bids,
+                    Side::Ask => &mut self.asks,
+                };
+                let changes = diff_levels(previous, &levels);
+                *previous = levels;
+
+                (!changes.is_empty()).then_some(Ok(BookLevelUpdate {
+                    side,
+                    slot,
+                    book_seq_num: self.book_seq_num,
+                    changes,
+                }))
+            }
+        }
+    }
+}
+
+/// Subscribe to a market and its two book sides and stream the L2 level
+/// changes between consecutive book states. The first update for each side
+/// is diffed against an empty book, so it carries the whole side.
+///
+/// Pubsub may coalesce or drop notifications under load. Each update carries
+/// the market's book_seq_num as last seen; a consumer following BookDelta
+/// events that finds it has moved past deltas it never saw should call the
+/// returned unsubscribe and start over from a fresh subscription.
+pub async fn subscribe_book_levels<'a>(
+    pubsub: &'a PubsubClient,
+    market: &Pubkey,
+    bids: &Pubkey,
+    asks: &Pubkey,
+) -> PubsubClientResult<(
+    BoxStream<'a, ClientResult<BookLevelUpdate>>,
+    impl FnOnce() -> BoxFuture<'static, ()> + Send,
+)> {
+    let config = || {
+        Some(RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        })
+    };
+    let (market_updates, market_unsubscribe) = pubsub.account_subscribe(market, config()).await?;
+    let (bids_updates, bids_unsubscribe) = pubsub.account_subscribe(bids, config()).await?;
+    let (asks_updates, asks_unsubscribe) = pubsub.account_subscribe(asks, config()).await?;
+
+    let updates = stream::select_all([
+        tag_updates(market_updates, WatchedAccount::Market),
+        tag_updates(bids_updates, WatchedAccount::Book),
+        tag_updates(asks_updates, WatchedAccount::Book),
+    ])
+    .scan(BookWatch::default(), |watch, (account, slot, data)| {
+        future::ready(Some(watch.apply(account, slot, data)))
+    })
+    .filter_map(future::ready)
+    .boxed();
+
+    let unsubscribe = move || {
+        async move {
+            market_unsubscribe().await;
+            bids_unsubscribe().await;
+            asks_unsubscribe().await;
+        }
+        .boxed()
+    };
+
+    Ok((updates, unsubscribe))
+}
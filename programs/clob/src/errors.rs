@@ -1,33 +1,121 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::InstructionError;
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Math operation overflow")]
-    MathOverflow,
-    #[msg("Insufficient balance")]
-    InsufficientBalance,
-    #[msg("Invalid token mint")]
-    InvalidTokenMint,
-    #[msg("Unauthorized")]
-    Unauthorized,
-    #[msg("Same mint addresses")]
-    SameMintAddresses,
-    #[msg("Invalid amount")]
-    InvalidAmount,
-    #[msg("Invalid parameter")]
-    InvalidParameter,
-    #[msg("Order not found")]
-    OrderNotFound,
-    #[msg("Orderbook full")]
-    OrderbookFull,
-    #[msg("Invalid order size")]
-    InvalidOrderSize,
-    #[msg("Invalid price")]
-    InvalidPrice,
-    #[msg("Event queue is full")]
-    EventQueueFull,
-    #[msg("Event queue is empty")]
-    EventQueueEmpty,
-    #[msg("Fill-or-kill order not completely filled")]
-    FillOrKillNotFilled,
+// One list drives the Anchor enum and the client lookup table, so the two
+// cannot drift. Codes are the position in the list plus Anchor's 6000
+// offset: only ever append.
+macro_rules! error_codes {
+    ($($name:ident => $msg:tt,)*) => {
+        #[error_code]
+        pub enum ErrorCode {
+            $(
+                #[msg($msg)]
+                $name,
+            )*
+        }
+
+        impl ErrorCode {
+            /// Every variant, in code order
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$name),*];
+
+            /// The variant name as a static string (Anchor's `name()` allocates)
+            pub fn variant_name(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => stringify!($name),)*
+                }
+            }
+
+            /// The `#[msg]` text
+            pub fn message(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => $msg,)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    MathOverflow => "Math operation overflow",
+    InsufficientBalance => "Insufficient balance",
+    InvalidTokenMint => "Invalid token mint",
+    Unauthorized => "Unauthorized",
+    SameMintAddresses => "Same mint addresses",
+    InvalidAmount => "Invalid amount",
+    InvalidParameter => "Invalid parameter",
+    OrderNotFound => "Order not found",
+    OrderbookFull => "Orderbook full",
+    InvalidOrderSize => "Invalid order size",
+    InvalidPrice => "Invalid price",
+    EventQueueFull => "Settlement backlog, crank needed",
+    EventQueueEmpty => "Event queue is empty",
+    FillOrKillNotFilled => "Fill-or-kill order not completely filled",
+    OrderbookNotEmpty => "Orderbook is not empty",
+    EventQueueNotEmpty => "Event queue is not empty",
+    MarketClosing => "Market is closing",
+    MarketNotClosing => "Market is not closing",
+    CloseGraceActive => "Close grace period has not expired",
+    NotOrphanedAccount => "Account is not an orphaned book or queue account",
+    RateLimited => "Too many orders placed in this slot",
+    DuplicateOrderId => "An order with this id is already on the book",
+    RefundAccountsMissing => "Wallet refund requires the vault, wallet, mint and token program accounts",
+    SideHalted => "New orders on this side are halted",
+    PriceNotTickAligned => "Price is not a whole number of ticks",
+    QuantityNotLotAligned => "Quantity is not a whole number of lots",
+    MarketNotPaused => "Market must be paused on both sides",
+    SlippageExceeded => "Swap output below the minimum",
+    InvalidIntentSignature => "Missing or mismatched ed25519 signature for the intent",
+    IntentExpired => "Signed intent has expired",
+    IntentNonceUsed => "Signed intent nonce was already used",
+    CrossTradesThrough => "Cross price trades through the resting book",
+    CrossAccountMissing => "Cross is missing a buyer or seller UserBalance account",
+    MarketPaused => "Deposits or withdrawals are paused on this market",
+    InvalidEventQueueShard => "Event queue shard missing, out of order or not this market's",
+    BookOwnersFull => "Order book side has no free owner table entry",
+    BookLayoutMismatch => "Order book account uses an outdated layout",
+    BookWouldLock => "Resting this order would lock or cross the book",
+    SettlementBacklog => "Oldest event awaiting the crank is too old to match against",
+    PriceLevelFull => "Price level already holds the most resting orders allowed",
+    TwapSliceNotDue => "TWAP interval has not elapsed since the last slice",
+    TwapNotActive => "TWAP order already finished or expired",
+    SessionNotActive => "Session orders need an unexpired session",
+    SessionNotExpired => "Owner's session has not expired",
+    PostOnlyWouldCross => "Post-only order would match against the opposite book",
+    InvalidBookAccount => "Bids or asks account is not sized for a book side",
+    SelfTradeNotAllowed => "Order would match a resting order of the same owner",
+    SettlementNotDue => "Market was already settled at this timestamp",
+    DuplicateClientOrderId => "Owner already has a resting order with this client order id",
+    TooManyAccounts => "More remaining accounts than the instruction accepts",
+    EventRetriesExhausted => "Event was requeued too often without its maker account",
+}
+
+impl ErrorCode {
+    /// Map a custom program error code (6000 and up) back to its variant
+    pub fn from_code(code: u32) -> Option<Self> {
+        let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+        Self::ALL.get(index as usize).copied()
+    }
+
+    /// The variant behind an `InstructionError::Custom` returned by the runtime
+    pub fn from_instruction_error(error: &InstructionError) -> Option<Self> {
+        match error {
+            InstructionError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+
+    /// Find the variant in a program log line or error string: Anchor's
+    /// "Error Number: 6003." or the runtime's "custom program error: 0x1773"
+    pub fn from_log(line: &str) -> Option<Self> {
+        if let Some((_, rest)) = line.split_once("custom program error: 0x") {
+            let hex: String = rest.chars().take_while(char::is_ascii_hexdigit).collect();
+            return Self::from_code(u32::from_str_radix(&hex, 16).ok()?);
+        }
+        if let Some((_, rest)) = line.split_once("Error Number: ") {
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            return Self::from_code(digits.parse().ok()?);
+        }
+        None
+    }
 }
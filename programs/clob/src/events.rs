@@ -1,4 +1,6 @@
-use crate::state::orderbook::order::Side;
+use crate::errors::ErrorCode;
+use crate::state::orderbook::order::{OrderStatus, Side};
+use crate::state::TwapStatus;
 use anchor_lang::prelude::*;
 
 #[event]
@@ -10,6 +12,19 @@ pub struct OrderPlaced {
     pub price: u64,
     pub quantity: u64,
     pub timestamp: i64,
+    pub client_order_id: u64, // 0 if the caller gave none
+}
+
+#[event]
+pub struct NativeOrderConverted {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub price_native_quote_per_lot: u64, // As submitted, in quote tokens per lot
+    pub quantity_native_base: u64,       // As submitted, in base tokens
+    pub price: u64,                      // Converted, in quote_tick_size units
+    pub quantity: u64,                   // Converted, in base_lot_size units
 }
 
 #[event]
@@ -22,6 +37,80 @@ pub struct OrderFilled {
     pub maker_owner: Pubkey,
     pub taker_owner: Pubkey,
     pub taker_side: Side,
+    pub gross_quote: u64, // Fill notional in quote tokens
+    pub fee_base: u64,    // Taker fee in base tokens; bid takers pay in base
+    pub fee_quote: u64,   // Taker fee in quote tokens; ask takers pay in quote
+    pub net_quote: u64,   // gross_quote - fee_quote
+}
+
+#[event]
+pub struct SignedIntentExecuted {
+    pub owner: Pubkey, // Signer of the intent
+    pub market: Pubkey,
+    pub relayer: Pubkey, // Submitted the transaction
+    pub nonce: u64,
+    pub client_order_id: u64, // From the order intent; 0 for cancels
+    pub order_id: u64,        // Order placed or cancelled
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub order_id: u64, // Taker order id the swap's fills carry
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub amount_in: u64,       // Input the vault received
+    pub amount_spent: u64,    // Input consumed by fills; the rest was returned
+    pub amount_out: u64,      // Output paid to the caller
+    pub filled_quantity: u64, // Lots filled
+}
+
+#[event]
+pub struct MakerFillSettled {
+    pub maker_order_id: u64,
+    pub taker_order_id: u64,
+    pub market: Pubkey,
+    pub maker_owner: Pubkey,
+    pub maker_side: Side,
+    pub price: u64,
+    pub quantity: u64,
+    pub gross_quote: u64, // Fill notional in quote tokens
+    pub fee_base: u64,    // Maker fee in base tokens; bid makers pay in base
+    pub fee_quote: u64,   // Maker fee in quote tokens; ask makers pay in quote
+    pub net_quote: u64,   // gross_quote - fee_quote
+}
+
+#[event]
+pub struct EventsConsumed {
+    pub market: Pubkey,
+    pub event_queue: Pubkey, // Shard that was cranked
+    pub processed: u8,       // Events settled by this call
+    pub remaining: u64,      // Events still waiting in this shard
+}
+
+#[event]
+pub struct OrderComplete {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub filled_quantity: u64,
+    pub resting_quantity: u64,
+    pub status: OrderStatus,
+}
+
+/// Terminal event of place_market_order, after the OrderComplete of the IOC
+/// it placed (if any)
+#[event]
+pub struct MarketOrderComplete {
+    pub order_id: u64, // 0 if nothing could fill and no order was placed
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub requested_quantity: u64,
+    pub filled_quantity: u64,
+    pub quote_amount: u64, // Quote tokens paid (bid) or received (ask)
+    pub avg_price: u64,    // In ticks, rounded down
 }
 
 #[event]
@@ -33,6 +122,32 @@ pub struct OrderCancelled {
     pub remaining_quantity: u64,
 }
 
+#[event]
+pub struct OrderIncreased {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub price: u64,
+    pub added_quantity: u64,
+    pub timestamp: i64, // Queue position, unchanged by the increase
+}
+
+#[event]
+pub struct OrderModified {
+    pub old_order_id: u64,
+    pub order_id: u64, // Id the order rests under from now on
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub price: u64,
+    pub old_quantity: u64,
+    pub new_quantity: u64,
+    pub old_remaining_quantity: u64,
+    pub new_remaining_quantity: u64,
+    pub timestamp: i64, // New queue position
+}
+
 #[event]
 pub struct MarketInitialized {
     pub market: Pubkey,
@@ -46,6 +161,13 @@ pub struct MarketInitialized {
     pub event_queue: Pubkey,
 }
 
+#[event]
+pub struct UserBalanceCreated {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub payer: Pubkey, // Paid the rent; differs from user for deposits on their behalf
+}
+
 #[event]
 pub struct UserDeposit {
     pub user: Pubkey,
@@ -63,3 +185,230 @@ pub struct UserWithdraw {
     pub amount: u64,
     pub new_balance: u64,
 }
+
+#[event]
+pub struct MarketClosing {
+    pub market: Pubkey,
+    pub close_grace_until: i64,
+}
+
+#[event]
+pub struct MarketFlagsUpdated {
+    pub market: Pubkey,
+    pub previous_flags: u8,
+    pub flags: u8,
+}
+
+#[event]
+pub struct SettlementTick {
+    pub market: Pubkey,
+    pub settled_by: Pubkey,
+    pub mark_price: u64,             // last_trade_price at settlement, in ticks
+    pub previous_settlement_ts: i64, // 0 on the first settlement
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventQueueShardAdded {
+    pub market: Pubkey,
+    pub event_queue: Pubkey,
+    pub shard: u8,
+    pub shard_count: u8,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub market: Pubkey,
+    pub deposits_paused: bool,
+    pub withdrawals_paused: bool,
+}
+
+#[event]
+pub struct CrossAuthorityUpdated {
+    pub market: Pubkey,
+    pub cross_authority: Pubkey, // Default = crossing disabled
+    pub max_cross_trade_through: u64,
+}
+
+#[event]
+pub struct MarketParamsUpdated {
+    pub market: Pubkey,
+    pub base_lot_size: u64,
+    pub quote_tick_size: u64,
+    pub prev_base_lot_size: u64,
+    pub prev_quote_tick_size: u64,
+    pub order_bond_lamports: u64,
+    pub prev_order_bond_lamports: u64,
+}
+
+#[event]
+pub struct BookCleared {
+    pub market: Pubkey,
+    pub cancelled: u8,       // Orders removed by this call
+    pub bids_remaining: u64, // Call again until both are 0
+    pub asks_remaining: u64,
+}
+
+#[event]
+pub struct MarketClosed {
+    pub market: Pubkey,
+    pub rent_payer: Pubkey,
+    pub lamports_refunded: u64,
+}
+
+#[event]
+pub struct WithheldFeesHarvested {
+    pub market: Pubkey,
+    pub base_harvested: u64,
+    pub quote_harvested: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,    // Fee tokens paid to the authority
+    pub remaining: u64, // Fees still accrued in this mint
+}
+
+#[event]
+pub struct OrphanedAccountReclaimed {
+    pub account: Pubkey,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct UserBalanceMigrated {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub previous_size: u32, // Account data length before the migration
+    pub schema_version: u8,
+    pub open_order_count: u32, // Recomputed from the books
+}
+
+#[event]
+pub struct TwapOrderPlaced {
+    pub twap: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: Side,
+    pub limit_price: u64,
+    pub total_quantity: u64,
+    pub slice_quantity: u64,
+    pub interval_secs: u32,
+    pub expiry: i64,
+}
+
+#[event]
+pub struct TwapSliceExecuted {
+    pub twap: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub executor: Pubkey,     // Sent the slice and took the reward
+    pub order_id: u64,        // IOC order the slice placed
+    pub slice_quantity: u64,  // Lots the slice offered
+    pub filled_quantity: u64, // Lots of it that filled
+    pub total_filled: u64,    // Lots filled over the whole schedule
+    pub reward_lamports: u64, // Paid to the executor
+}
+
+#[event]
+pub struct TwapStatusChanged {
+    pub twap: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub status: TwapStatus,
+    pub total_filled: u64,
+}
+
+#[event]
+pub struct TwapOrderClosed {
+    pub twap: Pubkey,
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub total_filled: u64,
+}
+
+#[event]
+pub struct SessionRegistered {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub expiry_ts: i64, // Session orders become cancellable by anyone after this
+}
+
+/// Most levels a single BookDelta reports; beyond that it is marked truncated
+pub const MAX_BOOK_DELTA_LEVELS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LevelChange {
+    pub side: Side,
+    pub price: u64,
+    pub new_total_quantity: u64, // Remaining quantity at this level after the change; 0 = level gone
+}
+
+#[event]
+pub struct BookDelta {
+    pub market: Pubkey,
+    pub seq_num: u64, // Market.book_seq_num after this change; a gap means refetch the books
+    pub changes: Vec<LevelChange>,
+    pub truncated: bool,     // More levels changed than fit; refetch the books
+    pub bids_hash: [u8; 32], // Bid side book_hash after this change
+    pub asks_hash: [u8; 32], // Ask side book_hash after this change
+}
+
+impl BookDelta {
+    pub fn new(
+        market: Pubkey,
+        seq_num: u64,
+        mut changes: Vec<LevelChange>,
+        bids_hash: [u8; 32],
+        asks_hash: [u8; 32],
+    ) -> Self {
+        let truncated = changes.len() > MAX_BOOK_DELTA_LEVELS;
+        changes.truncate(MAX_BOOK_DELTA_LEVELS);
+        Self {
+            market,
+            seq_num,
+            changes,
+            truncated,
+            bids_hash,
+            asks_hash,
+        }
+    }
+}
+
+/// `BalanceChanged::reason` values
+pub const BALANCE_REASON_DEPOSIT: u8 = 0;
+pub const BALANCE_REASON_WITHDRAW: u8 = 1;
+pub const BALANCE_REASON_TAKER_FILL: u8 = 2;
+pub const BALANCE_REASON_MAKER_FILL: u8 = 3;
+pub const BALANCE_REASON_ORDER_RESERVE: u8 = 4;
+pub const BALANCE_REASON_ORDER_REFUND: u8 = 5;
+pub const BALANCE_REASON_CROSS: u8 = 6;
+pub const BALANCE_REASON_SETTLEMENT_CREDIT: u8 = 7;
+
+/// Emitted alongside the specific event whenever a UserBalance moves, so
+/// summing the deltas per user reproduces the balance
+#[event]
+pub struct BalanceChanged {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub base_delta: i64,
+    pub quote_delta: i64,
+    pub reason: u8,  // BALANCE_REASON_*
+    pub ref_id: u64, // Order id for order-related changes, 0 for deposits and withdrawals
+}
+
+impl BalanceChanged {
+    /// `amount` as a positive delta
+    pub fn credit(amount: u64) -> Result<i64> {
+        i64::try_from(amount).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// `amount` as a negative delta
+    pub fn debit(amount: u64) -> Result<i64> {
+        Ok(-Self::credit(amount)?)
+    }
+}
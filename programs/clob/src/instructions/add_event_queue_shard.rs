@@ -0,0 +1,81 @@
+use crate::errors::ErrorCode;
+use crate::events::EventQueueShardAdded;
+use crate::state::{EventQueue, Market, MAX_EVENT_QUEUE_SHARDS};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddEventQueueShard<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(zero)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+}
+
+impl AddEventQueueShard<'_> {
+    // place_limit_order spreads maker fills over the shards by maker owner so
+    // crankers can drain them in parallel; every other instruction keeps
+    // queueing on the market's event_queue. Any shard may hold any maker's
+    // events, so remapping owners when a shard is added is harmless.
+    pub fn apply(ctx: Context<AddEventQueueShard>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.is_closing(), ErrorCode::MarketClosing);
+        let shard = market.event_queue_shard_count as usize;
+        require!(shard < MAX_EVENT_QUEUE_SHARDS, ErrorCode::InvalidParameter);
+
+        ctx.accounts.event_queue.load_init()?.init();
+
+        market.event_queue_shards[shard - 1] = ctx.accounts.event_queue.key();
+        market.event_queue_shard_count += 1;
+
+        emit!(EventQueueShardAdded {
+            market: market.key(),
+            event_queue: ctx.accounts.event_queue.key(),
+            shard: shard as u8,
+            shard_count: market.event_queue_shard_count,
+        });
+
+        msg!(
+            "Added event queue shard {} of {}",
+            shard,
+            market.event_queue_shard_count
+        );
+
+        Ok(())
+    }
+
+    /// Every event queue of `market` in shard order: `event_queue`, then the
+    /// other shards, which callers pass as the leading remaining accounts
+    pub(crate) fn load_shards<'info>(
+        market: &Market,
+        event_queue: &AccountLoader<'info, EventQueue>,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<AccountLoader<'info, EventQueue>>> {
+        let extra = market.event_queue_shard_count.saturating_sub(1) as usize;
+        require!(
+            remaining_accounts.len() >= extra,
+            ErrorCode::InvalidEventQueueShard
+        );
+
+        let mut shards = Vec::with_capacity(extra + 1);
+        shards.push(event_queue.clone());
+        for (account_info, key) in remaining_accounts
+            .iter()
+            .zip(&market.event_queue_shards[..extra])
+        {
+            require!(
+                account_info.key == key && account_info.is_writable,
+                ErrorCode::InvalidEventQueueShard
+            );
+            shards.push(AccountLoader::try_from(account_info)?);
+        }
+        Ok(shards)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::errors::ErrorCode;
+use crate::events::MarketClosing;
+use crate::state::Market;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct BeginMarketClose<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BeginMarketCloseParams {
+    pub grace_period: i64, // Seconds users have to cancel before force_cancel_all
+}
+
+impl BeginMarketClose<'_> {
+    // Stops new orders immediately; resting orders stay cancellable by their
+    // owners until the grace window ends, after which the authority may sweep them
+    pub fn apply(ctx: Context<BeginMarketClose>, params: BeginMarketCloseParams) -> Result<()> {
+        require!(params.grace_period > 0, ErrorCode::InvalidParameter);
+
+        let market = &mut ctx.accounts.market;
+        require!(!market.is_closing(), ErrorCode::MarketClosing);
+
+        market.close_grace_until = Clock::get()?
+            .unix_timestamp
+            .checked_add(params.grace_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(MarketClosing {
+            market: market.key(),
+            close_grace_until: market.close_grace_until,
+        });
+
+        msg!(
+            "Market closing, force cancel allowed from {}",
+            market.close_grace_until
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::errors::ErrorCode;
+use crate::instructions::CancelOrder;
+use crate::state::{AskSide, BidSide, Market, Side, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAllMyOrders<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+    pub user: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelAllMyOrdersParams {
+    pub limit: u8, // Maximum number of orders to cancel in this call
+}
+
+impl CancelAllMyOrders<'_> {
+    /// Cancel up to `limit` of the signer's resting orders, bids then asks,
+    /// refunding each to their balance as cancel_order does. Returns how
+    /// many are still resting, so the caller can repeat until it is 0.
+    pub fn apply(ctx: Context<CancelAllMyOrders>, params: CancelAllMyOrdersParams) -> Result<u32> {
+        let owner = ctx.accounts.user.key();
+        let bid_ids = ctx.accounts.bids.load()?.orderbook.order_ids_of(&owner);
+        let ask_ids = ctx.accounts.asks.load()?.orderbook.order_ids_of(&owner);
+        let total = bid_ids.len() + ask_ids.len();
+
+        let orders = bid_ids
+            .into_iter()
+            .map(|order_id| (order_id, Side::Bid))
+            .chain(ask_ids.into_iter().map(|order_id| (order_id, Side::Ask)))
+            .take(params.limit as usize);
+        let mut cancelled = 0;
+        for (order_id, side) in orders {
+            let (order, refund, book_delta) = CancelOrder::remove(
+                &mut ctx.accounts.market,
+                &ctx.accounts.bids,
+                &ctx.accounts.asks,
+                owner,
+                order_id,
+                side,
+            )?;
+            CancelOrder::close_order(&ctx.accounts.market, &mut ctx.accounts.user_balance)?;
+            let balance_deltas =
+                CancelOrder::refund_to_balance(&mut ctx.accounts.user_balance, side, refund)?;
+            CancelOrder::emit_cancelled(owner, &order, side, book_delta, Some(balance_deltas));
+            cancelled += 1;
+        }
+
+        let remaining = (total - cancelled) as u32;
+        msg!("Cancelled {} orders, {} remaining", cancelled, remaining);
+        Ok(remaining)
+    }
+}
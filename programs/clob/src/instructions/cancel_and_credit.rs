@@ -0,0 +1,107 @@
+use crate::errors::ErrorCode;
+use crate::events::{BalanceChanged, OrderCancelled, BALANCE_REASON_SETTLEMENT_CREDIT};
+use crate::instructions::CancelOrder;
+use crate::state::{AskSide, BidSide, Market, Side, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: CancelAndCreditParams)]
+pub struct CancelAndCredit<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+
+    // The order owner's balance: its open order count drops and the order's
+    // bond is returned there. Anchor writes
+    // accounts back in declaration order, so when the recipient is the owner
+    // the recipient's copy is the one that lands; apply updates that copy.
+    #[account(
+        mut,
+        seeds = [b"user_balance", params.owner.as_ref(), market.key().as_ref()],
+        bump = owner_balance.bump,
+    )]
+    pub owner_balance: Account<'info, UserBalance>,
+
+    // Receives the refund in place of the order owner
+    #[account(
+        mut,
+        seeds = [b"user_balance", recipient_balance.owner.as_ref(), market.key().as_ref()],
+        bump = recipient_balance.bump,
+    )]
+    pub recipient_balance: Account<'info, UserBalance>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelAndCreditParams {
+    pub owner: Pubkey, // Owner of the order to cancel
+    pub order_id: u64,
+    pub side: Side,
+}
+
+impl CancelAndCredit<'_> {
+    // Controlled settlement (OTC, liquidation): cancels someone else's
+    // resting order and credits its reservation to `recipient_balance`
+    // instead of the owner. Authority-only, and only while the market is
+    // paused so the book cannot move underneath the settlement.
+    pub fn apply(ctx: Context<CancelAndCredit>, params: CancelAndCreditParams) -> Result<()> {
+        require!(ctx.accounts.market.is_paused(), ErrorCode::MarketNotPaused);
+
+        let (order, refund, book_delta) = CancelOrder::remove(
+            &mut ctx.accounts.market,
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            params.owner,
+            params.order_id,
+            params.side,
+        )?;
+        let owner_balance =
+            if ctx.accounts.owner_balance.key() == ctx.accounts.recipient_balance.key() {
+                &mut ctx.accounts.recipient_balance
+            } else {
+                &mut ctx.accounts.owner_balance
+            };
+        CancelOrder::close_order(&ctx.accounts.market, owner_balance)?;
+        let recipient_balance = &mut ctx.accounts.recipient_balance;
+        let (base_delta, quote_delta) =
+            CancelOrder::refund_to_balance(recipient_balance, params.side, refund)?;
+
+        emit!(OrderCancelled {
+            order_id: order.order_id,
+            owner: params.owner,
+            market: book_delta.market,
+            side: params.side,
+            remaining_quantity: order.remaining_quantity,
+        });
+        emit!(BalanceChanged {
+            user: recipient_balance.owner,
+            market: book_delta.market,
+            base_delta,
+            quote_delta,
+            reason: BALANCE_REASON_SETTLEMENT_CREDIT,
+            ref_id: order.order_id,
+        });
+        emit!(book_delta);
+
+        msg!(
+            "Order {} of {} cancelled, {} credited to {}",
+            order.order_id,
+            params.owner,
+            refund,
+            recipient_balance.owner
+        );
+        Ok(())
+    }
+}
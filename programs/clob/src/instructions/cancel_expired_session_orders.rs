@@ -0,0 +1,108 @@
+use crate::errors::ErrorCode;
+use crate::instructions::CancelOrder;
+use crate::state::{AskSide, BidSide, Market, Side, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: CancelExpiredSessionOrdersParams)]
+pub struct CancelExpiredSessionOrders<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", params.owner.as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+
+    // Anyone; takes the cancelled orders' bonds as a bounty
+    #[account(mut)]
+    pub caller: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelExpiredSessionOrdersParams {
+    pub owner: Pubkey, // Whose lapsed session to sweep
+    pub limit: u8,     // Maximum number of orders to cancel in this call
+}
+
+impl CancelExpiredSessionOrders<'_> {
+    /// Once `owner`'s session has lapsed, cancel up to `limit` of their
+    /// session orders, bids then asks, refunding each to their balance. The
+    /// orders' bonds go to the caller instead of the owner, paying for the
+    /// sweep. Returns how many session orders are still resting.
+    pub fn apply(
+        ctx: Context<CancelExpiredSessionOrders>,
+        params: CancelExpiredSessionOrdersParams,
+    ) -> Result<u32> {
+        let user_balance = &ctx.accounts.user_balance;
+        require!(
+            user_balance.session_expiry != 0
+                && !user_balance.session_active(Clock::get()?.unix_timestamp),
+            ErrorCode::SessionNotExpired
+        );
+
+        let owner = params.owner;
+        let bid_ids = ctx
+            .accounts
+            .bids
+            .load()?
+            .orderbook
+            .session_order_ids_of(&owner);
+        let ask_ids = ctx
+            .accounts
+            .asks
+            .load()?
+            .orderbook
+            .session_order_ids_of(&owner);
+        let total = bid_ids.len() + ask_ids.len();
+
+        let orders = bid_ids
+            .into_iter()
+            .map(|order_id| (order_id, Side::Bid))
+            .chain(ask_ids.into_iter().map(|order_id| (order_id, Side::Ask)))
+            .take(params.limit as usize);
+        let mut cancelled = 0;
+        for (order_id, side) in orders {
+            let (order, refund, book_delta) = CancelOrder::remove(
+                &mut ctx.accounts.market,
+                &ctx.accounts.bids,
+                &ctx.accounts.asks,
+                owner,
+                order_id,
+                side,
+            )?;
+            let market = &ctx.accounts.market;
+            ctx.accounts.user_balance.record_order_closed();
+            Market::pay_order_bond(
+                &market.to_account_info(),
+                &ctx.accounts.caller.to_account_info(),
+                market.order_bond_lamports,
+            )?;
+            let balance_deltas =
+                CancelOrder::refund_to_balance(&mut ctx.accounts.user_balance, side, refund)?;
+            CancelOrder::emit_cancelled(owner, &order, side, book_delta, Some(balance_deltas));
+            cancelled += 1;
+        }
+
+        let remaining = (total - cancelled) as u32;
+        msg!(
+            "Cancelled {} expired session orders, {} remaining",
+            cancelled,
+            remaining
+        );
+        Ok(remaining)
+    }
+}
@@ -1,12 +1,16 @@
 use crate::errors::ErrorCode;
-use crate::events::OrderCancelled;
-use crate::state::{AskSide, BidSide, Market, OrderBook, Side, UserBalance};
+use crate::events::{
+    BalanceChanged, BookDelta, LevelChange, OrderCancelled, BALANCE_REASON_ORDER_REFUND,
+};
+use crate::state::{AskSide, BidSide, Market, Order, OrderBook, Side, UserBalance};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
+// Shared by cancel_order and cancel_order_by_client_id
 #[derive(Accounts)]
-#[instruction(params: CancelOrderParams)]
 pub struct CancelOrder<'info> {
     #[account(
+        mut,
         seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
         bump = market.bump,
         has_one = bids,
@@ -27,82 +31,233 @@ pub struct CancelOrder<'info> {
     )]
     pub user_balance: Account<'info, UserBalance>,
     pub user: Signer<'info>,
+
+    // Only needed with refund_to_wallet: the vault and mint of the reserved
+    // asset (quote for bids, base for asks) and the wallet to pay out to
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub wallet: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct CancelOrderParams {
     pub order_id: u64,
-    pub side: Side, // Specify which orderbook to search
+    pub side: Side,             // Specify which orderbook to search
+    pub refund_to_wallet: bool, // Pay the reservation out to `wallet` instead of UserBalance
 }
 
 impl CancelOrder<'_> {
     pub fn apply(ctx: Context<CancelOrder>, params: CancelOrderParams) -> Result<()> {
-        let market = &ctx.accounts.market;
-        let user_balance = &mut ctx.accounts.user_balance;
-        let mut bids = ctx.accounts.bids.load_mut()?;
-        let mut asks = ctx.accounts.asks.load_mut()?;
-
-        // Try to remove order from the specified orderbook
-        let removed_order = match params.side {
-            Side::Bid => bids.orderbook.remove_order(params.order_id)?,
-            Side::Ask => asks.orderbook.remove_order(params.order_id)?,
+        // Paying the refund out of the vault is a withdrawal
+        require!(
+            !(params.refund_to_wallet && ctx.accounts.market.withdrawals_paused),
+            ErrorCode::MarketPaused
+        );
+
+        let owner = ctx.accounts.user.key();
+        let (order, refund, book_delta) = Self::remove(
+            &mut ctx.accounts.market,
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            owner,
+            params.order_id,
+            params.side,
+        )?;
+        Self::close_order(&ctx.accounts.market, &mut ctx.accounts.user_balance)?;
+
+        // A wallet refund never touches UserBalance, so it reports no delta
+        let balance_deltas = if params.refund_to_wallet {
+            Self::refund_to_wallet(ctx.accounts, params.side, refund)?;
+            None
+        } else {
+            Some(Self::refund_to_balance(
+                &mut ctx.accounts.user_balance,
+                params.side,
+                refund,
+            )?)
         };
 
-        let order = removed_order.ok_or(ErrorCode::OrderNotFound)?;
+        Self::emit_cancelled(owner, &order, params.side, book_delta, balance_deltas);
+        Ok(())
+    }
+
+    /// Take `owner`'s order off the book. Returns the order, the reservation
+    /// to refund and the BookDelta to emit.
+    pub(crate) fn remove<'info>(
+        market: &mut Account<'info, Market>,
+        bids: &AccountLoader<'info, BidSide>,
+        asks: &AccountLoader<'info, AskSide>,
+        owner: Pubkey,
+        order_id: u64,
+        side: Side,
+    ) -> Result<(Order, u64, BookDelta)> {
+        let mut bids = bids.load_mut()?;
+        let mut asks = asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
 
-        // Verify the order belongs to the user
+        // Check ownership before touching the book, so a failed cancel never
+        // leaves it mutated
+        let found = match side {
+            Side::Bid => bids.orderbook.find_order_by_id(order_id),
+            Side::Ask => asks.orderbook.find_order_by_id(order_id),
+        };
         require!(
-            order.owner == ctx.accounts.user.key(),
+            found.ok_or(ErrorCode::OrderNotFound)?.owner == owner,
             ErrorCode::Unauthorized
         );
 
-        // Return reserved funds to user balance
-        match params.side {
+        let removed_order = match side {
+            Side::Bid => bids.orderbook.remove_order(order_id)?,
+            Side::Ask => asks.orderbook.remove_order(order_id)?,
+        };
+        let order = removed_order.ok_or(ErrorCode::OrderNotFound)?;
+        let refund = market.reserved_amount(side, &order)?;
+
+        let new_total_quantity = match side {
+            Side::Bid => bids.orderbook.level_quantity(order.price),
+            Side::Ask => asks.orderbook.level_quantity(order.price),
+        };
+        let book_delta = BookDelta::new(
+            market.key(),
+            market.next_book_seq_num()?,
+            vec![LevelChange {
+                side,
+                price: order.price,
+                new_total_quantity,
+            }],
+            bids.orderbook.book_hash(),
+            asks.orderbook.book_hash(),
+        );
+
+        Ok((order, refund, book_delta))
+    }
+
+    /// Count an order just removed as closed on its owner's balance and pay
+    /// its bond there
+    pub(crate) fn close_order(
+        market: &Account<Market>,
+        owner_balance: &mut Account<UserBalance>,
+    ) -> Result<()> {
+        owner_balance.record_order_closed();
+        Market::pay_order_bond(
+            &market.to_account_info(),
+            &owner_balance.to_account_info(),
+            market.order_bond_lamports,
+        )
+    }
+
+    /// Return reserved funds to the user's balance; returns the (base, quote)
+    /// deltas applied
+    pub(crate) fn refund_to_balance(
+        user_balance: &mut UserBalance,
+        side: Side,
+        refund: u64,
+    ) -> Result<(i64, i64)> {
+        match side {
             Side::Bid => {
                 // Return reserved quote tokens
-                let reserved_quote = order
-                    .price
-                    .checked_mul(order.remaining_quantity)
-                    .ok_or(ErrorCode::MathOverflow)?
-                    .checked_mul(market.quote_tick_size)
-                    .ok_or(ErrorCode::MathOverflow)?
-                    .checked_div(market.base_lot_size)
-                    .ok_or(ErrorCode::MathOverflow)?;
-
                 user_balance.quote_balance = user_balance
                     .quote_balance
-                    .checked_add(reserved_quote)
+                    .checked_add(refund)
                     .ok_or(ErrorCode::MathOverflow)?;
+                Ok((0, BalanceChanged::credit(refund)?))
             }
             Side::Ask => {
                 // Return reserved base tokens
-                let reserved_base = order
-                    .remaining_quantity
-                    .checked_mul(market.base_lot_size)
-                    .ok_or(ErrorCode::MathOverflow)?;
-
                 user_balance.base_balance = user_balance
                     .base_balance
-                    .checked_add(reserved_base)
+                    .checked_add(refund)
                     .ok_or(ErrorCode::MathOverflow)?;
+                Ok((BalanceChanged::credit(refund)?, 0))
             }
         }
+    }
 
-        // Emit order cancelled event
+    pub(crate) fn emit_cancelled(
+        owner: Pubkey,
+        order: &Order,
+        side: Side,
+        book_delta: BookDelta,
+        balance_deltas: Option<(i64, i64)>,
+    ) {
         emit!(OrderCancelled {
             order_id: order.order_id,
-            owner: ctx.accounts.user.key(),
-            market: market.key(),
-            side: params.side,
+            owner,
+            market: book_delta.market,
+            side,
             remaining_quantity: order.remaining_quantity,
         });
+        if let Some((base_delta, quote_delta)) = balance_deltas {
+            emit!(BalanceChanged {
+                user: owner,
+                market: book_delta.market,
+                base_delta,
+                quote_delta,
+                reason: BALANCE_REASON_ORDER_REFUND,
+                ref_id: order.order_id,
+            });
+        }
+        emit!(book_delta);
 
         msg!(
             "Order cancelled: id={}, remaining_quantity={}",
             order.order_id,
             order.remaining_quantity
         );
+    }
 
-        Ok(())
+    fn refund_to_wallet(accounts: &CancelOrder, side: Side, amount: u64) -> Result<()> {
+        let (Some(vault), Some(wallet), Some(mint), Some(token_program)) = (
+            &accounts.vault,
+            &accounts.wallet,
+            &accounts.mint,
+            &accounts.token_program,
+        ) else {
+            return err!(ErrorCode::RefundAccountsMissing);
+        };
+
+        let market = &accounts.market;
+        let (expected_mint, expected_vault) = match side {
+            Side::Bid => (market.quote_mint, market.quote_vault),
+            Side::Ask => (market.base_mint, market.base_vault),
+        };
+        require_keys_eq!(mint.key(), expected_mint, ErrorCode::InvalidTokenMint);
+        require_keys_eq!(vault.key(), expected_vault, ErrorCode::InvalidTokenMint);
+        require_keys_eq!(wallet.mint, expected_mint, ErrorCode::InvalidTokenMint);
+        require_keys_eq!(
+            *mint.to_account_info().owner,
+            token_program.key(),
+            ErrorCode::InvalidTokenMint
+        );
+
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let seeds: &[&[u8]] = &[
+            b"market".as_ref(),
+            market.base_mint.as_ref(),
+            market.quote_mint.as_ref(),
+            &[market.bump],
+        ];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    to: wallet.to_account_info(),
+                    authority: market.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+            mint.decimals,
+        )
     }
 }
@@ -0,0 +1,49 @@
+use crate::errors::ErrorCode;
+use crate::instructions::{CancelOrder, CancelOrderParams};
+use crate::state::Side;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelOrderByClientIdParams {
+    pub client_order_id: u64, // As given at placement; never 0
+    pub side: Side,
+    pub refund_to_wallet: bool, // As in cancel_order
+}
+
+impl CancelOrder<'_> {
+    /// Cancel the signer's resting order tagged `client_order_id`, for
+    /// callers that have not seen the exchange id yet. Only the signer's own
+    /// orders are searched, so users picking the same client id never
+    /// collide.
+    pub fn apply_by_client_id(
+        ctx: Context<CancelOrder>,
+        params: CancelOrderByClientIdParams,
+    ) -> Result<()> {
+        require!(params.client_order_id != 0, ErrorCode::InvalidParameter);
+        let owner = ctx.accounts.user.key();
+        let order_id = match params.side {
+            Side::Bid => ctx
+                .accounts
+                .bids
+                .load()?
+                .orderbook
+                .order_id_for_client_id(&owner, params.client_order_id),
+            Side::Ask => ctx
+                .accounts
+                .asks
+                .load()?
+                .orderbook
+                .order_id_for_client_id(&owner, params.client_order_id),
+        }
+        .ok_or(ErrorCode::OrderNotFound)?;
+
+        Self::apply(
+            ctx,
+            CancelOrderParams {
+                order_id,
+                side: params.side,
+                refund_to_wallet: params.refund_to_wallet,
+            },
+        )
+    }
+}
@@ -0,0 +1,91 @@
+use crate::errors::ErrorCode;
+use crate::events::SignedIntentExecuted;
+use crate::instructions::CancelOrder;
+use crate::state::{verify_ed25519_signature, AskSide, BidSide, CancelIntent, Market, UserBalance};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+
+#[derive(Accounts)]
+pub struct CancelSignedOrder<'info> {
+    // Submits and pays for the transaction; gains no authority over the order
+    pub relayer: Signer<'info>,
+
+    /// CHECK: the trader; authorised by the ed25519 signature over the intent
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", owner.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+
+    /// CHECK: the instructions sysvar, to find the ed25519 instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelSignedOrderParams {
+    pub intent: CancelIntent, // Signed by owner in the preceding ed25519 instruction
+}
+
+impl CancelSignedOrder<'_> {
+    // place_signed_order's counterpart: same signature, expiry and nonce
+    // rules, then cancel_order's path. The refund always goes to the
+    // owner's balance; a relayer never picks the destination wallet.
+    pub fn apply(ctx: Context<CancelSignedOrder>, params: CancelSignedOrderParams) -> Result<()> {
+        let intent = params.intent;
+        let owner = ctx.accounts.owner.key();
+        require_keys_eq!(
+            intent.market,
+            ctx.accounts.market.key(),
+            ErrorCode::InvalidParameter
+        );
+        require!(
+            Clock::get()?.unix_timestamp <= intent.expiry,
+            ErrorCode::IntentExpired
+        );
+        verify_ed25519_signature(&ctx.accounts.instructions, &owner, &intent.message())?;
+        ctx.accounts.user_balance.use_intent_nonce(intent.nonce)?;
+
+        let (order, refund, book_delta) = CancelOrder::remove(
+            &mut ctx.accounts.market,
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            owner,
+            intent.order_id,
+            intent.side,
+        )?;
+        CancelOrder::close_order(&ctx.accounts.market, &mut ctx.accounts.user_balance)?;
+        let balance_deltas =
+            CancelOrder::refund_to_balance(&mut ctx.accounts.user_balance, intent.side, refund)?;
+
+        CancelOrder::emit_cancelled(owner, &order, intent.side, book_delta, Some(balance_deltas));
+        emit!(SignedIntentExecuted {
+            owner,
+            market: ctx.accounts.market.key(),
+            relayer: ctx.accounts.relayer.key(),
+            nonce: intent.nonce,
+            client_order_id: 0,
+            order_id: order.order_id,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::errors::ErrorCode;
+use crate::events::{BookCleared, OrderCancelled};
+use crate::state::{AskSide, BidSide, EventQueue, FillEvent, Market, Side};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClearBook<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClearBookParams {
+    pub limit: u8, // Maximum number of orders to cancel
+}
+
+impl ClearBook<'_> {
+    // Emergency recovery: removes resting orders best-first (bids, then asks)
+    // while the market is paused. Nothing is refunded here; each removal
+    // queues a refund event that consume_events settles like a fill, so no
+    // owner accounts are needed. Stops early when the queue is full.
+    pub fn apply(ctx: Context<ClearBook>, params: ClearBookParams) -> Result<()> {
+        let market = &ctx.accounts.market;
+        require!(market.is_paused(), ErrorCode::MarketNotPaused);
+
+        let mut bids = ctx.accounts.bids.load_mut()?;
+        let mut asks = ctx.accounts.asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+        let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        let mut cancelled = 0;
+
+        for side in [Side::Bid, Side::Ask] {
+            while cancelled < params.limit && event_queue.free_slots() > 0 {
+                let order = match side {
+                    Side::Bid => bids.orderbook.pop(),
+                    Side::Ask => asks.orderbook.pop(),
+                };
+                let Some(order) = order else {
+                    break;
+                };
+
+                event_queue.push_event(FillEvent::refund(
+                    &order,
+                    side,
+                    market.reserved_amount(side, &order)?,
+                    market.order_bond_lamports,
+                    market.key(),
+                    timestamp,
+                ))?;
+                cancelled += 1;
+
+                emit!(OrderCancelled {
+                    order_id: order.order_id,
+                    owner: order.owner,
+                    market: market.key(),
+                    side,
+                    remaining_quantity: order.remaining_quantity,
+                });
+            }
+        }
+
+        let bids_remaining = bids.orderbook.len() as u64;
+        let asks_remaining = asks.orderbook.len() as u64;
+        emit!(BookCleared {
+            market: market.key(),
+            cancelled,
+            bids_remaining,
+            asks_remaining,
+        });
+
+        msg!(
+            "Cleared {} orders, {} bids and {} asks remaining",
+            cancelled,
+            bids_remaining,
+            asks_remaining
+        );
+        Ok(())
+    }
+}
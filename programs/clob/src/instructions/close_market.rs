@@ -0,0 +1,89 @@
+use crate::errors::ErrorCode;
+use crate::events::MarketClosed;
+use crate::instructions::AddEventQueueShard;
+use crate::state::{AskSide, BidSide, EventQueue, Market};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = rent_payer,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut, close = rent_payer)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut, close = rent_payer)]
+    pub asks: AccountLoader<'info, AskSide>,
+    #[account(mut, close = rent_payer)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// CHECK: Rent refund destination, checked against `market.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+impl CloseMarket<'_> {
+    // Closes the large book and queue accounts, refunding their rent to whoever
+    // funded them. The market and vaults stay alive so users can still withdraw.
+    // A sharded market's other event queues come as remaining accounts and
+    // are closed the same way.
+    pub fn apply<'info>(ctx: Context<'_, '_, 'info, 'info, CloseMarket<'info>>) -> Result<()> {
+        let event_queues = AddEventQueueShard::load_shards(
+            &ctx.accounts.market,
+            &ctx.accounts.event_queue,
+            ctx.remaining_accounts,
+        )?;
+        {
+            let bids = ctx.accounts.bids.load()?;
+            let asks = ctx.accounts.asks.load()?;
+            require!(
+                bids.orderbook.is_empty() && asks.orderbook.is_empty(),
+                ErrorCode::OrderbookNotEmpty
+            );
+
+            for event_queue in event_queues.iter() {
+                require!(
+                    event_queue.load()?.is_empty(),
+                    ErrorCode::EventQueueNotEmpty
+                );
+            }
+        }
+
+        let lamports_refunded = [
+            ctx.accounts.bids.to_account_info(),
+            ctx.accounts.asks.to_account_info(),
+        ]
+        .iter()
+        .chain(event_queues.iter().map(|queue| queue.as_ref()))
+        .try_fold(0u64, |acc, account| acc.checked_add(account.lamports()))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+        // event_queue itself is closed by its constraint
+        for shard in event_queues.iter().skip(1) {
+            shard.close(ctx.accounts.rent_payer.to_account_info())?;
+        }
+
+        emit!(MarketClosed {
+            market: ctx.accounts.market.key(),
+            rent_payer: ctx.accounts.rent_payer.key(),
+            lamports_refunded,
+        });
+
+        msg!(
+            "Market closed, refunded {} lamports to {}",
+            lamports_refunded,
+            ctx.accounts.rent_payer.key()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::events::TwapOrderClosed;
+use crate::state::TwapOrder;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseTwapOrder<'info> {
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [
+            b"twap",
+            twap_order.market.as_ref(),
+            owner.key().as_ref(),
+            &twap_order.twap_id.to_le_bytes(),
+        ],
+        bump = twap_order.bump,
+    )]
+    pub twap_order: Account<'info, TwapOrder>,
+
+    // Gets back the rent and any unspent reward escrow
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+impl CloseTwapOrder<'_> {
+    /// Cancel the schedule, or clean up one that finished or expired. Fills
+    /// so far stay; no further slices can run.
+    pub fn apply(ctx: Context<CloseTwapOrder>) -> Result<()> {
+        let twap = &ctx.accounts.twap_order;
+
+        emit!(TwapOrderClosed {
+            twap: twap.key(),
+            owner: twap.owner,
+            market: twap.market,
+            total_filled: twap.filled_quantity,
+        });
+
+        Ok(())
+    }
+}
@@ -1,20 +1,42 @@
 use crate::errors::ErrorCode;
-use crate::state::{EventQueue, FillEvent, Market, UserBalance};
+use crate::events::{
+    BalanceChanged, EventsConsumed, MakerFillSettled, BALANCE_REASON_MAKER_FILL,
+    BALANCE_REASON_ORDER_REFUND,
+};
+use crate::instructions::ForceCancelAll;
+use crate::state::{
+    EventQueue, FillEvent, Market, Side, UserBalance, FILL_EVENT_KIND_CROSS, FILL_EVENT_KIND_REFUND,
+};
 use anchor_lang::prelude::*;
 
+/// Most maker accounts one crank may pass. Each distinct maker is looked up
+/// once and its index cached, so the work stays linear in events plus
+/// makers times this bound; past it a caller could pad the transaction to
+/// exhaust the compute budget.
+pub const MAX_CONSUME_EVENTS_ACCOUNTS: usize = 32;
+
+/// Times an event whose maker account is missing may be moved to the tail.
+/// Past it the crank fails with EventRetriesExhausted, so the event stays at
+/// the head until a crank passes its maker.
+pub const MAX_EVENT_RETRIES: u8 = 8;
+
 #[derive(Accounts)]
 pub struct ConsumeEvents<'info> {
     #[account(
+        mut,
         seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
         bump = market.bump,
-        has_one = event_queue,
+        constraint = market.is_event_queue(&event_queue.key()) @ ErrorCode::InvalidEventQueueShard,
     )]
     pub market: Account<'info, Market>,
 
+    // Any of the market's event queue shards; each is cranked on its own
     #[account(mut)]
     pub event_queue: AccountLoader<'info, EventQueue>,
-    // remaining_accounts: maker user balance accounts to update
-    // Each account should be a mutable UserBalance PDA for the maker owner
+    // remaining_accounts: maker user balance accounts to update, at most
+    // MAX_CONSUME_EVENTS_ACCOUNTS. Each account should be a mutable
+    // UserBalance PDA for the maker owner; passing them in the order the
+    // makers first appear in the queue keeps every lookup short
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -23,102 +45,209 @@ pub struct ConsumeEventsParams {
 }
 
 impl ConsumeEvents<'_> {
-    pub fn apply(ctx: Context<ConsumeEvents>, params: ConsumeEventsParams) -> Result<()> {
+    /// Settle up to `limit` events and return how many were settled, so a
+    /// cranker can tell from the return data or EventsConsumed whether
+    /// another call is worthwhile
+    pub fn apply(ctx: Context<ConsumeEvents>, params: ConsumeEventsParams) -> Result<u8> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_CONSUME_EVENTS_ACCOUNTS,
+            ErrorCode::TooManyAccounts
+        );
         let mut event_queue = ctx.accounts.event_queue.load_mut()?;
-        let market = &ctx.accounts.market;
+        let market = &mut ctx.accounts.market;
 
         let mut processed = 0;
-
-        // Process events sequentially in order
-        while !event_queue.is_empty() && processed < params.limit {
-            let event = event_queue.pop_event()?;
-
-            // Find the account for this maker
-            let mut found_account = None;
-            for account_info in ctx.remaining_accounts.iter() {
-                // Verify this is the correct UserBalance PDA for this maker
-                let (expected_pda, _) = Pubkey::find_program_address(
-                    &[
-                        b"user_balance",
-                        event.maker_owner.as_ref(),
-                        market.key().as_ref(),
-                    ],
-                    &crate::ID,
-                );
-
-                if account_info.key() == expected_pda {
-                    found_account = Some(account_info);
-                    break;
-                }
+        let mut requeued = 0;
+        // Each maker's account index, so its account is searched for once
+        // however many of its events the crank settles
+        let mut maker_accounts: Vec<(Pubkey, usize)> = Vec::new();
+
+        // Process events in order, visiting each at most once: requeued
+        // events land behind the ones that were already waiting
+        let mut unvisited = event_queue.logical_len();
+        while unvisited > 0 && processed < params.limit {
+            unvisited -= 1;
+            let mut event = event_queue.pop_event()?;
+
+            if event.kind == FILL_EVENT_KIND_CROSS {
+                processed += 1;
+                continue;
             }
 
-            if let Some(account_info) = found_account {
-                // Update maker balance
-                Self::update_maker_balance(account_info, &event, market)?;
+            // Find the maker's UserBalance; matched on its stored keys, since
+            // deriving the PDA for every event dominates the crank's cost
+            let cached = maker_accounts
+                .iter()
+                .find(|(maker, _)| *maker == event.maker_owner)
+                .map(|&(_, index)| index);
+            let found_index = cached.or_else(|| {
+                let market_key = market.key();
+                let index = ctx.remaining_accounts.iter().position(|account_info| {
+                    UserBalance::is_account_of(account_info, &event.maker_owner, &market_key)
+                })?;
+                maker_accounts.push((event.maker_owner, index));
+                Some(index)
+            });
+
+            if let Some(index) = found_index {
+                let account_info = &ctx.remaining_accounts[index];
+                if event.kind == FILL_EVENT_KIND_REFUND {
+                    Self::refund_cleared_order(account_info, &event, market)?;
+                } else {
+                    // Update maker balance
+                    Self::update_maker_balance(account_info, &event, market)?;
+                }
                 processed += 1;
             } else {
-                // We don't have the maker's account, stop processing
-                break;
+                // We don't have the maker's account: move the event to the
+                // tail so it does not hold up makers whose accounts we have
+                require!(
+                    event.retry_count < MAX_EVENT_RETRIES,
+                    ErrorCode::EventRetriesExhausted
+                );
+                event.retry_count += 1;
+                event_queue.requeue_event(event)?;
+                requeued += 1;
             }
         }
 
-        msg!("Consumed {} events from queue", processed);
+        let remaining = event_queue.logical_len();
+        emit!(EventsConsumed {
+            market: market.key(),
+            event_queue: ctx.accounts.event_queue.key(),
+            processed,
+            remaining,
+        });
+
+        msg!(
+            "Consumed {} events from queue, requeued {}",
+            processed,
+            requeued
+        );
+        Ok(processed)
+    }
+
+    /// Settle an order removed from the book without a fill: hand back its
+    /// reservation, which the event carries in native units as `quantity`,
+    /// and its bond, carried as `price`
+    fn refund_cleared_order(
+        account_info: &AccountInfo,
+        event: &FillEvent,
+        market: &Account<Market>,
+    ) -> Result<()> {
+        let side = match event.maker_side {
+            0 => Side::Bid,
+            1 => Side::Ask,
+            _ => return Err(ErrorCode::InvalidParameter.into()),
+        };
+        let (base_delta, quote_delta) = ForceCancelAll::refund(account_info, side, event.quantity)?;
+        Market::pay_order_bond(&market.to_account_info(), account_info, event.price)?;
+
+        emit!(BalanceChanged {
+            user: event.maker_owner,
+            market: event.market,
+            base_delta,
+            quote_delta,
+            reason: BALANCE_REASON_ORDER_REFUND,
+            ref_id: event.maker_order_id,
+        });
+
         Ok(())
     }
 
+    /// Credit a maker's side of a fill, less the maker fee on what it
+    /// receives; the fee stays in the vault and accrues to the market
     fn update_maker_balance(
         account_info: &AccountInfo,
         event: &FillEvent,
-        market: &Market,
+        market: &mut Account<Market>,
     ) -> Result<()> {
-        // Borrow the account data mutably
-        let mut account_data = account_info.try_borrow_mut_data()?;
+        let mut user_balance = UserBalance::load_from(account_info)?;
 
-        // Deserialize UserBalance from the full account data (including discriminator)
-        let mut user_balance = UserBalance::try_deserialize(&mut account_data.as_ref())?;
+        let fill_base_amount = market.base_amount(event.quantity)?;
 
-        let fill_base_amount = event
-            .quantity
-            .checked_mul(market.base_lot_size)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        let fill_quote_amount = event
-            .price
-            .checked_mul(event.quantity)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_mul(market.quote_tick_size)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(market.base_lot_size)
-            .ok_or(ErrorCode::MathOverflow)?;
+        let fill_quote_amount = market.quote_amount(event.price, event.quantity)?;
 
         // Update maker balance based on their order side
         // Note: In place_limit_order, the maker's balance was already reserved/deducted
         // So in consume_events, we only need to apply the settlement:
         // - For bid makers: they already paid quote (reserved), now receive base
         // - For ask makers: they already paid base (reserved), now receive quote
-        match event.maker_side {
-            0 => {
+        let maker_side = match event.maker_side {
+            0 => Side::Bid,
+            1 => Side::Ask,
+            _ => return Err(ErrorCode::InvalidParameter.into()),
+        };
+        let (fee_base, fee_quote) = match maker_side {
+            Side::Bid => (market.maker_fee(fill_base_amount)?, 0),
+            Side::Ask => (0, market.maker_fee(fill_quote_amount)?),
+        };
+        market.accrue_fee(maker_side, fee_base.max(fee_quote))?;
+        let net_base = fill_base_amount
+            .checked_sub(fee_base)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let net_quote = fill_quote_amount
+            .checked_sub(fee_quote)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let (base_delta, quote_delta) = match maker_side {
+            Side::Bid => (BalanceChanged::credit(net_base)?, 0),
+            Side::Ask => (0, BalanceChanged::credit(net_quote)?),
+        };
+        match maker_side {
+            Side::Bid => {
                 // Maker bid order filled: receive base (quote was already deducted in place_limit_order)
                 user_balance.base_balance = user_balance
                     .base_balance
-                    .checked_add(fill_base_amount)
+                    .checked_add(net_base)
                     .ok_or(ErrorCode::MathOverflow)?;
                 // Note: quote was already deducted when order was placed, no need to subtract again
             }
-            1 => {
+            Side::Ask => {
                 // Maker ask order filled: receive quote (base was already deducted in place_limit_order)
                 user_balance.quote_balance = user_balance
                     .quote_balance
-                    .checked_add(fill_quote_amount)
+                    .checked_add(net_quote)
                     .ok_or(ErrorCode::MathOverflow)?;
                 // Note: base was already deducted when order was placed, no need to subtract again
             }
-            _ => return Err(ErrorCode::InvalidParameter.into()),
+        }
+        user_balance.record_fill(maker_side, fill_base_amount, fill_quote_amount);
+        if event.maker_order_closed != 0 {
+            user_balance.record_order_closed();
+        }
+
+        user_balance.store_to(account_info)?;
+        // The queue is drained before params change, so a fill settles under
+        // the bond its order was placed with
+        if event.maker_order_closed != 0 {
+            Market::pay_order_bond(
+                &market.to_account_info(),
+                account_info,
+                market.order_bond_lamports,
+            )?;
         }
 
-        // Serialize the updated balance back to the account
-        let mut cursor = std::io::Cursor::new(account_data.as_mut());
-        user_balance.try_serialize(&mut cursor)?;
+        emit!(MakerFillSettled {
+            maker_order_id: event.maker_order_id,
+            taker_order_id: event.taker_order_id,
+            market: event.market,
+            maker_owner: event.maker_owner,
+            maker_side,
+            price: event.price,
+            quantity: event.quantity,
+            gross_quote: fill_quote_amount,
+            fee_base,
+            fee_quote,
+            net_quote,
+        });
+        emit!(BalanceChanged {
+            user: event.maker_owner,
+            market: event.market,
+            base_delta,
+            quote_delta,
+            reason: BALANCE_REASON_MAKER_FILL,
+            ref_id: event.maker_order_id,
+        });
 
         Ok(())
     }
@@ -1,14 +1,20 @@
 use crate::errors::ErrorCode;
-use crate::events::UserDeposit;
-use crate::state::{Market, UserBalance};
+use crate::events::{BalanceChanged, UserBalanceCreated, UserDeposit, BALANCE_REASON_DEPOSIT};
+use crate::state::{Market, UserBalance, USER_BALANCE_SCHEMA_VERSION};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
+    // Pays for the balance account and authorises the transfer: the token
+    // account's owner or a delegate it approved
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// CHECK: only seeds the credited UserBalance; depositing on someone's
+    /// behalf can only ever add to their balance
+    pub owner: UncheckedAccount<'info>,
+
     #[account(
         seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
         bump = market.bump
@@ -19,21 +25,26 @@ pub struct Deposit<'info> {
         init_if_needed,
         payer = user,
         space = 8 + UserBalance::INIT_SPACE,
-        seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
+        seeds = [b"user_balance", owner.key().as_ref(), market.key().as_ref()],
         bump
     )]
     pub user_balance: Account<'info, UserBalance>,
 
+    // Base and quote may live under different token programs; every token
+    // account here must belong to the one `token_program` names for `mint`
     #[account(
         mut,
-        token::mint = mint
+        token::mint = mint,
+        token::token_program = token_program
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         seeds = [b"vault", market.key().as_ref(), mint.key().as_ref()],
-        bump
+        bump = market.vault_bump(&mint.key()),
+        token::mint = mint,
+        token::token_program = token_program
     )]
     pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
@@ -54,19 +65,47 @@ pub struct DepositParams {
 impl Deposit<'_> {
     pub fn apply(ctx: Context<Deposit>, params: DepositParams) -> Result<()> {
         require!(params.amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            !ctx.accounts.market.deposits_paused,
+            ErrorCode::MarketPaused
+        );
 
         let user_balance = &mut ctx.accounts.user_balance;
         let market = &ctx.accounts.market;
-        // Initialize user balance if it's first time
+        // init_if_needed only hands over a fresh, zeroed account or one this
+        // program already owns under these seeds; nobody else can create a
+        // PDA of ours. A zero owner therefore means created just now, and an
+        // existing balance must already belong to this owner and market.
         if user_balance.owner == Pubkey::default() {
-            user_balance.owner = ctx.accounts.user.key();
+            user_balance.owner = ctx.accounts.owner.key();
             user_balance.market = market.key();
             user_balance.base_balance = 0;
             user_balance.quote_balance = 0;
             user_balance.bump = ctx.bumps.user_balance;
+            user_balance.last_order_slot = 0;
+            user_balance.orders_this_slot = 0;
+            user_balance.next_intent_nonce = 0;
+            user_balance.position_base_net = 0;
+            user_balance.position_cost_quote = 0;
+            user_balance.realized_pnl_quote = 0;
+            user_balance.open_order_count = 0;
+            user_balance.schema_version = USER_BALANCE_SCHEMA_VERSION;
+
+            emit!(UserBalanceCreated {
+                user: user_balance.owner,
+                market: user_balance.market,
+                payer: ctx.accounts.user.key(),
+            });
+        } else {
+            require!(
+                user_balance.owner == ctx.accounts.owner.key()
+                    && user_balance.market == market.key(),
+                ErrorCode::Unauthorized
+            );
         }
 
-        // Transfer tokens from user to vault using checked transfer
+        // Transfer tokens from user to vault using checked transfer; the token
+        // program checks that the signer owns the account or is its delegate
         let cpi_accounts = TransferChecked {
             from: ctx.accounts.user_token_account.to_account_info(),
             to: ctx.accounts.vault_token_account.to_account_info(),
@@ -76,35 +115,57 @@ impl Deposit<'_> {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
+        let vault_before = ctx.accounts.vault_token_account.amount;
         token_interface::transfer_checked(cpi_ctx, params.amount, ctx.accounts.mint.decimals)?;
 
+        // Credit what the vault actually received; Token-2022 transfer fees are
+        // withheld from the transferred amount and never reach the vault balance
+        ctx.accounts.vault_token_account.reload()?;
+        let received = ctx
+            .accounts
+            .vault_token_account
+            .amount
+            .checked_sub(vault_before)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(received > 0, ErrorCode::InvalidAmount);
+
         // Update user balance record
-        let new_balance = if ctx.accounts.mint.key() == market.base_mint {
+        let is_base = ctx.accounts.mint.key() == market.base_mint;
+        let new_balance = if is_base {
             user_balance.base_balance = user_balance
                 .base_balance
-                .checked_add(params.amount)
+                .checked_add(received)
                 .ok_or(ErrorCode::MathOverflow)?;
             user_balance.base_balance
         } else {
             user_balance.quote_balance = user_balance
                 .quote_balance
-                .checked_add(params.amount)
+                .checked_add(received)
                 .ok_or(ErrorCode::MathOverflow)?;
             user_balance.quote_balance
         };
 
         // Emit deposit event
         emit!(UserDeposit {
-            user: ctx.accounts.user.key(),
+            user: ctx.accounts.owner.key(),
             market: market.key(),
             mint: ctx.accounts.mint.key(),
-            amount: params.amount,
+            amount: received,
             new_balance,
         });
+        let delta = BalanceChanged::credit(received)?;
+        emit!(BalanceChanged {
+            user: ctx.accounts.owner.key(),
+            market: market.key(),
+            base_delta: if is_base { delta } else { 0 },
+            quote_delta: if is_base { 0 } else { delta },
+            reason: BALANCE_REASON_DEPOSIT,
+            ref_id: 0,
+        });
 
         msg!(
             "Deposited {} tokens of mint {} to market vault",
-            params.amount,
+            received,
             ctx.accounts.mint.key()
         );
 
@@ -0,0 +1,142 @@
+use crate::errors::ErrorCode;
+use crate::events::{TwapSliceExecuted, TwapStatusChanged};
+use crate::instructions::{AddEventQueueShard, PlaceLimitOrder, PlaceLimitOrderParams};
+use crate::state::{
+    AskSide, BidSide, EventQueue, Market, SelfTradeBehavior, Side, TimeInForce, TwapOrder,
+    TwapStatus, UserBalance,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteTwapSlice<'info> {
+    // Anyone may send a due slice, and takes the TWAP's per-slice reward
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"twap",
+            market.key().as_ref(),
+            twap_order.owner.as_ref(),
+            &twap_order.twap_id.to_le_bytes(),
+        ],
+        bump = twap_order.bump,
+    )]
+    pub twap_order: Account<'info, TwapOrder>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", twap_order.owner.as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == twap_order.owner @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl ExecuteTwapSlice<'_> {
+    /// Send the TWAP's next slice as an IOC order on the owner's balance,
+    /// through place_limit_order's path. A schedule past its expiry is
+    /// marked expired instead. When the balance cannot cover the slice the
+    /// parent pauses until its next interval rather than failing, and
+    /// resumes on the first due slice it can pay for. A sharded market's
+    /// other event queues come first in the remaining accounts.
+    pub fn apply<'info>(ctx: Context<'_, '_, 'info, 'info, ExecuteTwapSlice<'info>>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let twap = &mut ctx.accounts.twap_order;
+        require!(!twap.is_done(), ErrorCode::TwapNotActive);
+        require!(now >= twap.next_slice_at, ErrorCode::TwapSliceNotDue);
+        let previous_status = twap.status;
+
+        let slice_quantity = twap.next_slice_quantity();
+        let market = &ctx.accounts.market;
+        let user_balance = &ctx.accounts.user_balance;
+        let funded = match twap.side {
+            Side::Bid => {
+                user_balance.quote_balance
+                    >= market.quote_amount(twap.limit_price, slice_quantity)?
+            }
+            Side::Ask => user_balance.base_balance >= market.base_amount(slice_quantity)?,
+        };
+
+        if twap.expiry != 0 && now > twap.expiry {
+            twap.status = TwapStatus::Expired;
+        } else if !funded {
+            twap.status = TwapStatus::Paused;
+            twap.next_slice_at = now.saturating_add(twap.interval_secs as i64);
+        } else {
+            let event_queues = AddEventQueueShard::load_shards(
+                &ctx.accounts.market,
+                &ctx.accounts.event_queue,
+                ctx.remaining_accounts,
+            )?;
+            // IOC never rests, so the executor is never charged a bond
+            let result = PlaceLimitOrder::place(
+                &mut ctx.accounts.market,
+                &ctx.accounts.bids,
+                &ctx.accounts.asks,
+                &event_queues,
+                &mut ctx.accounts.user_balance,
+                twap.owner,
+                &ctx.accounts.executor,
+                &ctx.accounts.system_program,
+                PlaceLimitOrderParams {
+                    side: twap.side,
+                    price: twap.limit_price,
+                    quantity: slice_quantity,
+                    time_in_force: TimeInForce::IOC,
+                    all_or_none: false,
+                    session: false,
+                    self_trade: SelfTradeBehavior::default(),
+                    client_order_id: 0,
+                },
+            )?;
+            twap.record_slice(result.filled_quantity, now)?;
+            let reward_lamports =
+                twap.pay_reward(&twap.to_account_info(), &ctx.accounts.executor)?;
+
+            emit!(TwapSliceExecuted {
+                twap: twap.key(),
+                owner: twap.owner,
+                market: twap.market,
+                executor: ctx.accounts.executor.key(),
+                order_id: result.order_id,
+                slice_quantity,
+                filled_quantity: result.filled_quantity,
+                total_filled: twap.filled_quantity,
+                reward_lamports,
+            });
+        }
+
+        if twap.status != previous_status {
+            emit!(TwapStatusChanged {
+                twap: twap.key(),
+                owner: twap.owner,
+                market: twap.market,
+                status: twap.status,
+                total_filled: twap.filled_quantity,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use crate::errors::ErrorCode;
+use crate::events::{BalanceChanged, OrderCancelled, BALANCE_REASON_ORDER_REFUND};
+use crate::state::{AskSide, BidSide, Market, Side, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ForceCancelAll<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+    // remaining_accounts: UserBalance PDAs of the owners of the orders to cancel
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ForceCancelAllParams {
+    pub limit: u8, // Maximum number of orders to cancel
+}
+
+impl ForceCancelAll<'_> {
+    // Cancels resting orders best-first (bids, then asks) once the close grace
+    // window has expired, refunding reservations to the owners' balances.
+    // Stops at the first order whose owner's UserBalance was not supplied.
+    pub fn apply(ctx: Context<ForceCancelAll>, params: ForceCancelAllParams) -> Result<()> {
+        let market = &ctx.accounts.market;
+        require!(market.is_closing(), ErrorCode::MarketNotClosing);
+        require!(
+            Clock::get()?.unix_timestamp >= market.close_grace_until,
+            ErrorCode::CloseGraceActive
+        );
+
+        let mut bids = ctx.accounts.bids.load_mut()?;
+        let mut asks = ctx.accounts.asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+        let mut cancelled = 0;
+
+        for side in [Side::Bid, Side::Ask] {
+            while cancelled < params.limit {
+                let order = match side {
+                    Side::Bid => bids.orderbook.peek(),
+                    Side::Ask => asks.orderbook.peek(),
+                };
+                let Some(order) = order else {
+                    break;
+                };
+
+                let Some(account_info) =
+                    Self::find_user_balance(ctx.remaining_accounts, &market.key(), &order.owner)
+                else {
+                    // We don't have the owner's account, stop processing
+                    msg!("Force cancelled {} orders", cancelled);
+                    return Ok(());
+                };
+                match side {
+                    Side::Bid => bids.orderbook.pop(),
+                    Side::Ask => asks.orderbook.pop(),
+                };
+                let reserved = market.reserved_amount(side, &order)?;
+                let (base_delta, quote_delta) = Self::refund(account_info, side, reserved)?;
+                Market::pay_order_bond(
+                    &market.to_account_info(),
+                    account_info,
+                    market.order_bond_lamports,
+                )?;
+                cancelled += 1;
+
+                emit!(OrderCancelled {
+                    order_id: order.order_id,
+                    owner: order.owner,
+                    market: market.key(),
+                    side,
+                    remaining_quantity: order.remaining_quantity,
+                });
+                emit!(BalanceChanged {
+                    user: order.owner,
+                    market: market.key(),
+                    base_delta,
+                    quote_delta,
+                    reason: BALANCE_REASON_ORDER_REFUND,
+                    ref_id: order.order_id,
+                });
+            }
+        }
+
+        msg!("Force cancelled {} orders", cancelled);
+        Ok(())
+    }
+
+    fn find_user_balance<'a, 'info>(
+        remaining_accounts: &'a [AccountInfo<'info>],
+        market: &Pubkey,
+        owner: &Pubkey,
+    ) -> Option<&'a AccountInfo<'info>> {
+        remaining_accounts
+            .iter()
+            .find(|account_info| UserBalance::is_account_of(account_info, owner, market))
+    }
+
+    /// Credits `amount` of the asset an order on `side` reserves (quote for
+    /// bids, base for asks) for an order that left the book, and returns the
+    /// (base, quote) deltas applied.
+    pub(crate) fn refund(
+        account_info: &AccountInfo,
+        side: Side,
+        amount: u64,
+    ) -> Result<(i64, i64)> {
+        let mut user_balance = UserBalance::load_from(account_info)?;
+        user_balance.record_order_closed();
+
+        let deltas = match side {
+            Side::Bid => {
+                // Return reserved quote tokens
+                user_balance.quote_balance = user_balance
+                    .quote_balance
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                (0, BalanceChanged::credit(amount)?)
+            }
+            Side::Ask => {
+                // Return reserved base tokens
+                user_balance.base_balance = user_balance
+                    .base_balance
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                (BalanceChanged::credit(amount)?, 0)
+            }
+        };
+
+        user_balance.store_to(account_info)?;
+
+        Ok(deltas)
+    }
+}
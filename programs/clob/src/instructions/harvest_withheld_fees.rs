@@ -0,0 +1,113 @@
+use crate::events::WithheldFeesHarvested;
+use crate::state::Market;
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{
+    self,
+    spl_token_2022::{
+        extension::{
+            transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Account as SplTokenAccount,
+    },
+};
+use anchor_spl::token_interface::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint, Mint, TokenAccount,
+    TokenInterface,
+};
+
+#[derive(Accounts)]
+pub struct HarvestWithheldFees<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = base_mint,
+        has_one = quote_mint,
+        has_one = base_vault,
+        has_one = quote_vault,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut, mint::token_program = base_token_program)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, mint::token_program = quote_token_program)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+impl HarvestWithheldFees<'_> {
+    /// Permissionless: moves Token-2022 transfer fees withheld on the vaults
+    /// to their mints. Withheld amounts are not part of a vault's `amount`, so
+    /// user balances are unaffected; this only clears them off our accounts.
+    pub fn apply(ctx: Context<HarvestWithheldFees>) -> Result<()> {
+        let accounts = &ctx.accounts;
+
+        let base_harvested = harvest(
+            &accounts.base_token_program,
+            &accounts.base_mint,
+            &accounts.base_vault,
+        )?;
+        let quote_harvested = harvest(
+            &accounts.quote_token_program,
+            &accounts.quote_mint,
+            &accounts.quote_vault,
+        )?;
+
+        emit!(WithheldFeesHarvested {
+            market: accounts.market.key(),
+            base_harvested,
+            quote_harvested,
+        });
+
+        msg!(
+            "Harvested withheld fees: base={}, quote={}",
+            base_harvested,
+            quote_harvested
+        );
+
+        Ok(())
+    }
+}
+
+/// Harvest one vault, returning the amount moved to the mint
+fn harvest<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<u64> {
+    let vault_info = vault.to_account_info();
+    let withheld = withheld_amount(&vault_info)?;
+    if withheld == 0 {
+        return Ok(0);
+    }
+
+    let cpi_accounts = HarvestWithheldTokensToMint {
+        token_program_id: token_program.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    harvest_withheld_tokens_to_mint(cpi_ctx, vec![vault_info])?;
+
+    Ok(withheld)
+}
+
+/// Transfer fees withheld on a token account; always 0 for legacy SPL Token
+/// accounts and Token-2022 mints without the TransferFee extension
+pub fn withheld_amount(token_account: &AccountInfo) -> Result<u64> {
+    if token_account.owner != &token_2022::ID {
+        return Ok(0);
+    }
+
+    let data = token_account.try_borrow_data()?;
+    let state = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map(|fee| u64::from(fee.withheld_amount))
+        .unwrap_or(0))
+}
@@ -0,0 +1,162 @@
+use crate::errors::ErrorCode;
+use crate::events::{
+    BalanceChanged, BookDelta, LevelChange, OrderIncreased, BALANCE_REASON_ORDER_RESERVE,
+};
+use crate::state::{AskSide, BidSide, Market, Order, OrderBook, Side, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: IncreaseOrderParams)]
+pub struct IncreaseOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+    pub user: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct IncreaseOrderParams {
+    pub order_id: u64,
+    pub side: Side,               // Specify which orderbook to search
+    pub additional_quantity: u64, // Quantity to add in base_lot_size units
+}
+
+impl IncreaseOrder<'_> {
+    /// Adds quantity to a resting order at its existing price, keeping its
+    /// id. The order is updated in place, so it stays one entry on the book
+    /// and the added quantity shares its queue position;
+    /// increase_order_size is the variant that gives up priority instead.
+    pub fn apply(ctx: Context<IncreaseOrder>, params: IncreaseOrderParams) -> Result<()> {
+        require!(params.additional_quantity > 0, ErrorCode::InvalidOrderSize);
+        require!(!ctx.accounts.market.is_closing(), ErrorCode::MarketClosing);
+        require!(
+            !ctx.accounts.market.is_side_halted(params.side),
+            ErrorCode::SideHalted
+        );
+
+        let market = &mut ctx.accounts.market;
+        let user_balance = &mut ctx.accounts.user_balance;
+        let mut bids = ctx.accounts.bids.load_mut()?;
+        let mut asks = ctx.accounts.asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+
+        let existing = match params.side {
+            Side::Bid => bids.orderbook.find_order_by_id(params.order_id),
+            Side::Ask => asks.orderbook.find_order_by_id(params.order_id),
+        }
+        .ok_or(ErrorCode::OrderNotFound)?;
+
+        // Verify the order belongs to the user
+        require!(
+            existing.owner == ctx.accounts.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Reserve balance for the added quantity only
+        let (base_delta, quote_delta) = match params.side {
+            Side::Bid => {
+                let required_quote =
+                    market.quote_amount(existing.price, params.additional_quantity)?;
+
+                user_balance.quote_balance = user_balance
+                    .quote_balance
+                    .checked_sub(required_quote)
+                    .ok_or(ErrorCode::InsufficientBalance)?;
+                (0, BalanceChanged::debit(required_quote)?)
+            }
+            Side::Ask => {
+                let required_base = market.base_amount(params.additional_quantity)?;
+
+                user_balance.base_balance = user_balance
+                    .base_balance
+                    .checked_sub(required_base)
+                    .ok_or(ErrorCode::InsufficientBalance)?;
+                (BalanceChanged::debit(required_base)?, 0)
+            }
+        };
+
+        let order = Order {
+            quantity: existing
+                .quantity
+                .checked_add(params.additional_quantity)
+                .ok_or(ErrorCode::MathOverflow)?,
+            remaining_quantity: existing
+                .remaining_quantity
+                .checked_add(params.additional_quantity)
+                .ok_or(ErrorCode::MathOverflow)?,
+            ..existing
+        };
+
+        // Same id, price and timestamp, so the order goes back where it was
+        match params.side {
+            Side::Bid => {
+                bids.orderbook.remove_order(order.order_id)?;
+                bids.orderbook.insert_order(order)?;
+            }
+            Side::Ask => {
+                asks.orderbook.remove_order(order.order_id)?;
+                asks.orderbook.insert_order(order)?;
+            }
+        }
+        let new_total_quantity = match params.side {
+            Side::Bid => bids.orderbook.level_quantity(order.price),
+            Side::Ask => asks.orderbook.level_quantity(order.price),
+        };
+
+        emit!(OrderIncreased {
+            order_id: order.order_id,
+            owner: order.owner,
+            market: market.key(),
+            side: params.side,
+            price: order.price,
+            added_quantity: params.additional_quantity,
+            timestamp: order.timestamp,
+        });
+        emit!(BalanceChanged {
+            user: order.owner,
+            market: market.key(),
+            base_delta,
+            quote_delta,
+            reason: BALANCE_REASON_ORDER_RESERVE,
+            ref_id: order.order_id,
+        });
+        emit!(BookDelta::new(
+            market.key(),
+            market.next_book_seq_num()?,
+            vec![LevelChange {
+                side: params.side,
+                price: order.price,
+                new_total_quantity,
+            }],
+            bids.orderbook.book_hash(),
+            asks.orderbook.book_hash(),
+        ));
+
+        msg!(
+            "Order increased: id={}, added_quantity={}",
+            order.order_id,
+            params.additional_quantity
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use crate::errors::ErrorCode;
+use crate::events::{
+    BalanceChanged, BookDelta, LevelChange, OrderModified, BALANCE_REASON_ORDER_RESERVE,
+};
+use crate::state::{AskSide, BidSide, Market, Order, OrderBook, Side, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct IncreaseOrderSize<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+    pub user: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct IncreaseOrderSizeParams {
+    pub order_id: u64,
+    pub additional_quantity: u64, // Quantity to add in base_lot_size units
+}
+
+impl IncreaseOrderSize<'_> {
+    /// Amend a resting order up at its price, giving up its time priority:
+    /// unlike increase_order, the whole order goes to the back of its level.
+    /// Equal timestamps fall back to order id, so the order is re-queued
+    /// under a fresh id, which is returned. Only the difference between the
+    /// new reservation and what the order held is taken from the balance.
+    pub fn apply(ctx: Context<IncreaseOrderSize>, params: IncreaseOrderSizeParams) -> Result<u64> {
+        require!(params.additional_quantity > 0, ErrorCode::InvalidOrderSize);
+        let market = &mut ctx.accounts.market;
+        require!(!market.is_closing(), ErrorCode::MarketClosing);
+
+        let mut bids = ctx.accounts.bids.load_mut()?;
+        let mut asks = ctx.accounts.asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+
+        let (side, existing) = if let Some(order) = bids.orderbook.find_order_by_id(params.order_id)
+        {
+            (Side::Bid, order)
+        } else {
+            let order = asks
+                .orderbook
+                .find_order_by_id(params.order_id)
+                .ok_or(ErrorCode::OrderNotFound)?;
+            (Side::Ask, order)
+        };
+        require!(!market.is_side_halted(side), ErrorCode::SideHalted);
+        require!(
+            existing.owner == ctx.accounts.user.key(),
+            ErrorCode::Unauthorized
+        );
+
+        let removed = match side {
+            Side::Bid => bids.orderbook.remove_order(params.order_id)?,
+            Side::Ask => asks.orderbook.remove_order(params.order_id)?,
+        };
+        let old = removed.ok_or(ErrorCode::OrderNotFound)?;
+        let old_reserved = market.reserved_amount(side, &old)?;
+
+        let order = Order {
+            order_id: market.next_order_id,
+            owner: existing.owner,
+            price: existing.price,
+            quantity: old
+                .quantity
+                .checked_add(params.additional_quantity)
+                .ok_or(ErrorCode::MathOverflow)?,
+            remaining_quantity: old
+                .remaining_quantity
+                .checked_add(params.additional_quantity)
+                .ok_or(ErrorCode::MathOverflow)?,
+            timestamp: Clock::get()?.unix_timestamp,
+            flags: existing.flags,
+            _padding: [0; 7],
+            client_order_id: existing.client_order_id,
+        };
+        market.next_order_id = market
+            .next_order_id
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let required = market
+            .reserved_amount(side, &order)?
+            .checked_sub(old_reserved)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let user_balance = &mut ctx.accounts.user_balance;
+        let (base_delta, quote_delta) = match side {
+            Side::Bid => {
+                user_balance.quote_balance = user_balance
+                    .quote_balance
+                    .checked_sub(required)
+                    .ok_or(ErrorCode::InsufficientBalance)?;
+                bids.orderbook.insert_order(order)?;
+                (0, BalanceChanged::debit(required)?)
+            }
+            Side::Ask => {
+                user_balance.base_balance = user_balance
+                    .base_balance
+                    .checked_sub(required)
+                    .ok_or(ErrorCode::InsufficientBalance)?;
+                asks.orderbook.insert_order(order)?;
+                (BalanceChanged::debit(required)?, 0)
+            }
+        };
+        let new_total_quantity = match side {
+            Side::Bid => bids.orderbook.level_quantity(order.price),
+            Side::Ask => asks.orderbook.level_quantity(order.price),
+        };
+
+        emit!(OrderModified {
+            old_order_id: params.order_id,
+            order_id: order.order_id,
+            owner: order.owner,
+            market: market.key(),
+            side,
+            price: order.price,
+            old_quantity: old.quantity,
+            new_quantity: order.quantity,
+            old_remaining_quantity: old.remaining_quantity,
+            new_remaining_quantity: order.remaining_quantity,
+            timestamp: order.timestamp,
+        });
+        emit!(BalanceChanged {
+            user: order.owner,
+            market: market.key(),
+            base_delta,
+            quote_delta,
+            reason: BALANCE_REASON_ORDER_RESERVE,
+            ref_id: order.order_id,
+        });
+        emit!(BookDelta::new(
+            market.key(),
+            market.next_book_seq_num()?,
+            vec![LevelChange {
+                side,
+                price: order.price,
+                new_total_quantity,
+            }],
+            bids.orderbook.book_hash(),
+            asks.orderbook.book_hash(),
+        ));
+
+        Ok(order.order_id)
+    }
+}
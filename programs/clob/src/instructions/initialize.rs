@@ -1,6 +1,8 @@
 use crate::errors::ErrorCode;
 use crate::events::MarketInitialized;
-use crate::state::{AskSide, BidSide, EventQueue, Market, MAX_EVENTS};
+use crate::state::{
+    AskSide, BidSide, EventQueue, Market, TieBreakMode, EXTRA_EVENT_QUEUE_SHARDS, MAX_FEE_BPS,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
@@ -44,9 +46,19 @@ pub struct Initialize<'info> {
     pub base_mint: InterfaceAccount<'info, Mint>,
     pub quote_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(zero)]
+    // Checked here so a wrongly sized book fails with a named error instead
+    // of load_init's out-of-range slice
+    #[account(
+        zero,
+        constraint = bids.as_ref().data_len() == 8 + std::mem::size_of::<BidSide>()
+            @ ErrorCode::InvalidBookAccount,
+    )]
     pub bids: AccountLoader<'info, BidSide>,
-    #[account(zero)]
+    #[account(
+        zero,
+        constraint = asks.as_ref().data_len() == 8 + std::mem::size_of::<AskSide>()
+            @ ErrorCode::InvalidBookAccount,
+    )]
     pub asks: AccountLoader<'info, AskSide>,
     #[account(zero)]
     pub event_queue: AccountLoader<'info, EventQueue>,
@@ -60,8 +72,17 @@ pub struct Initialize<'info> {
 pub struct InitializeParams {
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
-    pub base_lot_size: u64,   // Minimum base asset unit size
-    pub quote_tick_size: u64, // Minimum quote asset price tick size
+    pub base_lot_size: u64,               // Minimum base asset unit size
+    pub quote_tick_size: u64,             // Minimum quote asset price tick size
+    pub rent_payer: Pubkey,               // Who funded the bids/asks/event_queue accounts
+    pub tie_break_mode: TieBreakMode,     // Priority among resting orders at equal price
+    pub max_orders_per_slot_per_user: u8, // Placement cap per user per slot; 0 = unlimited
+    pub allow_locked_book: bool,          // Let a remainder rest at or through the opposite best
+    pub order_bond_lamports: u64,         // Lamports each resting order bonds; 0 = none
+    pub max_event_age_secs: u32,          // Oldest unsettled event matching tolerates; 0 = off
+    pub max_orders_per_level: u16,        // Resting orders allowed at one price; 0 = unlimited
+    pub maker_fee_bps: u16,               // Fee on what makers receive, up to MAX_FEE_BPS
+    pub taker_fee_bps: u16,               // Fee on what takers receive, up to MAX_FEE_BPS
 }
 
 impl Initialize<'_> {
@@ -75,16 +96,23 @@ impl Initialize<'_> {
         // Validate orderbook parameters
         require!(params.base_lot_size > 0, ErrorCode::InvalidParameter);
         require!(params.quote_tick_size > 0, ErrorCode::InvalidParameter);
+        require!(
+            params.rent_payer != Pubkey::default(),
+            ErrorCode::InvalidParameter
+        );
+        require!(
+            params.maker_fee_bps <= MAX_FEE_BPS && params.taker_fee_bps <= MAX_FEE_BPS,
+            ErrorCode::InvalidParameter
+        );
 
         // Initialize bids book
-        let _bids = &mut ctx.accounts.bids.load_init()?;
+        let bids = &mut ctx.accounts.bids.load_init()?;
+        bids.orderbook.init(params.tie_break_mode);
         // Initialize asks book
-        let _asks = &mut ctx.accounts.asks.load_init()?;
+        let asks = &mut ctx.accounts.asks.load_init()?;
+        asks.orderbook.init(params.tie_break_mode);
         // Initialize event queue
-        let event_queue = &mut ctx.accounts.event_queue.load_init()?;
-        event_queue.head = 0;
-        event_queue.tail = 0;
-        event_queue.capacity = MAX_EVENTS as u64;
+        ctx.accounts.event_queue.load_init()?.init();
 
         let market = &mut ctx.accounts.market;
         market.authority = ctx.accounts.authority.key();
@@ -98,7 +126,27 @@ impl Initialize<'_> {
         market.base_lot_size = params.base_lot_size;
         market.quote_tick_size = params.quote_tick_size;
         market.next_order_id = 1; // Start order IDs from 1
+        market.rent_payer = params.rent_payer;
+        market.tie_break_mode = params.tie_break_mode;
+        market.close_grace_until = 0;
+        market.max_orders_per_slot_per_user = params.max_orders_per_slot_per_user;
+        market.book_seq_num = 0;
+        market.flags = 0;
+        market.cross_authority = Pubkey::default();
+        market.max_cross_trade_through = 0;
+        market.deposits_paused = false;
+        market.withdrawals_paused = false;
+        market.event_queue_shard_count = 1;
+        market.event_queue_shards = [Pubkey::default(); EXTRA_EVENT_QUEUE_SHARDS];
+        market.allow_locked_book = params.allow_locked_book;
+        market.order_bond_lamports = params.order_bond_lamports;
+        market.max_event_age_secs = params.max_event_age_secs;
+        market.max_orders_per_level = params.max_orders_per_level;
+        market.maker_fee_bps = params.maker_fee_bps;
+        market.taker_fee_bps = params.taker_fee_bps;
         market.bump = ctx.bumps.market;
+        market.base_vault_bump = ctx.bumps.base_vault;
+        market.quote_vault_bump = ctx.bumps.quote_vault;
 
         emit!(MarketInitialized {
             market: market.key(),
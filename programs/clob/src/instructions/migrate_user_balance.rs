@@ -0,0 +1,120 @@
+use crate::errors::ErrorCode;
+use crate::events::UserBalanceMigrated;
+use crate::state::{
+    AskSide, BidSide, Market, UserBalance, USER_BALANCE_SCHEMA_VERSION, USER_BALANCE_SIZE,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct MigrateUserBalance<'info> {
+    // The balance's owner; pays the rent for the added space
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    pub bids: AccountLoader<'info, BidSide>,
+    pub asks: AccountLoader<'info, AskSide>,
+
+    /// CHECK: the user's UserBalance, possibly under an older, shorter layout
+    /// that does not deserialize as the current one. The seeds pin it to the
+    /// signer; apply checks the discriminator.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
+        bump,
+    )]
+    pub user_balance: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateUserBalance<'_> {
+    // Brings a UserBalance written under an older schema up to the current
+    // one in place. Fields are only ever appended, so the account is grown
+    // to the current size with the new tail zeroed, then the fields that can
+    // be derived are rebuilt: the open order count from the books.
+    // Reservations are taken out of the balances when orders rest, so those
+    // are already right. A current account is left as it is.
+    pub fn apply(ctx: Context<MigrateUserBalance>) -> Result<()> {
+        let account_info = ctx.accounts.user_balance.to_account_info();
+        let previous_size = account_info.data_len();
+        {
+            let data = account_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *UserBalance::DISCRIMINATOR,
+                ErrorCode::InvalidParameter
+            );
+        }
+
+        if previous_size >= USER_BALANCE_SIZE
+            && UserBalance::load_from(&account_info)?.schema_version == USER_BALANCE_SCHEMA_VERSION
+        {
+            msg!(
+                "User balance already at schema {}",
+                USER_BALANCE_SCHEMA_VERSION
+            );
+            return Ok(());
+        }
+
+        if previous_size < USER_BALANCE_SIZE {
+            let shortfall = Rent::get()?
+                .minimum_balance(USER_BALANCE_SIZE)
+                .saturating_sub(account_info.lamports());
+            if shortfall > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.user.to_account_info(),
+                            to: account_info.clone(),
+                        },
+                    ),
+                    shortfall,
+                )?;
+            }
+            account_info.realloc(USER_BALANCE_SIZE, true)?;
+        }
+
+        let user = ctx.accounts.user.key();
+        let open_orders = ctx
+            .accounts
+            .bids
+            .load()?
+            .orderbook
+            .orders()
+            .into_iter()
+            .chain(ctx.accounts.asks.load()?.orderbook.orders())
+            .filter(|order| order.owner == user)
+            .count();
+
+        let mut user_balance = UserBalance::load_from(&account_info)?;
+        user_balance.open_order_count = open_orders as u32;
+        user_balance.schema_version = USER_BALANCE_SCHEMA_VERSION;
+        user_balance.store_to(&account_info)?;
+
+        emit!(UserBalanceMigrated {
+            user,
+            market: ctx.accounts.market.key(),
+            previous_size: previous_size as u32,
+            schema_version: USER_BALANCE_SCHEMA_VERSION,
+            open_order_count: user_balance.open_order_count,
+        });
+
+        msg!(
+            "User balance migrated from {} to {} bytes",
+            previous_size,
+            USER_BALANCE_SIZE
+        );
+
+        Ok(())
+    }
+}
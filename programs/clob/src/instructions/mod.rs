@@ -1,15 +1,87 @@
+pub mod add_event_queue_shard;
+pub mod begin_market_close;
+pub mod cancel_all_my_orders;
+pub mod cancel_and_credit;
+pub mod cancel_expired_session_orders;
 pub mod cancel_order;
+pub mod cancel_order_by_client_id;
+pub mod cancel_signed_order;
+pub mod clear_book;
+pub mod close_market;
+pub mod close_twap_order;
 pub mod close_user_balance;
 pub mod consume_events;
 pub mod deposit;
+pub mod execute_twap_slice;
+pub mod force_cancel_all;
+pub mod harvest_withheld_fees;
+pub mod increase_order;
+pub mod increase_order_size;
 pub mod initialize;
+pub mod migrate_user_balance;
+pub mod modify_order;
+pub mod open_trade_cursor;
 pub mod place_limit_order;
+pub mod place_market_order;
+pub mod place_signed_order;
+pub mod place_twap_order;
+pub mod query_next_order_id;
+pub mod query_price_levels;
+pub mod query_total_events_pushed;
+pub mod quote_order;
+pub mod read_trades;
+pub mod reclaim_orphaned_account;
+pub mod register_session;
+pub mod set_cross_authority;
+pub mod set_market_flags;
+pub mod set_pause_flags;
+pub mod settle_market;
+pub mod submit_cross;
+pub mod swap_exact_in;
+pub mod update_market_params;
 pub mod withdraw;
+pub mod withdraw_fees;
 
+pub use add_event_queue_shard::*;
+pub use begin_market_close::*;
+pub use cancel_all_my_orders::*;
+pub use cancel_and_credit::*;
+pub use cancel_expired_session_orders::*;
 pub use cancel_order::*;
+pub use cancel_order_by_client_id::*;
+pub use cancel_signed_order::*;
+pub use clear_book::*;
+pub use close_market::*;
+pub use close_twap_order::*;
 pub use close_user_balance::*;
 pub use consume_events::*;
 pub use deposit::*;
+pub use execute_twap_slice::*;
+pub use force_cancel_all::*;
+pub use harvest_withheld_fees::*;
+pub use increase_order::*;
+pub use increase_order_size::*;
 pub use initialize::*;
+pub use migrate_user_balance::*;
+pub use modify_order::*;
+pub use open_trade_cursor::*;
 pub use place_limit_order::*;
+pub use place_market_order::*;
+pub use place_signed_order::*;
+pub use place_twap_order::*;
+pub use query_next_order_id::*;
+pub use query_price_levels::*;
+pub use query_total_events_pushed::*;
+pub use quote_order::*;
+pub use read_trades::*;
+pub use reclaim_orphaned_account::*;
+pub use register_session::*;
+pub use set_cross_authority::*;
+pub use set_market_flags::*;
+pub use set_pause_flags::*;
+pub use settle_market::*;
+pub use submit_cross::*;
+pub use swap_exact_in::*;
+pub use update_market_params::*;
 pub use withdraw::*;
+pub use withdraw_fees::*;
@@ -0,0 +1,244 @@
+use crate::errors::ErrorCode;
+use crate::events::{
+    BalanceChanged, BookDelta, LevelChange, OrderModified, BALANCE_REASON_ORDER_REFUND,
+};
+use crate::instructions::{
+    AddEventQueueShard, CancelOrder, PlaceLimitOrder, PlaceLimitOrderParams, PlaceLimitOrderResult,
+};
+use crate::state::{
+    AskSide, BidSide, FillStatus, Order, OrderBook, OrderStatus, SelfTradeBehavior, Side,
+    TimeInForce,
+};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ModifyOrderParams {
+    pub order_id: u64,
+    pub side: Side,                // Book the order rests on
+    pub new_price: Option<u64>,    // Price in quote_tick_size units; None keeps the price
+    pub new_quantity: Option<u64>, // Lots left to rest; None keeps the remaining quantity
+}
+
+impl PlaceLimitOrder<'_> {
+    /// Change a resting order's price or size in one instruction. Shrinking
+    /// it at the same price trims it in place: it keeps its id and queue
+    /// position, and the freed reservation returns to the balance. Anything
+    /// else cancels it and places it again as a GTC under a fresh id and
+    /// timestamp, which matches first if the new price crosses the book.
+    pub fn apply_modify<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: ModifyOrderParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        require!(
+            params.new_price.is_some() || params.new_quantity.is_some(),
+            ErrorCode::InvalidParameter
+        );
+        require!(params.new_quantity != Some(0), ErrorCode::InvalidOrderSize);
+        let market = &ctx.accounts.market;
+        require!(!market.is_closing(), ErrorCode::MarketClosing);
+        require!(!market.is_side_halted(params.side), ErrorCode::SideHalted);
+
+        let old = Self::take_order(
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            params.side,
+            params.order_id,
+            ctx.accounts.user.key(),
+        )?;
+
+        let price = params.new_price.unwrap_or(old.price);
+        let quantity = params.new_quantity.unwrap_or(old.remaining_quantity);
+        if price == old.price && quantity <= old.remaining_quantity {
+            require!(
+                quantity < old.remaining_quantity,
+                ErrorCode::InvalidParameter
+            );
+            return Self::trim(ctx, params.side, old, quantity);
+        }
+        Self::replace(ctx, params.side, old, price, quantity)
+    }
+
+    /// Take `order_id` off `side`'s book once it is known to be `owner`'s
+    fn take_order<'info>(
+        bids: &AccountLoader<'info, BidSide>,
+        asks: &AccountLoader<'info, AskSide>,
+        side: Side,
+        order_id: u64,
+        owner: Pubkey,
+    ) -> Result<Order> {
+        let mut bids = bids.load_mut()?;
+        let mut asks = asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+
+        // Check before touching the book, so a failed modify never leaves it
+        // mutated
+        let found = match side {
+            Side::Bid => bids.orderbook.find_order_by_id(order_id),
+            Side::Ask => asks.orderbook.find_order_by_id(order_id),
+        }
+        .ok_or(ErrorCode::OrderNotFound)?;
+        require!(found.owner == owner, ErrorCode::Unauthorized);
+
+        let removed = match side {
+            Side::Bid => bids.orderbook.remove_order(order_id)?,
+            Side::Ask => asks.orderbook.remove_order(order_id)?,
+        };
+        Ok(removed.ok_or(ErrorCode::OrderNotFound)?)
+    }
+
+    /// Put the order back with only `quantity` lots left. It keeps its
+    /// timestamp, so it keeps its place in the queue.
+    fn trim<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        side: Side,
+        old: Order,
+        quantity: u64,
+    ) -> Result<PlaceLimitOrderResult> {
+        let market = &mut ctx.accounts.market;
+        let mut bids = ctx.accounts.bids.load_mut()?;
+        let mut asks = ctx.accounts.asks.load_mut()?;
+
+        let order = Order {
+            remaining_quantity: quantity,
+            ..old
+        };
+        let refund = market
+            .reserved_amount(side, &old)?
+            .checked_sub(market.reserved_amount(side, &order)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        match side {
+            Side::Bid => bids.orderbook.insert_order(order)?,
+            Side::Ask => asks.orderbook.insert_order(order)?,
+        }
+
+        let (base_delta, quote_delta) =
+            CancelOrder::refund_to_balance(&mut ctx.accounts.user_balance, side, refund)?;
+        let new_total_quantity = match side {
+            Side::Bid => bids.orderbook.level_quantity(order.price),
+            Side::Ask => asks.orderbook.level_quantity(order.price),
+        };
+
+        emit!(OrderModified {
+            old_order_id: order.order_id,
+            order_id: order.order_id,
+            owner: order.owner,
+            market: market.key(),
+            side,
+            price: order.price,
+            old_quantity: old.quantity,
+            new_quantity: old.quantity - (old.remaining_quantity - quantity),
+            old_remaining_quantity: old.remaining_quantity,
+            new_remaining_quantity: quantity,
+            timestamp: order.timestamp,
+        });
+        emit!(BalanceChanged {
+            user: order.owner,
+            market: market.key(),
+            base_delta,
+            quote_delta,
+            reason: BALANCE_REASON_ORDER_REFUND,
+            ref_id: order.order_id,
+        });
+        emit!(BookDelta::new(
+            market.key(),
+            market.next_book_seq_num()?,
+            vec![LevelChange {
+                side,
+                price: order.price,
+                new_total_quantity,
+            }],
+            bids.orderbook.book_hash(),
+            asks.orderbook.book_hash(),
+        ));
+
+        Ok(PlaceLimitOrderResult {
+            order_id: order.order_id,
+            filled_quantity: 0,
+            resting_quantity: quantity,
+            status: OrderStatus::Resting,
+            fill_status: FillStatus::Resting,
+        })
+    }
+
+    /// Cancel the order as cancel_order would, then place `quantity` lots
+    /// at `price` with the same flags
+    fn replace<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        side: Side,
+        old: Order,
+        price: u64,
+        quantity: u64,
+    ) -> Result<PlaceLimitOrderResult> {
+        let owner = ctx.accounts.user.key();
+        let market = &mut ctx.accounts.market;
+
+        let refund = market.reserved_amount(side, &old)?;
+
+        let bids = ctx.accounts.bids.load()?;
+        let asks = ctx.accounts.asks.load()?;
+        let new_total_quantity = match side {
+            Side::Bid => bids.orderbook.level_quantity(old.price),
+            Side::Ask => asks.orderbook.level_quantity(old.price),
+        };
+        let book_delta = BookDelta::new(
+            market.key(),
+            market.next_book_seq_num()?,
+            vec![LevelChange {
+                side,
+                price: old.price,
+                new_total_quantity,
+            }],
+            bids.orderbook.book_hash(),
+            asks.orderbook.book_hash(),
+        );
+        drop(bids);
+        drop(asks);
+        let user_balance = &mut ctx.accounts.user_balance;
+        CancelOrder::close_order(market, user_balance)?;
+        let balance_deltas = CancelOrder::refund_to_balance(user_balance, side, refund)?;
+        CancelOrder::emit_cancelled(owner, &old, side, book_delta, Some(balance_deltas));
+
+        let event_queues = AddEventQueueShard::load_shards(
+            &ctx.accounts.market,
+            &ctx.accounts.event_queue,
+            ctx.remaining_accounts,
+        )?;
+        let placed = Self::place(
+            &mut ctx.accounts.market,
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            &event_queues,
+            &mut ctx.accounts.user_balance,
+            owner,
+            &ctx.accounts.user,
+            &ctx.accounts.system_program,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force: TimeInForce::GTC,
+                all_or_none: old.is_all_or_none(),
+                session: old.is_session(),
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id: old.client_order_id,
+            },
+        )?;
+
+        emit!(OrderModified {
+            old_order_id: old.order_id,
+            order_id: placed.order_id,
+            owner,
+            market: ctx.accounts.market.key(),
+            side,
+            price,
+            old_quantity: old.quantity,
+            new_quantity: quantity,
+            old_remaining_quantity: old.remaining_quantity,
+            new_remaining_quantity: placed.resting_quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(placed)
+    }
+}
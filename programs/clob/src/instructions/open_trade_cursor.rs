@@ -0,0 +1,44 @@
+use crate::errors::ErrorCode;
+use crate::state::{EventQueue, Market, TradeCursor};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct OpenTradeCursor<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        constraint = market.is_event_queue(&event_queue.key()) @ ErrorCode::InvalidEventQueueShard,
+    )]
+    pub market: Account<'info, Market>,
+
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TradeCursor::INIT_SPACE,
+        seeds = [b"trade_cursor", event_queue.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub trade_cursor: Account<'info, TradeCursor>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl OpenTradeCursor<'_> {
+    /// Start a trade stream at the queue's current end: the first read
+    /// returns trades made after this call
+    pub fn apply(ctx: Context<OpenTradeCursor>) -> Result<()> {
+        let next_seq = ctx.accounts.event_queue.load()?.next_seq();
+
+        let cursor = &mut ctx.accounts.trade_cursor;
+        cursor.owner = ctx.accounts.owner.key();
+        cursor.event_queue = ctx.accounts.event_queue.key();
+        cursor.next_seq = next_seq;
+        cursor.bump = ctx.bumps.trade_cursor;
+
+        Ok(())
+    }
+}
@@ -1,14 +1,22 @@
 use crate::errors::ErrorCode;
-use crate::events::{OrderFilled, OrderPlaced};
+use crate::events::{
+    BalanceChanged, BookDelta, LevelChange, NativeOrderConverted, OrderCancelled, OrderComplete,
+    OrderFilled, OrderPlaced, BALANCE_REASON_ORDER_RESERVE, BALANCE_REASON_TAKER_FILL,
+};
+use crate::instructions::{AddEventQueueShard, LevelFill};
 use crate::state::{
-    AskSide, BidSide, EventQueue, FillEvent, Market, Order, OrderBook, Side, TimeInForce,
-    UserBalance,
+    AskOrderBook, AskSide, BidOrderBook, BidSide, EventQueue, FillEvent, FillStatus, Market, Order,
+    OrderBook, OrderStatus, SelfTradeBehavior, Side, TimeInForce, UserBalance,
+    FILL_EVENT_KIND_FILL, ORDER_FLAG_ALL_OR_NONE, ORDER_FLAG_SESSION,
 };
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token_interface::{TokenAccount, TokenInterface};
 
+// Shared by place_limit_order, place_limit_order_native, place_market_order
+// and modify_order, so no #[instruction] args: each takes differently
+// shaped params
 #[derive(Accounts)]
-#[instruction(params: PlaceLimitOrderParams)]
 pub struct PlaceLimitOrder<'info> {
     #[account(
         mut,
@@ -27,11 +35,16 @@ pub struct PlaceLimitOrder<'info> {
     #[account(mut)]
     pub event_queue: AccountLoader<'info, EventQueue>,
 
+    // The stored bump makes the seeds check a single create_program_address,
+    // so a caller-supplied bump would save nothing
     #[account(
         mut,
         seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
         bump = user_balance.bump,
-        constraint = user_balance.owner == user.key() @ ErrorCode::Unauthorized
+        constraint = user_balance.owner == user.key() @ ErrorCode::Unauthorized,
+        // Already implied by the seeds, but a balance debited for another
+        // market's book must never slip through a future seeds change
+        constraint = user_balance.market == market.key() @ ErrorCode::Unauthorized
     )]
     pub user_balance: Account<'info, UserBalance>,
 
@@ -47,42 +60,236 @@ pub struct PlaceLimitOrder<'info> {
     )]
     pub quote_vault: InterfaceAccount<'info, TokenAccount>,
 
+    // Pays the market's order bond when the order rests
+    #[account(mut)]
     pub user: Signer<'info>,
     pub base_token_program: Interface<'info, TokenInterface>,
     pub quote_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct PlaceLimitOrderParams {
-    pub side: Side,                 // Buy or Sell
-    pub price: u64,                 // Price in quote_tick_size units
-    pub quantity: u64,              // Quantity in base_lot_size units
-    pub time_in_force: TimeInForce, // Time in force type
+    pub side: Side,                    // Buy or Sell
+    pub price: u64,                    // Price in quote_tick_size units
+    pub quantity: u64,                 // Quantity in base_lot_size units
+    pub time_in_force: TimeInForce,    // Time in force type
+    pub all_or_none: bool,             // Any resting remainder only fills in full
+    pub session: bool,                 // Cancellable by anyone once the owner's session lapses
+    pub self_trade: SelfTradeBehavior, // On reaching the owner's own resting orders
+    pub client_order_id: u64,          // Caller's own id, for cancel_order_by_client_id; 0 = none
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlaceLimitOrderNativeParams {
+    pub side: Side,
+    pub price_native_quote_per_lot: u64, // Quote tokens paid for one lot; must be a whole tick
+    pub quantity_native_base: u64,       // Base tokens; must be a whole number of lots
+    pub time_in_force: TimeInForce,
+    pub all_or_none: bool,
+    pub session: bool,
+    pub self_trade: SelfTradeBehavior,
+    pub client_order_id: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlaceLimitOrderResult {
+    pub order_id: u64,         // Id assigned to the order
+    pub filled_quantity: u64,  // Lots filled on placement
+    pub resting_quantity: u64, // Lots left on the book, 0 unless it rests
+    pub status: OrderStatus,   // Same as the OrderComplete event
+    pub fill_status: FillStatus,
 }
 
 impl PlaceLimitOrder<'_> {
-    pub fn apply(ctx: Context<PlaceLimitOrder>, params: PlaceLimitOrderParams) -> Result<()> {
+    /// Convert raw token amounts to ticks and lots, then place as usual.
+    /// NativeOrderConverted is emitted first so indexers can pair the
+    /// submitted amounts with the order's usual events.
+    pub fn apply_native<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: PlaceLimitOrderNativeParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        let market = &ctx.accounts.market;
+        let price = market.price_from_native(params.price_native_quote_per_lot)?;
+        let quantity = market.lots_from_native(params.quantity_native_base)?;
+
+        emit!(NativeOrderConverted {
+            order_id: market.next_order_id,
+            owner: ctx.accounts.user.key(),
+            market: market.key(),
+            side: params.side,
+            price_native_quote_per_lot: params.price_native_quote_per_lot,
+            quantity_native_base: params.quantity_native_base,
+            price,
+            quantity,
+        });
+
+        Self::apply(
+            ctx,
+            PlaceLimitOrderParams {
+                side: params.side,
+                price,
+                quantity,
+                time_in_force: params.time_in_force,
+                all_or_none: params.all_or_none,
+                session: params.session,
+                self_trade: params.self_trade,
+                client_order_id: params.client_order_id,
+            },
+        )
+    }
+
+    /// Returns the assigned id and outcome as return data, so clients can
+    /// track the order without parsing logs. A sharded market's other event
+    /// queues come first in the remaining accounts.
+    pub fn apply<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: PlaceLimitOrderParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        let owner = ctx.accounts.user.key();
+        let event_queues = AddEventQueueShard::load_shards(
+            &ctx.accounts.market,
+            &ctx.accounts.event_queue,
+            ctx.remaining_accounts,
+        )?;
+        Self::place(
+            &mut ctx.accounts.market,
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            &event_queues,
+            &mut ctx.accounts.user_balance,
+            owner,
+            &ctx.accounts.user,
+            &ctx.accounts.system_program,
+            params,
+        )
+    }
+
+    /// The placement itself, for any caller that has established `owner`
+    /// authorised it: a signer here, a signed intent in place_signed_order.
+    /// `bond_payer` funds the order bond if the order rests.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn place<'info>(
+        market: &mut Account<'info, Market>,
+        bids: &AccountLoader<'info, BidSide>,
+        asks: &AccountLoader<'info, AskSide>,
+        event_queues: &[AccountLoader<'info, EventQueue>],
+        user_balance: &mut Account<'info, UserBalance>,
+        owner: Pubkey,
+        bond_payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+        params: PlaceLimitOrderParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        let (placed, _) = Self::place_order(
+            market,
+            bids,
+            asks,
+            event_queues,
+            user_balance,
+            owner,
+            bond_payer,
+            system_program,
+            params,
+            true,
+        )?;
+        Ok(placed)
+    }
+
+    /// `place`, optionally without requiring the balance to cover the whole
+    /// quantity at the limit price up front. Only for IOC orders sized from
+    /// the book, whose fills are each debited as they happen. Also returns
+    /// the fills summed per price level, in the order the book was walked.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn place_order<'info>(
+        market: &mut Account<'info, Market>,
+        bids: &AccountLoader<'info, BidSide>,
+        asks: &AccountLoader<'info, AskSide>,
+        event_queues: &[AccountLoader<'info, EventQueue>],
+        user_balance: &mut Account<'info, UserBalance>,
+        owner: Pubkey,
+        bond_payer: &Signer<'info>,
+        system_program: &Program<'info, System>,
+        params: PlaceLimitOrderParams,
+        check_cover: bool,
+    ) -> Result<(PlaceLimitOrderResult, Vec<LevelFill>)> {
         // Enhanced parameter validation
         require!(params.price > 0, ErrorCode::InvalidPrice);
         require!(params.quantity > 0, ErrorCode::InvalidOrderSize);
+        require!(!market.is_closing(), ErrorCode::MarketClosing);
+        require!(!market.is_side_halted(params.side), ErrorCode::SideHalted);
+
+        let mut asks = asks.load_mut()?;
+        let mut bids = bids.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+
+        // A post-only order must not take even a lot, so any opposite order
+        // at or through its price refuses it before anything is touched
+        if params.time_in_force == TimeInForce::PostOnly {
+            let crosses = match params.side {
+                Side::Bid => asks
+                    .orderbook
+                    .peek()
+                    .is_some_and(|best| AskOrderBook::crosses(best.price, params.price)),
+                Side::Ask => bids
+                    .orderbook
+                    .peek()
+                    .is_some_and(|best| BidOrderBook::crosses(best.price, params.price)),
+            };
+            require!(!crosses, ErrorCode::PostOnlyWouldCross);
+        }
+
+        // Cancelling by client id looks the order up per owner and side, so
+        // one owner's resting orders there must not share a client id
+        if params.client_order_id != 0 {
+            let taken = match params.side {
+                Side::Bid => bids
+                    .orderbook
+                    .order_id_for_client_id(&owner, params.client_order_id),
+                Side::Ask => asks
+                    .orderbook
+                    .order_id_for_client_id(&owner, params.client_order_id),
+            };
+            require!(taken.is_none(), ErrorCode::DuplicateClientOrderId);
+        }
 
-        let mut asks = ctx.accounts.asks.load_mut()?;
-        let mut bids = ctx.accounts.bids.load_mut()?;
+        // Each crossing order is at most one fill or one cancellation, and
+        // every fill takes at least a lot, so this bounds the events matching
+        // can push. Reject now instead of running out of queue halfway
+        // through. Any shard could receive all of them, so each needs the room.
+        let crossing = match params.side {
+            Side::Bid => asks.orderbook.crossing_order_count(params.price),
+            Side::Ask => bids.orderbook.crossing_order_count(params.price),
+        };
+        // Cancelling the taker's own makers takes none of its quantity
+        let worst_case_events = match params.self_trade {
+            SelfTradeBehavior::CancelProvide => crossing as u64,
+            _ => (crossing as u64).min(params.quantity),
+        };
+        for event_queue in event_queues.iter() {
+            require!(
+                event_queue.load()?.free_slots() >= worst_case_events,
+                ErrorCode::EventQueueFull
+            );
+        }
+        // An order that would not match still rests while the crank is behind
+        if crossing > 0 {
+            market.check_settlement_backlog(event_queues, Clock::get()?.unix_timestamp)?;
+        }
 
-        let market = &mut ctx.accounts.market;
-        let user_balance = &mut ctx.accounts.user_balance;
+        user_balance
+            .record_order_placement(Clock::get()?.slot, market.max_orders_per_slot_per_user)?;
+        // Otherwise the order could be swept the moment it rests
+        require!(
+            !params.session || user_balance.session_active(Clock::get()?.unix_timestamp),
+            ErrorCode::SessionNotActive
+        );
 
         // Check if user has sufficient balance
         match params.side {
+            _ if !check_cover => {}
             Side::Bid => {
-                let required_quote = params
-                    .price
-                    .checked_mul(params.quantity)
-                    .ok_or(ErrorCode::MathOverflow)?
-                    .checked_mul(market.quote_tick_size)
-                    .ok_or(ErrorCode::MathOverflow)?
-                    .checked_div(market.base_lot_size)
-                    .ok_or(ErrorCode::MathOverflow)?;
+                let required_quote = market.quote_amount(params.price, params.quantity)?;
 
                 require!(
                     user_balance.quote_balance >= required_quote,
@@ -90,10 +297,7 @@ impl PlaceLimitOrder<'_> {
                 );
             }
             Side::Ask => {
-                let required_base = params
-                    .quantity
-                    .checked_mul(market.base_lot_size)
-                    .ok_or(ErrorCode::MathOverflow)?;
+                let required_base = market.base_amount(params.quantity)?;
 
                 require!(
                     user_balance.base_balance >= required_base,
@@ -102,14 +306,25 @@ impl PlaceLimitOrder<'_> {
             }
         }
 
+        let mut flags = 0;
+        if params.all_or_none {
+            flags |= ORDER_FLAG_ALL_OR_NONE;
+        }
+        if params.session {
+            flags |= ORDER_FLAG_SESSION;
+        }
+
         // Create new order
         let mut new_order = Order {
             order_id: market.next_order_id,
-            owner: ctx.accounts.user.key(),
+            owner,
             price: params.price,
             quantity: params.quantity,
             remaining_quantity: params.quantity,
             timestamp: Clock::get()?.unix_timestamp,
+            flags,
+            _padding: [0; 7],
+            client_order_id: params.client_order_id,
         };
 
         // Increment order ID counter
@@ -118,40 +333,63 @@ impl PlaceLimitOrder<'_> {
             .checked_add(1)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        // Match against opposite side orderbook
+        // Match against opposite side orderbook. Under CancelProvide the
+        // owner's own crossing orders are taken off the book instead
+        let mut cancelled = Vec::new();
         let fills = match params.side {
-            Side::Bid => asks.orderbook.match_orders(&mut new_order)?,
-            Side::Ask => bids.orderbook.match_orders(&mut new_order)?,
+            Side::Bid => asks.orderbook.match_orders_evicting(
+                &mut new_order,
+                &mut cancelled,
+                Some(params.self_trade),
+            )?,
+            Side::Ask => bids.orderbook.match_orders_evicting(
+                &mut new_order,
+                &mut cancelled,
+                Some(params.self_trade),
+            )?,
         };
+        // Not params.quantity - remaining: DecrementTake drops quantity
+        // without filling it
+        let filled_quantity: u64 = fills.iter().map(|fill| fill.quantity).sum();
+        if let Some(fill) = fills.last() {
+            market.last_trade_price = fill.price;
+        }
 
         // Handle Fill-Or-Kill (FOK): if order wasn't completely filled, reject it
-        if params.time_in_force == TimeInForce::FOK && new_order.remaining_quantity > 0 {
+        if params.time_in_force == TimeInForce::FOK && filled_quantity < params.quantity {
             return Err(ErrorCode::FillOrKillNotFilled.into());
         }
 
         // Process fills: update taker balance immediately, queue events for maker balance updates
+        let mut fill_amounts = Vec::with_capacity(fills.len());
         for fill in fills.iter() {
-            let fill_base_amount = fill
-                .quantity
-                .checked_mul(market.base_lot_size)
-                .ok_or(ErrorCode::MathOverflow)?;
-
-            let fill_quote_amount = fill
-                .price
-                .checked_mul(fill.quantity)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_mul(market.quote_tick_size)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(market.base_lot_size)
-                .ok_or(ErrorCode::MathOverflow)?;
+            let fill_base_amount = market.base_amount(fill.quantity)?;
+
+            let fill_quote_amount = market.fill_quote_amount(
+                fill.maker_side,
+                fill.price,
+                fill.quantity,
+                fill.maker_remaining_quantity,
+            )?;
+
+            // The taker fee comes out of the asset the taker receives
+            let fee = match params.side {
+                Side::Bid => market.taker_fee(fill_base_amount)?,
+                Side::Ask => market.taker_fee(fill_quote_amount)?,
+            };
+            market.accrue_fee(params.side, fee)?;
+            fill_amounts.push((fill_base_amount, fill_quote_amount, fee));
 
             // 1. Immediately update taker balance
             match params.side {
                 Side::Bid => {
                     // Taker is bidding: receive base, pay quote
+                    let net_base = fill_base_amount
+                        .checked_sub(fee)
+                        .ok_or(ErrorCode::MathOverflow)?;
                     user_balance.base_balance = user_balance
                         .base_balance
-                        .checked_add(fill_base_amount)
+                        .checked_add(net_base)
                         .ok_or(ErrorCode::MathOverflow)?;
 
                     user_balance.quote_balance = user_balance
@@ -166,15 +404,20 @@ impl PlaceLimitOrder<'_> {
                         .checked_sub(fill_base_amount)
                         .ok_or(ErrorCode::InsufficientBalance)?;
 
+                    let net_quote = fill_quote_amount
+                        .checked_sub(fee)
+                        .ok_or(ErrorCode::MathOverflow)?;
                     user_balance.quote_balance = user_balance
                         .quote_balance
-                        .checked_add(fill_quote_amount)
+                        .checked_add(net_quote)
                         .ok_or(ErrorCode::MathOverflow)?;
                 }
             }
+            user_balance.record_fill(params.side, fill_base_amount, fill_quote_amount);
 
-            // 2. Push fill event to queue for maker balance processing
-            let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+            // 2. Push fill event to the maker's queue shard for maker balance processing
+            let shard = market.event_queue_shard(&fill.maker_owner);
+            let mut event_queue = event_queues[shard].load_mut()?;
             let fill_event = FillEvent {
                 maker_order_id: fill.maker_order_id,
                 taker_order_id: fill.taker_order_id,
@@ -182,43 +425,74 @@ impl PlaceLimitOrder<'_> {
                 quantity: fill.quantity,
                 timestamp: Clock::get()?.unix_timestamp,
                 maker_owner: fill.maker_owner,
-                taker_owner: ctx.accounts.user.key(),
+                taker_owner: owner,
                 market: market.key(),
                 maker_side: match fill.maker_side {
                     Side::Bid => 0,
                     Side::Ask => 1,
                 },
-                _padding: [0; 7],
+                kind: FILL_EVENT_KIND_FILL,
+                maker_order_closed: fill.maker_order_closed as u8,
+                retry_count: 0,
+                _padding: [0; 4],
             };
             event_queue.push_event(fill_event)?;
+        }
 
-            // 3. Emit fill event
-            emit!(OrderFilled {
-                maker_order_id: fill.maker_order_id,
-                taker_order_id: fill.taker_order_id,
-                market: market.key(),
-                price: fill.price,
-                quantity: fill.quantity,
-                maker_owner: fill.maker_owner,
-                taker_owner: ctx.accounts.user.key(),
-                taker_side: params.side,
-            });
+        // Cancelled orders are refunded through the queue like clear_book's
+        let maker_side = match params.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let timestamp = Clock::get()?.unix_timestamp;
+        for order in cancelled.iter() {
+            let refund = market.reserved_amount(maker_side, order)?;
+            let bond = market.order_bond_lamports;
+            let shard = market.event_queue_shard(&order.owner);
+            event_queues[shard]
+                .load_mut()?
+                .push_event(FillEvent::refund(
+                    order,
+                    maker_side,
+                    refund,
+                    bond,
+                    market.key(),
+                    timestamp,
+                ))?;
         }
 
         // If order still has remaining quantity, add to appropriate orderbook
         // But skip for IOC (Immediate-Or-Cancel) orders - they should not rest in the orderbook
-        if new_order.remaining_quantity > 0 && params.time_in_force != TimeInForce::IOC {
+        let rests = new_order.remaining_quantity > 0 && params.time_in_force != TimeInForce::IOC;
+        if rests && !market.allow_locked_book {
+            // Matching only stops short of the opposite best when it skipped
+            // all-or-none makers there; resting would then lock or cross
+            let locks = match params.side {
+                Side::Bid => asks
+                    .orderbook
+                    .peek()
+                    .is_some_and(|best| AskOrderBook::crosses(best.price, new_order.price)),
+                Side::Ask => bids
+                    .orderbook
+                    .peek()
+                    .is_some_and(|best| BidOrderBook::crosses(best.price, new_order.price)),
+            };
+            require!(!locks, ErrorCode::BookWouldLock);
+        }
+        if rests {
+            let resting_at_level = match params.side {
+                Side::Bid => bids.orderbook.level_order_count(new_order.price),
+                Side::Ask => asks.orderbook.level_order_count(new_order.price),
+            };
+            market.check_level_room(resting_at_level)?;
+        }
+        let mut reserve_deltas = (0, 0);
+        if rests {
             // Reserve required balance for the remaining order
-            match params.side {
+            reserve_deltas = match params.side {
                 Side::Bid => {
-                    let required_quote = new_order
-                        .price
-                        .checked_mul(new_order.remaining_quantity)
-                        .ok_or(ErrorCode::MathOverflow)?
-                        .checked_mul(market.quote_tick_size)
-                        .ok_or(ErrorCode::MathOverflow)?
-                        .checked_div(market.base_lot_size)
-                        .ok_or(ErrorCode::MathOverflow)?;
+                    let required_quote =
+                        market.quote_amount(new_order.price, new_order.remaining_quantity)?;
 
                     user_balance.quote_balance = user_balance
                         .quote_balance
@@ -226,12 +500,10 @@ impl PlaceLimitOrder<'_> {
                         .ok_or(ErrorCode::InsufficientBalance)?;
 
                     bids.orderbook.insert_order(new_order)?;
+                    (0, BalanceChanged::debit(required_quote)?)
                 }
                 Side::Ask => {
-                    let required_base = new_order
-                        .remaining_quantity
-                        .checked_mul(market.base_lot_size)
-                        .ok_or(ErrorCode::MathOverflow)?;
+                    let required_base = market.base_amount(new_order.remaining_quantity)?;
 
                     user_balance.base_balance = user_balance
                         .base_balance
@@ -239,21 +511,180 @@ impl PlaceLimitOrder<'_> {
                         .ok_or(ErrorCode::InsufficientBalance)?;
 
                     asks.orderbook.insert_order(new_order)?;
+                    (BalanceChanged::debit(required_base)?, 0)
                 }
+            };
+            user_balance.record_order_opened();
+            // Held on the market until the order closes; orders that never
+            // rest pay nothing
+            if market.order_bond_lamports > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: bond_payer.to_account_info(),
+                            to: market.to_account_info(),
+                        },
+                    ),
+                    market.order_bond_lamports,
+                )?;
+            }
+        }
+
+        // Every opposite-side level a fill touched, then the level the
+        // remainder joined
+        let mut level_changes: Vec<LevelChange> = Vec::new();
+        let touched_prices = fills
+            .iter()
+            .map(|fill| fill.price)
+            .chain(cancelled.iter().map(|order| order.price));
+        for price in touched_prices {
+            if level_changes.iter().any(|change| change.price == price) {
+                continue;
             }
+            let (side, new_total_quantity) = match params.side {
+                Side::Bid => (Side::Ask, asks.orderbook.level_quantity(price)),
+                Side::Ask => (Side::Bid, bids.orderbook.level_quantity(price)),
+            };
+            level_changes.push(LevelChange {
+                side,
+                price,
+                new_total_quantity,
+            });
+        }
+        if rests {
+            let new_total_quantity = match params.side {
+                Side::Bid => bids.orderbook.level_quantity(new_order.price),
+                Side::Ask => asks.orderbook.level_quantity(new_order.price),
+            };
+            level_changes.push(LevelChange {
+                side: params.side,
+                price: new_order.price,
+                new_total_quantity,
+            });
+        }
+        let book_delta = if level_changes.is_empty() {
+            None
+        } else {
+            Some(BookDelta::new(
+                market.key(),
+                market.next_book_seq_num()?,
+                level_changes,
+                bids.orderbook.book_hash(),
+                asks.orderbook.book_hash(),
+            ))
+        };
+
+        // Events are emitted only after all state changes, in a fixed order:
+        // an OrderCancelled per cancelled order, every OrderFilled in match
+        // order, then OrderPlaced if a remainder rests, then BookDelta if the
+        // book changed, then exactly one OrderComplete as the terminal event.
+        // Each OrderFilled and the OrderPlaced are followed by the taker's
+        // BalanceChanged.
+        for order in cancelled.iter() {
+            emit!(OrderCancelled {
+                order_id: order.order_id,
+                owner: order.owner,
+                market: market.key(),
+                side: maker_side,
+                remaining_quantity: order.remaining_quantity,
+            });
+        }
+        for (fill, (fill_base_amount, gross_quote, fee)) in fills.iter().zip(fill_amounts) {
+            let (fee_base, fee_quote) = match params.side {
+                Side::Bid => (fee, 0),
+                Side::Ask => (0, fee),
+            };
+            emit!(OrderFilled {
+                maker_order_id: fill.maker_order_id,
+                taker_order_id: fill.taker_order_id,
+                market: market.key(),
+                price: fill.price,
+                quantity: fill.quantity,
+                maker_owner: fill.maker_owner,
+                taker_owner: owner,
+                taker_side: params.side,
+                gross_quote,
+                fee_base,
+                fee_quote,
+                net_quote: gross_quote - fee_quote,
+            });
+            let (base_delta, quote_delta) = match params.side {
+                Side::Bid => (
+                    BalanceChanged::credit(fill_base_amount - fee_base)?,
+                    BalanceChanged::debit(gross_quote)?,
+                ),
+                Side::Ask => (
+                    BalanceChanged::debit(fill_base_amount)?,
+                    BalanceChanged::credit(gross_quote - fee_quote)?,
+                ),
+            };
+            emit!(BalanceChanged {
+                user: owner,
+                market: market.key(),
+                base_delta,
+                quote_delta,
+                reason: BALANCE_REASON_TAKER_FILL,
+                ref_id: new_order.order_id,
+            });
+        }
 
-            // Emit order placed event for remaining quantity
+        if rests {
             emit!(OrderPlaced {
                 order_id: new_order.order_id,
-                owner: ctx.accounts.user.key(),
+                owner,
                 market: market.key(),
                 side: params.side,
                 price: new_order.price,
                 quantity: new_order.remaining_quantity,
                 timestamp: new_order.timestamp,
+                client_order_id: new_order.client_order_id,
+            });
+            emit!(BalanceChanged {
+                user: owner,
+                market: market.key(),
+                base_delta: reserve_deltas.0,
+                quote_delta: reserve_deltas.1,
+                reason: BALANCE_REASON_ORDER_RESERVE,
+                ref_id: new_order.order_id,
             });
         }
 
-        Ok(())
+        if let Some(book_delta) = book_delta {
+            emit!(book_delta);
+        }
+
+        let resting_quantity = if rests {
+            new_order.remaining_quantity
+        } else {
+            0
+        };
+        let status = if filled_quantity == params.quantity {
+            OrderStatus::Filled
+        } else if !rests {
+            OrderStatus::Cancelled
+        } else if filled_quantity > 0 {
+            OrderStatus::PartiallyFilled
+        } else {
+            OrderStatus::Resting
+        };
+        emit!(OrderComplete {
+            order_id: new_order.order_id,
+            owner,
+            market: market.key(),
+            side: params.side,
+            filled_quantity,
+            resting_quantity,
+            status,
+        });
+
+        let placed = PlaceLimitOrderResult {
+            order_id: new_order.order_id,
+            filled_quantity,
+            resting_quantity,
+            status,
+            fill_status: FillStatus::new(filled_quantity, params.quantity - filled_quantity, rests),
+        };
+        Ok((placed, LevelFill::from_fills(&fills)))
     }
 }
@@ -0,0 +1,161 @@
+use crate::errors::ErrorCode;
+use crate::events::MarketOrderComplete;
+use crate::instructions::{AddEventQueueShard, PlaceLimitOrder, PlaceLimitOrderParams, QuoteOrder};
+use crate::state::{Fill, SelfTradeBehavior, Side, TimeInForce};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlaceMarketOrderParams {
+    pub side: Side,
+    pub quantity: u64, // Most lots to take, in base_lot_size units
+    pub max_quote_to_spend: Option<u64>, // Bids only: cap on quote tokens spent
+}
+
+/// Most price levels a market order result lists; keeps it inside the 1024
+/// bytes of return data
+pub const MAX_LEVEL_FILLS: usize = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LevelFill {
+    pub price: u64,    // Price in quote_tick_size units
+    pub quantity: u64, // Lots filled at this price
+}
+
+impl LevelFill {
+    /// Sum `fills` per price. Matching walks the book a level at a time, so
+    /// fills at one price are always adjacent.
+    pub fn from_fills(fills: &[Fill]) -> Vec<LevelFill> {
+        let mut levels: Vec<LevelFill> = Vec::new();
+        for fill in fills {
+            match levels.last_mut() {
+                Some(level) if level.price == fill.price => level.quantity += fill.quantity,
+                _ => levels.push(LevelFill {
+                    price: fill.price,
+                    quantity: fill.quantity,
+                }),
+            }
+        }
+        levels
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlaceMarketOrderResult {
+    pub order_id: u64,          // IOC order the sweep placed; 0 if nothing could fill
+    pub filled_quantity: u64,   // Lots filled
+    pub quote_amount: u64,      // Quote tokens paid (bid) or received (ask)
+    pub avg_price: u64,         // Quote per lot actually exchanged, in ticks, rounded down
+    pub levels: Vec<LevelFill>, // Lots filled at each level, best price first
+    pub levels_truncated: bool, // Levels past MAX_LEVEL_FILLS were left out
+}
+
+impl PlaceLimitOrder<'_> {
+    /// Take liquidity at any price: sweep the opposite book level by level
+    /// until `quantity` is filled, the book runs out, or for bids
+    /// `max_quote_to_spend` would be exceeded. Nothing rests. The sweep is
+    /// sized the way quote_order prices it, then placed as an IOC at the last
+    /// level it reaches, so fills are exactly a limit order's. Each fill is
+    /// debited as it happens; if the free balance runs out partway the whole
+    /// order fails with InsufficientBalance rather than filling in part.
+    pub fn apply_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: PlaceMarketOrderParams,
+    ) -> Result<PlaceMarketOrderResult> {
+        require!(params.quantity > 0, ErrorCode::InvalidOrderSize);
+        require!(
+            params.max_quote_to_spend.is_none() || params.side == Side::Bid,
+            ErrorCode::InvalidParameter
+        );
+
+        let owner = ctx.accounts.user.key();
+        let (maker_side, resting) = match params.side {
+            Side::Bid => (
+                Side::Ask,
+                ctx.accounts
+                    .asks
+                    .load()?
+                    .orderbook
+                    .crossing_orders(u64::MAX),
+            ),
+            Side::Ask => (
+                Side::Bid,
+                ctx.accounts.bids.load()?.orderbook.crossing_orders(1),
+            ),
+        };
+        let sweep = QuoteOrder::simulate(
+            &ctx.accounts.market,
+            maker_side,
+            resting,
+            params.quantity,
+            params.max_quote_to_spend,
+        )?;
+
+        let mut result = PlaceMarketOrderResult {
+            order_id: 0,
+            filled_quantity: 0,
+            quote_amount: 0,
+            avg_price: 0,
+            levels: Vec::new(),
+            levels_truncated: false,
+        };
+        if sweep.fillable_base > 0 {
+            let quote_before = ctx.accounts.user_balance.quote_balance;
+            let event_queues = AddEventQueueShard::load_shards(
+                &ctx.accounts.market,
+                &ctx.accounts.event_queue,
+                ctx.remaining_accounts,
+            )?;
+            // The usual up-front check would price every lot at the worst
+            // level; the fills themselves debit what each level costs
+            let (placed, mut levels) = Self::place_order(
+                &mut ctx.accounts.market,
+                &ctx.accounts.bids,
+                &ctx.accounts.asks,
+                &event_queues,
+                &mut ctx.accounts.user_balance,
+                owner,
+                &ctx.accounts.user,
+                &ctx.accounts.system_program,
+                PlaceLimitOrderParams {
+                    side: params.side,
+                    price: sweep.worst_price,
+                    quantity: sweep.fillable_base,
+                    time_in_force: TimeInForce::IOC,
+                    all_or_none: false,
+                    session: false,
+                    self_trade: SelfTradeBehavior::default(),
+                    client_order_id: 0,
+                },
+                false,
+            )?;
+            let quote_after = ctx.accounts.user_balance.quote_balance;
+
+            result.order_id = placed.order_id;
+            result.filled_quantity = placed.filled_quantity;
+            result.quote_amount = quote_before.abs_diff(quote_after);
+            result.levels_truncated = levels.len() > MAX_LEVEL_FILLS;
+            levels.truncate(MAX_LEVEL_FILLS);
+            result.levels = levels;
+            if placed.filled_quantity > 0 {
+                let market = &ctx.accounts.market;
+                let avg_price = result.quote_amount as u128 * market.base_lot_size as u128
+                    / (placed.filled_quantity as u128 * market.quote_tick_size as u128);
+                result.avg_price =
+                    u64::try_from(avg_price).map_err(|_| error!(ErrorCode::MathOverflow))?;
+            }
+        }
+
+        emit!(MarketOrderComplete {
+            order_id: result.order_id,
+            owner,
+            market: ctx.accounts.market.key(),
+            side: params.side,
+            requested_quantity: params.quantity,
+            filled_quantity: result.filled_quantity,
+            quote_amount: result.quote_amount,
+            avg_price: result.avg_price,
+        });
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::errors::ErrorCode;
+use crate::events::SignedIntentExecuted;
+use crate::instructions::{
+    AddEventQueueShard, PlaceLimitOrder, PlaceLimitOrderParams, PlaceLimitOrderResult,
+};
+use crate::state::{
+    verify_ed25519_signature, AskSide, BidSide, EventQueue, Market, OrderIntent, SelfTradeBehavior,
+    UserBalance,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+
+#[derive(Accounts)]
+pub struct PlaceSignedOrder<'info> {
+    // Submits and pays for the transaction, and any order bond; gains no
+    // authority over the order
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: the trader; authorised by the ed25519 signature over the intent
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", owner.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+
+    /// CHECK: the instructions sysvar, to find the ed25519 instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlaceSignedOrderParams {
+    pub intent: OrderIntent, // Signed by owner in the preceding ed25519 instruction
+}
+
+impl PlaceSignedOrder<'_> {
+    // Lets a trader with no SOL quote through a relayer. The transaction must
+    // carry an ed25519 program instruction, immediately before this one,
+    // verifying the owner's signature over intent.message(). Past that the
+    // order goes through place_limit_order's path on the owner's balance.
+    pub fn apply<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceSignedOrder<'info>>,
+        params: PlaceSignedOrderParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        let intent = params.intent;
+        let owner = ctx.accounts.owner.key();
+        require_keys_eq!(
+            intent.market,
+            ctx.accounts.market.key(),
+            ErrorCode::InvalidParameter
+        );
+        require!(
+            Clock::get()?.unix_timestamp <= intent.expiry,
+            ErrorCode::IntentExpired
+        );
+        verify_ed25519_signature(&ctx.accounts.instructions, &owner, &intent.message())?;
+        ctx.accounts.user_balance.use_intent_nonce(intent.nonce)?;
+
+        let event_queues = AddEventQueueShard::load_shards(
+            &ctx.accounts.market,
+            &ctx.accounts.event_queue,
+            ctx.remaining_accounts,
+        )?;
+        let result = PlaceLimitOrder::place(
+            &mut ctx.accounts.market,
+            &ctx.accounts.bids,
+            &ctx.accounts.asks,
+            &event_queues,
+            &mut ctx.accounts.user_balance,
+            owner,
+            &ctx.accounts.relayer,
+            &ctx.accounts.system_program,
+            PlaceLimitOrderParams {
+                side: intent.side,
+                price: intent.price,
+                quantity: intent.quantity,
+                time_in_force: intent.time_in_force,
+                all_or_none: false,
+                session: false,
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id: intent.client_order_id,
+            },
+        )?;
+
+        emit!(SignedIntentExecuted {
+            owner,
+            market: ctx.accounts.market.key(),
+            relayer: ctx.accounts.relayer.key(),
+            nonce: intent.nonce,
+            client_order_id: intent.client_order_id,
+            order_id: result.order_id,
+        });
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::errors::ErrorCode;
+use crate::events::TwapOrderPlaced;
+use crate::state::{Market, Side, TwapOrder, TwapStatus, UserBalance};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+#[instruction(params: PlaceTwapOrderParams)]
+pub struct PlaceTwapOrder<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TwapOrder::INIT_SPACE,
+        seeds = [
+            b"twap",
+            market.key().as_ref(),
+            owner.key().as_ref(),
+            &params.twap_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub twap_order: Account<'info, TwapOrder>,
+
+    // Slices trade against this balance
+    #[account(
+        seeds = [b"user_balance", owner.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+
+    // Pays the account's rent and the executors' reward escrow
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlaceTwapOrderParams {
+    pub twap_id: u64, // Distinguishes the owner's TWAP orders on one market
+    pub side: Side,
+    pub limit_price: u64,     // Price in quote_tick_size units
+    pub total_quantity: u64,  // Lots to fill over the whole schedule
+    pub slice_quantity: u64,  // Lots each slice offers at most
+    pub interval_secs: u32,   // Least time between two slices
+    pub expiry: i64,          // No slices after this unix timestamp; 0 = none
+    pub reward_lamports: u64, // Paid per slice to whoever executes it; 0 = none
+}
+
+impl PlaceTwapOrder<'_> {
+    /// Create the parent order; its first slice is due at once. The reward
+    /// for every slice a fully filling schedule takes is escrowed up front
+    /// and whatever is left returns to the owner on close.
+    pub fn apply(ctx: Context<PlaceTwapOrder>, params: PlaceTwapOrderParams) -> Result<()> {
+        require!(params.limit_price > 0, ErrorCode::InvalidPrice);
+        require!(
+            params.slice_quantity > 0 && params.slice_quantity <= params.total_quantity,
+            ErrorCode::InvalidOrderSize
+        );
+        require!(params.interval_secs > 0, ErrorCode::InvalidParameter);
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            params.expiry == 0 || params.expiry > now,
+            ErrorCode::InvalidParameter
+        );
+        require!(!ctx.accounts.market.is_closing(), ErrorCode::MarketClosing);
+
+        let twap = &mut ctx.accounts.twap_order;
+        twap.owner = ctx.accounts.owner.key();
+        twap.market = ctx.accounts.market.key();
+        twap.twap_id = params.twap_id;
+        twap.side = params.side;
+        twap.limit_price = params.limit_price;
+        twap.total_quantity = params.total_quantity;
+        twap.slice_quantity = params.slice_quantity;
+        twap.interval_secs = params.interval_secs;
+        twap.expiry = params.expiry;
+        twap.filled_quantity = 0;
+        twap.slices_executed = 0;
+        twap.next_slice_at = now;
+        twap.reward_lamports = params.reward_lamports;
+        twap.status = TwapStatus::Active;
+        twap.bump = ctx.bumps.twap_order;
+
+        let escrow = params
+            .reward_lamports
+            .checked_mul(twap.planned_slices()?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if escrow > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: twap.to_account_info(),
+                    },
+                ),
+                escrow,
+            )?;
+        }
+
+        emit!(TwapOrderPlaced {
+            twap: twap.key(),
+            owner: twap.owner,
+            market: twap.market,
+            side: twap.side,
+            limit_price: twap.limit_price,
+            total_quantity: twap.total_quantity,
+            slice_quantity: twap.slice_quantity,
+            interval_secs: twap.interval_secs,
+            expiry: twap.expiry,
+        });
+
+        Ok(())
+    }
+}
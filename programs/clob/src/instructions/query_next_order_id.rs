@@ -0,0 +1,19 @@
+use crate::state::Market;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QueryNextOrderId<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+impl QueryNextOrderId<'_> {
+    /// The id the market's next placement will be assigned, via return data.
+    /// A fallback for clients: place_limit_order returns the id it assigned.
+    pub fn apply(ctx: Context<QueryNextOrderId>) -> Result<u64> {
+        Ok(ctx.accounts.market.next_order_id)
+    }
+}
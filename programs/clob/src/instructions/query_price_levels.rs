@@ -0,0 +1,53 @@
+use crate::state::{AskSide, BidSide, Market, Side};
+use anchor_lang::prelude::*;
+
+/// Most prices one page lists; keeps it inside the 1024 bytes of return data
+pub const MAX_PRICE_LEVELS_PER_PAGE: usize = 100;
+
+#[derive(Accounts)]
+pub struct QueryPriceLevels<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    pub bids: AccountLoader<'info, BidSide>,
+    pub asks: AccountLoader<'info, AskSide>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QueryPriceLevelsParams {
+    pub side: Side,
+    pub after_price: u64, // List prices above this; 0 starts from the lowest
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PriceLevelsPage {
+    pub prices: Vec<u64>, // Prices with resting orders, ascending
+    pub has_more: bool,   // Call again with after_price = the last price listed
+}
+
+impl QueryPriceLevels<'_> {
+    /// One page of the prices `side` has liquidity at, via return data, so
+    /// a client can draw the price grid without fetching the book account
+    pub fn apply(
+        ctx: Context<QueryPriceLevels>,
+        params: QueryPriceLevelsParams,
+    ) -> Result<PriceLevelsPage> {
+        let levels = match params.side {
+            Side::Bid => ctx.accounts.bids.load()?.orderbook.distinct_price_levels(),
+            Side::Ask => ctx.accounts.asks.load()?.orderbook.distinct_price_levels(),
+        };
+        let mut prices: Vec<u64> = levels
+            .into_iter()
+            .filter(|&price| price > params.after_price)
+            .collect();
+        let has_more = prices.len() > MAX_PRICE_LEVELS_PER_PAGE;
+        prices.truncate(MAX_PRICE_LEVELS_PER_PAGE);
+
+        Ok(PriceLevelsPage { prices, has_more })
+    }
+}
@@ -0,0 +1,24 @@
+use crate::errors::ErrorCode;
+use crate::state::{EventQueue, Market};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QueryTotalEventsPushed<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        constraint = market.is_event_queue(&event_queue.key()) @ ErrorCode::InvalidEventQueueShard,
+    )]
+    pub market: Account<'info, Market>,
+
+    pub event_queue: AccountLoader<'info, EventQueue>,
+}
+
+impl QueryTotalEventsPushed<'_> {
+    /// How many events the queue has taken over its lifetime, via return data.
+    /// Sampled twice it gives throughput; against consumed events it shows
+    /// whether any were lost. Events the crank requeued count once.
+    pub fn apply(ctx: Context<QueryTotalEventsPushed>) -> Result<u64> {
+        Ok(ctx.accounts.event_queue.load()?.total_events_pushed)
+    }
+}
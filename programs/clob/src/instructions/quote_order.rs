@@ -0,0 +1,143 @@
+use crate::errors::ErrorCode;
+use crate::state::{AskSide, BidSide, Market, Order, Side};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct QuoteOrder<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+    )]
+    pub market: Account<'info, Market>,
+
+    // Deliberately not mut: quoting must never write to the books
+    pub bids: AccountLoader<'info, BidSide>,
+    pub asks: AccountLoader<'info, AskSide>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuoteOrderParams {
+    pub side: Side,                // Side of the hypothetical taker order
+    pub price: u64,                // Limit price in quote_tick_size units
+    pub quantity: u64,             // Quantity in base_lot_size units
+    pub quote_budget: Option<u64>, // Bids only: cap on quote tokens spent
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuoteOrderResult {
+    pub fillable_base: u64,  // Fillable quantity in base_lot_size units
+    pub quote_required: u64, // Quote tokens paid (bid) or received (ask) across all fills
+    pub worst_price: u64,    // Last price level reached, 0 if nothing fills
+    pub avg_price: u64,      // Quantity-weighted average fill price, rounded down
+    pub levels_touched: u32, // Distinct price levels filled against
+}
+
+impl QuoteOrder<'_> {
+    /// Dry-run of a taker order against the current book, returned via return
+    /// data. Intended for simulateTransaction; the result matches what an
+    /// identical place_limit_order would fill in the same slot.
+    pub fn apply(ctx: Context<QuoteOrder>, params: QuoteOrderParams) -> Result<QuoteOrderResult> {
+        require!(params.price > 0, ErrorCode::InvalidPrice);
+        require!(params.quantity > 0, ErrorCode::InvalidOrderSize);
+        require!(
+            params.quote_budget.is_none() || params.side == Side::Bid,
+            ErrorCode::InvalidParameter
+        );
+
+        let market = &ctx.accounts.market;
+        let maker_side = match params.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let resting = match params.side {
+            Side::Bid => ctx
+                .accounts
+                .asks
+                .load()?
+                .orderbook
+                .crossing_orders(params.price),
+            Side::Ask => ctx
+                .accounts
+                .bids
+                .load()?
+                .orderbook
+                .crossing_orders(params.price),
+        };
+
+        Self::simulate(
+            market,
+            maker_side,
+            resting,
+            params.quantity,
+            params.quote_budget,
+        )
+    }
+
+    /// Walk `resting` (crossing `maker_side` orders in match order) as a
+    /// taker for up to `quantity` lots, optionally capped by a quote budget
+    pub(crate) fn simulate(
+        market: &Market,
+        maker_side: Side,
+        resting: Vec<Order>,
+        quantity: u64,
+        quote_budget: Option<u64>,
+    ) -> Result<QuoteOrderResult> {
+        let mut result = QuoteOrderResult::default();
+        let mut remaining = quantity;
+        let mut budget = quote_budget;
+        let mut notional: u128 = 0;
+
+        for order in resting {
+            if remaining == 0 {
+                break;
+            }
+
+            let mut quantity = order.remaining_quantity.min(remaining);
+            if let Some(budget) = budget {
+                // Most lots affordable at this level; deeper levels only get worse
+                let affordable = (budget as u128 * market.base_lot_size as u128)
+                    / (order.price as u128 * market.quote_tick_size as u128);
+                quantity = quantity.min(u64::try_from(affordable).unwrap_or(u64::MAX));
+            }
+            if !order.can_fill_against(quantity) {
+                // A smaller order behind an all-or-none maker may still fit
+                continue;
+            }
+            if quantity == 0 {
+                break;
+            }
+
+            // Same per-fill rounding as place_limit_order
+            let quote = market.fill_quote_amount(
+                maker_side,
+                order.price,
+                quantity,
+                order.remaining_quantity - quantity,
+            )?;
+
+            if let Some(budget) = budget.as_mut() {
+                *budget -= quote;
+            }
+            if result.worst_price != order.price {
+                result.levels_touched += 1;
+            }
+
+            remaining -= quantity;
+            result.fillable_base += quantity;
+            result.quote_required = result
+                .quote_required
+                .checked_add(quote)
+                .ok_or(ErrorCode::MathOverflow)?;
+            result.worst_price = order.price;
+            notional += order.price as u128 * quantity as u128;
+        }
+
+        if result.fillable_base > 0 {
+            result.avg_price = (notional / result.fillable_base as u128) as u64;
+        }
+
+        Ok(result)
+    }
+}
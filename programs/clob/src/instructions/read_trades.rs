@@ -0,0 +1,50 @@
+use crate::state::{EventQueue, TradeCursor, TradeRecord, MAX_TRADES_PER_READ};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ReadTrades<'info> {
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = event_queue,
+        seeds = [b"trade_cursor", event_queue.key().as_ref(), owner.key().as_ref()],
+        bump = trade_cursor.bump,
+    )]
+    pub trade_cursor: Account<'info, TradeCursor>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReadTradesParams {
+    pub max_trades: u8, // Capped at MAX_TRADES_PER_READ
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReadTradesResult {
+    pub trades: Vec<TradeRecord>,
+    pub gap: bool,       // The ring overwrote events the cursor had not read yet
+    pub next_seq: u64,   // Where the cursor now stands
+    pub caught_up: bool, // No events left past next_seq
+}
+
+impl ReadTrades<'_> {
+    /// Return the trades since the cursor via return data and advance it
+    /// past them, so the next call resumes where this one stopped. Call
+    /// again while `caught_up` is false.
+    pub fn apply(ctx: Context<ReadTrades>, params: ReadTradesParams) -> Result<ReadTradesResult> {
+        let queue = ctx.accounts.event_queue.load()?;
+        let cursor = &mut ctx.accounts.trade_cursor;
+        let max_trades = (params.max_trades as usize).min(MAX_TRADES_PER_READ);
+        let (trades, gap) = cursor.read(&queue, max_trades);
+
+        Ok(ReadTradesResult {
+            trades,
+            gap,
+            next_seq: cursor.next_seq,
+            caught_up: cursor.next_seq >= queue.next_seq(),
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use crate::errors::ErrorCode;
+use crate::events::OrphanedAccountReclaimed;
+use crate::state::{AskSide, BidSide, EventQueue, Market};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+
+#[derive(Accounts)]
+pub struct ReclaimOrphanedAccount<'info> {
+    // Must sign: only whoever created the account in the first market-creation
+    // transaction holds its keypair
+    #[account(mut, owner = crate::ID @ ErrorCode::NotOrphanedAccount)]
+    pub orphan: Signer<'info>,
+
+    /// CHECK: Only receives the reclaimed lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    // Optional extra check that the orphan is not one of this market's accounts
+    pub market: Option<Account<'info, Market>>,
+}
+
+impl ReclaimOrphanedAccount<'_> {
+    // Closes a bids/asks/event_queue account whose Initialize never landed. An
+    // account qualifies only if it is program-owned, sized exactly like one of
+    // the zero-copy book or queue accounts, and still has the all-zero
+    // discriminator that `#[account(zero)]` requires, i.e. no instruction has
+    // ever initialized it. Market and UserBalance are always created with a
+    // discriminator so they can never match.
+    pub fn apply(ctx: Context<ReclaimOrphanedAccount>) -> Result<()> {
+        let orphan = ctx.accounts.orphan.to_account_info();
+
+        {
+            let data = orphan.try_borrow_data()?;
+            let book_or_queue_size = [
+                8 + std::mem::size_of::<BidSide>(),
+                8 + std::mem::size_of::<AskSide>(),
+                8 + std::mem::size_of::<EventQueue>(),
+            ]
+            .contains(&data.len());
+            require!(book_or_queue_size, ErrorCode::NotOrphanedAccount);
+            require!(data[..8] == [0u8; 8], ErrorCode::NotOrphanedAccount);
+        }
+
+        if let Some(market) = &ctx.accounts.market {
+            require!(
+                ![market.bids, market.asks].contains(orphan.key)
+                    && !market.is_event_queue(orphan.key),
+                ErrorCode::NotOrphanedAccount
+            );
+        }
+
+        // Same steps as Anchor's `close` constraint
+        let recipient = ctx.accounts.recipient.to_account_info();
+        let lamports = orphan.lamports();
+        **recipient.lamports.borrow_mut() = recipient
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ErrorCode::MathOverflow)?;
+        **orphan.lamports.borrow_mut() = 0;
+        orphan.assign(&system_program::ID);
+        orphan.realloc(0, false)?;
+
+        emit!(OrphanedAccountReclaimed {
+            account: orphan.key(),
+            recipient: recipient.key(),
+            lamports,
+        });
+
+        msg!(
+            "Reclaimed {} lamports from orphaned account {}",
+            lamports,
+            orphan.key()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::errors::ErrorCode;
+use crate::events::SessionRegistered;
+use crate::state::{Market, UserBalance};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RegisterSession<'info> {
+    #[account(
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"user_balance", user.key().as_ref(), market.key().as_ref()],
+        bump = user_balance.bump,
+        constraint = user_balance.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_balance: Account<'info, UserBalance>,
+    pub user: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RegisterSessionParams {
+    pub expiry_ts: i64, // Unix timestamp the session lapses at; in the past ends it now
+}
+
+impl RegisterSession<'_> {
+    /// Start or refresh the signer's session. A market maker's bot calls this
+    /// on a heartbeat; should it stop, the session lapses and anyone can
+    /// sweep its session orders with cancel_expired_session_orders.
+    pub fn apply(ctx: Context<RegisterSession>, params: RegisterSessionParams) -> Result<()> {
+        let user_balance = &mut ctx.accounts.user_balance;
+        user_balance.session_expiry = params.expiry_ts;
+
+        emit!(SessionRegistered {
+            user: user_balance.owner,
+            market: ctx.accounts.market.key(),
+            expiry_ts: params.expiry_ts,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::errors::ErrorCode;
+use crate::events::CrossAuthorityUpdated;
+use crate::state::Market;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCrossAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetCrossAuthorityParams {
+    pub cross_authority: Pubkey,      // Default pubkey disables crossing
+    pub max_cross_trade_through: u64, // Trade-through bound in ticks
+}
+
+impl SetCrossAuthority<'_> {
+    pub fn apply(ctx: Context<SetCrossAuthority>, params: SetCrossAuthorityParams) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.cross_authority = params.cross_authority;
+        market.max_cross_trade_through = params.max_cross_trade_through;
+
+        emit!(CrossAuthorityUpdated {
+            market: market.key(),
+            cross_authority: market.cross_authority,
+            max_cross_trade_through: market.max_cross_trade_through,
+        });
+
+        msg!(
+            "Cross authority set to {}, trade-through bound {} ticks",
+            market.cross_authority,
+            market.max_cross_trade_through
+        );
+
+        Ok(())
+    }
+}
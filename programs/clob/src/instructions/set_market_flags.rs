@@ -0,0 +1,51 @@
+use crate::errors::ErrorCode;
+use crate::events::MarketFlagsUpdated;
+use crate::state::{Market, MARKET_FLAGS_ALL};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMarketFlags<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetMarketFlagsParams {
+    pub flags: u8, // Full replacement set of MARKET_FLAG_* bits
+}
+
+impl SetMarketFlags<'_> {
+    // Halts only gate new orders; cancels, withdrawals and settlement keep
+    // working so users can always get out
+    pub fn apply(ctx: Context<SetMarketFlags>, params: SetMarketFlagsParams) -> Result<()> {
+        require!(
+            params.flags & !MARKET_FLAGS_ALL == 0,
+            ErrorCode::InvalidParameter
+        );
+
+        let market = &mut ctx.accounts.market;
+        let previous_flags = market.flags;
+        market.flags = params.flags;
+
+        emit!(MarketFlagsUpdated {
+            market: market.key(),
+            previous_flags,
+            flags: market.flags,
+        });
+
+        msg!(
+            "Market flags {:#04x} -> {:#04x}",
+            previous_flags,
+            market.flags
+        );
+
+        Ok(())
+    }
+}
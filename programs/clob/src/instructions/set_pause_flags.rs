@@ -0,0 +1,47 @@
+use crate::errors::ErrorCode;
+use crate::events::PauseFlagsUpdated;
+use crate::state::Market;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPauseFlagsParams {
+    pub deposits_paused: bool,    // Reject deposit while set
+    pub withdrawals_paused: bool, // Reject withdraw while set
+}
+
+impl SetPauseFlags<'_> {
+    // Emergency stop for the vaults. Unlike the halt flags this does not
+    // touch trading; close_user_balance keeps working for emptied balances.
+    pub fn apply(ctx: Context<SetPauseFlags>, params: SetPauseFlagsParams) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        market.deposits_paused = params.deposits_paused;
+        market.withdrawals_paused = params.withdrawals_paused;
+
+        emit!(PauseFlagsUpdated {
+            market: market.key(),
+            deposits_paused: market.deposits_paused,
+            withdrawals_paused: market.withdrawals_paused,
+        });
+
+        msg!(
+            "Deposits paused: {}, withdrawals paused: {}",
+            market.deposits_paused,
+            market.withdrawals_paused
+        );
+
+        Ok(())
+    }
+}
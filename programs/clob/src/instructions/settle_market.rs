@@ -0,0 +1,46 @@
+use crate::errors::ErrorCode;
+use crate::events::SettlementTick;
+use crate::state::Market;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SettleMarket<'info> {
+    // Anyone may crank a settlement
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+impl SettleMarket<'_> {
+    /// Record a settlement point, marked at the last trade price. Nothing
+    /// moves between balances yet; this is where perp-style funding will be
+    /// computed from the mark and the time since the previous settlement.
+    pub fn apply(ctx: Context<SettleMarket>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let timestamp = Clock::get()?.unix_timestamp;
+        // At most one tick per timestamp, so the interval since the last is
+        // never zero
+        require!(
+            timestamp > market.last_settlement_ts,
+            ErrorCode::SettlementNotDue
+        );
+
+        let previous_settlement_ts = market.last_settlement_ts;
+        market.last_settlement_ts = timestamp;
+
+        emit!(SettlementTick {
+            market: market.key(),
+            settled_by: ctx.accounts.cranker.key(),
+            mark_price: market.last_trade_price,
+            previous_settlement_ts,
+            timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+use crate::errors::ErrorCode;
+use crate::events::{BalanceChanged, OrderFilled, BALANCE_REASON_CROSS};
+use crate::state::{
+    AskSide, BidSide, EventQueue, FillEvent, Market, Side, UserBalance, FILL_EVENT_KIND_CROSS,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SubmitCross<'info> {
+    pub cross_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = cross_authority @ ErrorCode::Unauthorized,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+    )]
+    pub market: Account<'info, Market>,
+
+    pub bids: AccountLoader<'info, BidSide>,
+    pub asks: AccountLoader<'info, AskSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+    // remaining_accounts: the mutable UserBalance PDA of every buyer and
+    // seller in the batch, each listed once
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossTrade {
+    pub buyer: Pubkey,  // Pays quote, receives base
+    pub seller: Pubkey, // Pays base, receives quote
+    pub price: u64,     // In quote_tick_size units
+    pub quantity: u64,  // In base_lot_size units
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SubmitCrossParams {
+    pub crosses: Vec<CrossTrade>, // Settled in order, all or none
+}
+
+impl SubmitCross<'_> {
+    // Trades matched off-chain settle here against the traders' balances
+    // directly; nothing rests on or is taken from the book. Each cross is
+    // priced against the book as it stands, so the venue cannot fill a
+    // buyer above (or a seller below) resting liquidity by more than the
+    // configured bound. Every cross gets a fresh id from next_order_id and
+    // is queued as a FILL_EVENT_KIND_CROSS event, so queue readers see it
+    // in the same stream as book fills.
+    pub fn apply(ctx: Context<SubmitCross>, params: SubmitCrossParams) -> Result<()> {
+        require!(!params.crosses.is_empty(), ErrorCode::InvalidParameter);
+        let market = &mut ctx.accounts.market;
+        let market_key = market.key();
+        require!(!market.is_closing(), ErrorCode::MarketClosing);
+        require!(
+            !market.is_side_halted(Side::Bid) && !market.is_side_halted(Side::Ask),
+            ErrorCode::SideHalted
+        );
+
+        let best_bid = ctx
+            .accounts
+            .bids
+            .load()?
+            .orderbook
+            .peek()
+            .map(|order| order.price);
+        let best_ask = ctx
+            .accounts
+            .asks
+            .load()?
+            .orderbook
+            .peek()
+            .map(|order| order.price);
+
+        let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+        require!(
+            event_queue.free_slots() >= params.crosses.len() as u64,
+            ErrorCode::EventQueueFull
+        );
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        for cross in params.crosses.iter() {
+            require!(
+                cross.price > 0 && cross.quantity > 0,
+                ErrorCode::InvalidOrderSize
+            );
+            require!(cross.buyer != cross.seller, ErrorCode::InvalidParameter);
+            require!(
+                market.cross_within_bounds(cross.price, best_bid, best_ask),
+                ErrorCode::CrossTradesThrough
+            );
+
+            market.last_trade_price = cross.price;
+            let cross_id = market.next_order_id;
+            market.next_order_id = market
+                .next_order_id
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            let base = market.base_amount(cross.quantity)?;
+            let quote = market.quote_amount(cross.price, cross.quantity)?;
+
+            let buyer_account =
+                Self::user_balance_account(ctx.remaining_accounts, &market_key, &cross.buyer)?;
+            Self::settle(buyer_account, Side::Bid, base, quote)?;
+            let seller_account =
+                Self::user_balance_account(ctx.remaining_accounts, &market_key, &cross.seller)?;
+            Self::settle(seller_account, Side::Ask, base, quote)?;
+
+            event_queue.push_event(FillEvent {
+                maker_order_id: cross_id,
+                taker_order_id: cross_id,
+                price: cross.price,
+                quantity: cross.quantity,
+                timestamp,
+                maker_owner: cross.seller,
+                taker_owner: cross.buyer,
+                market: market_key,
+                maker_side: 1,
+                kind: FILL_EVENT_KIND_CROSS,
+                maker_order_closed: 0,
+                retry_count: 0,
+                _padding: [0; 4],
+            })?;
+
+            // Crosses are agreed off-book and carry no fee
+            let fee_quote = 0;
+            emit!(OrderFilled {
+                maker_order_id: cross_id,
+                taker_order_id: cross_id,
+                market: market_key,
+                price: cross.price,
+                quantity: cross.quantity,
+                maker_owner: cross.seller,
+                taker_owner: cross.buyer,
+                taker_side: Side::Bid,
+                gross_quote: quote,
+                fee_base: 0,
+                fee_quote,
+                net_quote: quote - fee_quote,
+            });
+            emit!(BalanceChanged {
+                user: cross.buyer,
+                market: market_key,
+                base_delta: BalanceChanged::credit(base)?,
+                quote_delta: BalanceChanged::debit(quote)?,
+                reason: BALANCE_REASON_CROSS,
+                ref_id: cross_id,
+            });
+            emit!(BalanceChanged {
+                user: cross.seller,
+                market: market_key,
+                base_delta: BalanceChanged::debit(base)?,
+                quote_delta: BalanceChanged::credit(quote)?,
+                reason: BALANCE_REASON_CROSS,
+                ref_id: cross_id,
+            });
+        }
+
+        msg!("Settled {} crosses", params.crosses.len());
+        Ok(())
+    }
+
+    /// The UserBalance PDA of `owner` among `accounts`
+    fn user_balance_account<'a, 'info>(
+        accounts: &'a [AccountInfo<'info>],
+        market: &Pubkey,
+        owner: &Pubkey,
+    ) -> Result<&'a AccountInfo<'info>> {
+        accounts
+            .iter()
+            .find(|account_info| UserBalance::is_account_of(account_info, owner, market))
+            .ok_or(error!(ErrorCode::CrossAccountMissing))
+    }
+
+    /// Move one cross through a trader's balance: the buyer (`Bid`) pays
+    /// `quote` for `base`, the seller (`Ask`) the reverse
+    fn settle(account_info: &AccountInfo, side: Side, base: u64, quote: u64) -> Result<()> {
+        require!(
+            account_info.owner == &crate::ID,
+            ErrorCode::CrossAccountMissing
+        );
+        let mut user_balance = UserBalance::load_from(account_info)?;
+
+        match side {
+            Side::Bid => {
+                user_balance.quote_balance = user_balance
+                    .quote_balance
+                    .checked_sub(quote)
+                    .ok_or(ErrorCode::InsufficientBalance)?;
+                user_balance.base_balance = user_balance
+                    .base_balance
+                    .checked_add(base)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            Side::Ask => {
+                user_balance.base_balance = user_balance
+                    .base_balance
+                    .checked_sub(base)
+                    .ok_or(ErrorCode::InsufficientBalance)?;
+                user_balance.quote_balance = user_balance
+                    .quote_balance
+                    .checked_add(quote)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        user_balance.record_fill(side, base, quote);
+
+        user_balance.store_to(account_info)
+    }
+}
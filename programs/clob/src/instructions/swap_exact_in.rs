@@ -0,0 +1,371 @@
+use crate::errors::ErrorCode;
+use crate::events::{BookDelta, LevelChange, OrderFilled, SwapExecuted};
+use crate::instructions::QuoteOrder;
+use crate::state::{
+    AskSide, BidSide, EventQueue, FillEvent, Market, Order, OrderBook, Side, FILL_EVENT_KIND_FILL,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct SwapExactIn<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub bids: AccountLoader<'info, BidSide>,
+    #[account(mut)]
+    pub asks: AccountLoader<'info, AskSide>,
+    #[account(mut)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    #[account(mut)]
+    pub base_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub quote_vault: InterfaceAccount<'info, TokenAccount>,
+
+    // The caller's wallets: one pays in, the other receives the proceeds
+    #[account(mut, token::mint = base_mint)]
+    pub user_base_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = quote_mint)]
+    pub user_quote_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = base_token_program)]
+    pub base_mint: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = quote_token_program)]
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+    pub base_token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapExactInParams {
+    pub side: Side,          // Bid spends quote for base, Ask sells base for quote
+    pub amount_in: u64,      // Input tokens taken from the caller
+    pub min_amount_out: u64, // Fail unless at least this many output tokens are paid out
+}
+
+impl SwapExactIn<'_> {
+    // Wallet-to-wallet taker trade for routers: no UserBalance is read or
+    // written. The input is pulled into the vault, matched as an IOC market
+    // order, and the proceeds plus any unspent input (dust below a lot or
+    // the part the book could not fill) are paid straight back. Makers still
+    // settle through the event queue. Proceeds are net of the taker fee,
+    // which stays in the output vault.
+    pub fn apply(ctx: Context<SwapExactIn>, params: SwapExactInParams) -> Result<()> {
+        require!(params.amount_in > 0, ErrorCode::InvalidAmount);
+        require!(!ctx.accounts.market.is_closing(), ErrorCode::MarketClosing);
+        // Pulls the input into the vault and pays the proceeds out of it
+        require!(
+            !ctx.accounts.market.deposits_paused && !ctx.accounts.market.withdrawals_paused,
+            ErrorCode::MarketPaused
+        );
+        require!(
+            !ctx.accounts.market.is_side_halted(params.side),
+            ErrorCode::SideHalted
+        );
+
+        ctx.accounts.market.check_settlement_backlog(
+            std::slice::from_ref(&ctx.accounts.event_queue),
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Budget with what the vault actually received, net of any Token-2022
+        // transfer fee
+        let received = ctx.accounts.transfer_in(params.side, params.amount_in)?;
+
+        let market = &mut ctx.accounts.market;
+        let mut bids = ctx.accounts.bids.load_mut()?;
+        let mut asks = ctx.accounts.asks.load_mut()?;
+        bids.orderbook.check_layout()?;
+        asks.orderbook.check_layout()?;
+        let mut event_queue = ctx.accounts.event_queue.load_mut()?;
+
+        // A market order: cross every level, sized by the input. Bids find
+        // the lots they can afford the same way quote_order does.
+        let (price, quantity) = match params.side {
+            Side::Bid => {
+                let quote = QuoteOrder::simulate(
+                    market,
+                    Side::Ask,
+                    asks.orderbook.crossing_orders(u64::MAX),
+                    u64::MAX,
+                    Some(received),
+                )?;
+                (quote.worst_price, quote.fillable_base)
+            }
+            Side::Ask => (1, received / market.base_lot_size),
+        };
+
+        let mut taker = Order {
+            order_id: market.next_order_id,
+            owner: ctx.accounts.user.key(),
+            price,
+            quantity,
+            remaining_quantity: quantity,
+            timestamp: Clock::get()?.unix_timestamp,
+            ..Default::default()
+        };
+        market.next_order_id = market
+            .next_order_id
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Same bound on queued events as place_limit_order
+        let crossing = match params.side {
+            Side::Bid => asks.orderbook.crossing_order_count(price),
+            Side::Ask => bids.orderbook.crossing_order_count(price),
+        };
+        require!(
+            event_queue.free_slots() >= (crossing as u64).min(quantity),
+            ErrorCode::EventQueueFull
+        );
+
+        let fills = if quantity == 0 {
+            Vec::new()
+        } else {
+            match params.side {
+                Side::Bid => asks.orderbook.match_orders(&mut taker)?,
+                Side::Ask => bids.orderbook.match_orders(&mut taker)?,
+            }
+        };
+
+        if let Some(fill) = fills.last() {
+            market.last_trade_price = fill.price;
+        }
+
+        let maker_side = match params.side {
+            Side::Bid => Side::Ask,
+            Side::Ask => Side::Bid,
+        };
+        let mut spent: u64 = 0;
+        let mut amount_out: u64 = 0;
+        let mut gross_quotes = Vec::with_capacity(fills.len());
+        for fill in fills.iter() {
+            let base = market.base_amount(fill.quantity)?;
+            let quote = market.fill_quote_amount(
+                fill.maker_side,
+                fill.price,
+                fill.quantity,
+                fill.maker_remaining_quantity,
+            )?;
+            let (fill_in, fill_out) = match params.side {
+                Side::Bid => (quote, base),
+                Side::Ask => (base, quote),
+            };
+            let fee = market.taker_fee(fill_out)?;
+            market.accrue_fee(params.side, fee)?;
+            gross_quotes.push((quote, fee));
+            spent = spent.checked_add(fill_in).ok_or(ErrorCode::MathOverflow)?;
+            amount_out = amount_out
+                .checked_add(fill_out - fee)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            event_queue.push_event(FillEvent {
+                maker_order_id: fill.maker_order_id,
+                taker_order_id: fill.taker_order_id,
+                price: fill.price,
+                quantity: fill.quantity,
+                timestamp: taker.timestamp,
+                maker_owner: fill.maker_owner,
+                taker_owner: taker.owner,
+                market: market.key(),
+                maker_side: match fill.maker_side {
+                    Side::Bid => 0,
+                    Side::Ask => 1,
+                },
+                kind: FILL_EVENT_KIND_FILL,
+                maker_order_closed: fill.maker_order_closed as u8,
+                retry_count: 0,
+                _padding: [0; 4],
+            })?;
+        }
+
+        let unspent = received
+            .checked_sub(spent)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        require!(
+            amount_out >= params.min_amount_out,
+            ErrorCode::SlippageExceeded
+        );
+
+        let mut level_changes: Vec<LevelChange> = Vec::new();
+        for fill in fills.iter() {
+            if level_changes
+                .iter()
+                .any(|change| change.price == fill.price)
+            {
+                continue;
+            }
+            let new_total_quantity = match maker_side {
+                Side::Bid => bids.orderbook.level_quantity(fill.price),
+                Side::Ask => asks.orderbook.level_quantity(fill.price),
+            };
+            level_changes.push(LevelChange {
+                side: maker_side,
+                price: fill.price,
+                new_total_quantity,
+            });
+        }
+        let book_delta = if level_changes.is_empty() {
+            None
+        } else {
+            Some(BookDelta::new(
+                market.key(),
+                market.next_book_seq_num()?,
+                level_changes,
+                bids.orderbook.book_hash(),
+                asks.orderbook.book_hash(),
+            ))
+        };
+        drop(bids);
+        drop(asks);
+        drop(event_queue);
+
+        // The proceeds are what the makers' side spends
+        ctx.accounts.transfer_out(maker_side, amount_out)?;
+        ctx.accounts.transfer_out(params.side, unspent)?;
+
+        let market_key = ctx.accounts.market.key();
+        for (fill, (gross_quote, fee)) in fills.iter().zip(gross_quotes) {
+            let (fee_base, fee_quote) = match params.side {
+                Side::Bid => (fee, 0),
+                Side::Ask => (0, fee),
+            };
+            emit!(OrderFilled {
+                maker_order_id: fill.maker_order_id,
+                taker_order_id: fill.taker_order_id,
+                market: market_key,
+                price: fill.price,
+                quantity: fill.quantity,
+                maker_owner: fill.maker_owner,
+                taker_owner: taker.owner,
+                taker_side: params.side,
+                gross_quote,
+                fee_base,
+                fee_quote,
+                net_quote: gross_quote - fee_quote,
+            });
+        }
+        if let Some(book_delta) = book_delta {
+            emit!(book_delta);
+        }
+        emit!(SwapExecuted {
+            order_id: taker.order_id,
+            user: taker.owner,
+            market: market_key,
+            side: params.side,
+            amount_in: received,
+            amount_spent: spent,
+            amount_out,
+            filled_quantity: quantity - taker.remaining_quantity,
+        });
+
+        msg!(
+            "Swapped {} in for {} out, {} unspent returned",
+            spent,
+            amount_out,
+            unspent
+        );
+        Ok(())
+    }
+
+    /// Pull the input for a swap on `side` into its vault; returns what the
+    /// vault received
+    fn transfer_in(&mut self, side: Side, amount: u64) -> Result<u64> {
+        let (from, vault, mint, token_program) = match side {
+            Side::Bid => (
+                &self.user_quote_account,
+                &mut self.quote_vault,
+                &self.quote_mint,
+                &self.quote_token_program,
+            ),
+            Side::Ask => (
+                &self.user_base_account,
+                &mut self.base_vault,
+                &self.base_mint,
+                &self.base_token_program,
+            ),
+        };
+
+        let vault_before = vault.amount;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    to: vault.to_account_info(),
+                    authority: self.user.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+            ),
+            amount,
+            mint.decimals,
+        )?;
+        vault.reload()?;
+        let received = vault
+            .amount
+            .checked_sub(vault_before)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(received > 0, ErrorCode::InvalidAmount);
+        Ok(received)
+    }
+
+    /// Pay `amount` of the asset a swap on `side` spends (quote for bids,
+    /// base for asks) from its vault to the caller
+    fn transfer_out(&self, side: Side, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let (to, vault, mint, token_program) = match side {
+            Side::Bid => (
+                &self.user_quote_account,
+                &self.quote_vault,
+                &self.quote_mint,
+                &self.quote_token_program,
+            ),
+            Side::Ask => (
+                &self.user_base_account,
+                &self.base_vault,
+                &self.base_mint,
+                &self.base_token_program,
+            ),
+        };
+
+        let market = &self.market;
+        let seeds: &[&[u8]] = &[
+            b"market".as_ref(),
+            market.base_mint.as_ref(),
+            market.quote_mint.as_ref(),
+            &[market.bump],
+        ];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: market.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+            mint.decimals,
+        )
+    }
+}
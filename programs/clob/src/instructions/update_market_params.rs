@@ -0,0 +1,89 @@
+use crate::errors::ErrorCode;
+use crate::events::MarketParamsUpdated;
+use crate::instructions::AddEventQueueShard;
+use crate::state::{AskSide, BidSide, EventQueue, Market};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMarketParams<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = bids,
+        has_one = asks,
+        has_one = event_queue,
+    )]
+    pub market: Account<'info, Market>,
+
+    pub bids: AccountLoader<'info, BidSide>,
+    pub asks: AccountLoader<'info, AskSide>,
+    pub event_queue: AccountLoader<'info, EventQueue>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateMarketParamsParams {
+    pub base_lot_size: u64,       // New minimum base asset unit size
+    pub quote_tick_size: u64,     // New minimum quote asset price tick size
+    pub order_bond_lamports: u64, // New bond for orders placed from now on
+}
+
+impl UpdateMarketParams<'_> {
+    // Changing lot or tick size under resting orders would misprice them, so
+    // both books must be empty, and the queue must be drained so pending
+    // fills still settle at the params they matched under.
+    pub fn apply<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateMarketParams<'info>>,
+        params: UpdateMarketParamsParams,
+    ) -> Result<()> {
+        require!(params.base_lot_size > 0, ErrorCode::InvalidParameter);
+        require!(params.quote_tick_size > 0, ErrorCode::InvalidParameter);
+        let event_queues = AddEventQueueShard::load_shards(
+            &ctx.accounts.market,
+            &ctx.accounts.event_queue,
+            ctx.remaining_accounts,
+        )?;
+        for event_queue in event_queues.iter() {
+            require!(
+                event_queue.load()?.is_empty(),
+                ErrorCode::EventQueueNotEmpty
+            );
+        }
+
+        require!(
+            ctx.accounts.bids.load()?.orderbook.is_empty()
+                && ctx.accounts.asks.load()?.orderbook.is_empty(),
+            ErrorCode::OrderbookNotEmpty
+        );
+
+        let market = &mut ctx.accounts.market;
+
+        let prev_base_lot_size = market.base_lot_size;
+        let prev_quote_tick_size = market.quote_tick_size;
+        let prev_order_bond_lamports = market.order_bond_lamports;
+        market.base_lot_size = params.base_lot_size;
+        market.quote_tick_size = params.quote_tick_size;
+        market.order_bond_lamports = params.order_bond_lamports;
+
+        emit!(MarketParamsUpdated {
+            market: market.key(),
+            base_lot_size: market.base_lot_size,
+            quote_tick_size: market.quote_tick_size,
+            prev_base_lot_size,
+            prev_quote_tick_size,
+            order_bond_lamports: market.order_bond_lamports,
+            prev_order_bond_lamports,
+        });
+
+        msg!(
+            "Market params updated: lot {} tick {}",
+            market.base_lot_size,
+            market.quote_tick_size
+        );
+
+        Ok(())
+    }
+}
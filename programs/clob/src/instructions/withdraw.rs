@@ -1,5 +1,5 @@
 use crate::errors::ErrorCode;
-use crate::events::UserWithdraw;
+use crate::events::{BalanceChanged, UserWithdraw, BALANCE_REASON_WITHDRAW};
 use crate::state::{Market, UserBalance};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
@@ -23,16 +23,21 @@ pub struct Withdraw<'info> {
     )]
     pub user_balance: Account<'info, UserBalance>,
 
+    // Base and quote may live under different token programs; every token
+    // account here must belong to the one `token_program` names for `mint`
     #[account(
         mut,
-        token::mint = mint
+        token::mint = mint,
+        token::token_program = token_program
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         seeds = [b"vault", market.key().as_ref(), mint.key().as_ref()],
-        bump
+        bump = market.vault_bump(&mint.key()),
+        token::mint = mint,
+        token::token_program = token_program
     )]
     pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
@@ -50,14 +55,20 @@ pub struct WithdrawParams {
 }
 
 impl Withdraw<'_> {
-    pub fn apply(ctx: Context<Withdraw>, params: WithdrawParams) -> Result<()> {
+    /// Returns the free balance left for the withdrawn mint
+    pub fn apply(ctx: Context<Withdraw>, params: WithdrawParams) -> Result<u64> {
         require!(params.amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            !ctx.accounts.market.withdrawals_paused,
+            ErrorCode::MarketPaused
+        );
 
         let user_balance = &mut ctx.accounts.user_balance;
         let market = &ctx.accounts.market;
 
         // Check and update user balance record
-        let new_balance = if ctx.accounts.mint.key() == market.base_mint {
+        let is_base = ctx.accounts.mint.key() == market.base_mint;
+        let new_balance = if is_base {
             require!(
                 user_balance.base_balance >= params.amount,
                 ErrorCode::InsufficientBalance
@@ -110,6 +121,15 @@ impl Withdraw<'_> {
             amount: params.amount,
             new_balance,
         });
+        let delta = BalanceChanged::debit(params.amount)?;
+        emit!(BalanceChanged {
+            user: ctx.accounts.user.key(),
+            market: market.key(),
+            base_delta: if is_base { delta } else { 0 },
+            quote_delta: if is_base { 0 } else { delta },
+            reason: BALANCE_REASON_WITHDRAW,
+            ref_id: 0,
+        });
 
         msg!(
             "Withdrawn {} tokens of mint {} from market vault",
@@ -117,6 +137,6 @@ impl Withdraw<'_> {
             ctx.accounts.mint.key()
         );
 
-        Ok(())
+        Ok(new_balance)
     }
 }
@@ -0,0 +1,109 @@
+use crate::errors::ErrorCode;
+use crate::events::FeesWithdrawn;
+use crate::state::{Market, Side};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.base_mint.as_ref(), market.quote_mint.as_ref()],
+        bump = market.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority,
+        token::token_program = token_program
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref(), mint.key().as_ref()],
+        bump = market.vault_bump(&mint.key()),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = mint.key() == market.base_mint || mint.key() == market.quote_mint,
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WithdrawFeesParams {
+    pub amount: u64, // Up to the fees accrued in `mint`
+}
+
+impl WithdrawFees<'_> {
+    /// Pay `amount` of the fees accrued in one mint out of its vault to the
+    /// authority. Returns the fees left accrued in that mint.
+    pub fn apply(ctx: Context<WithdrawFees>, params: WithdrawFeesParams) -> Result<u64> {
+        require!(params.amount > 0, ErrorCode::InvalidAmount);
+
+        let market = &mut ctx.accounts.market;
+        // Base fees come from bid fills, quote fees from ask fills
+        let side = if ctx.accounts.mint.key() == market.base_mint {
+            Side::Bid
+        } else {
+            Side::Ask
+        };
+        let accrued = match side {
+            Side::Bid => &mut market.base_fees_accrued,
+            Side::Ask => &mut market.quote_fees_accrued,
+        };
+        *accrued = accrued
+            .checked_sub(params.amount)
+            .ok_or(ErrorCode::InsufficientBalance)?;
+        let remaining = *accrued;
+
+        let seeds: &[&[u8]] = &[
+            b"market".as_ref(),
+            market.base_mint.as_ref(),
+            market.quote_mint.as_ref(),
+            &[market.bump],
+        ];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: market.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                &[seeds],
+            ),
+            params.amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(FeesWithdrawn {
+            market: market.key(),
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            amount: params.amount,
+            remaining,
+        });
+
+        msg!(
+            "Withdrawn {} fee tokens of mint {}, {} left accrued",
+            params.amount,
+            ctx.accounts.mint.key(),
+            remaining
+        );
+
+        Ok(remaining)
+    }
+}
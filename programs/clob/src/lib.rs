@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod errors;
 pub mod events;
 pub mod instructions;
@@ -23,7 +25,7 @@ pub mod clob {
         Deposit::apply(ctx, params)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, params: WithdrawParams) -> Result<()> {
+    pub fn withdraw(ctx: Context<Withdraw>, params: WithdrawParams) -> Result<u64> {
         Withdraw::apply(ctx, params)
     }
 
@@ -31,18 +33,230 @@ pub mod clob {
         CloseUserBalance::apply(ctx)
     }
 
-    pub fn place_limit_order(
-        ctx: Context<PlaceLimitOrder>,
+    pub fn migrate_user_balance(ctx: Context<MigrateUserBalance>) -> Result<()> {
+        MigrateUserBalance::apply(ctx)
+    }
+
+    pub fn place_limit_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
         params: PlaceLimitOrderParams,
-    ) -> Result<()> {
+    ) -> Result<PlaceLimitOrderResult> {
         PlaceLimitOrder::apply(ctx, params)
     }
 
+    pub fn place_limit_order_native<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: PlaceLimitOrderNativeParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        PlaceLimitOrder::apply_native(ctx, params)
+    }
+
+    pub fn place_market_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: PlaceMarketOrderParams,
+    ) -> Result<PlaceMarketOrderResult> {
+        PlaceLimitOrder::apply_market(ctx, params)
+    }
+
+    pub fn modify_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceLimitOrder<'info>>,
+        params: ModifyOrderParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        PlaceLimitOrder::apply_modify(ctx, params)
+    }
+
+    pub fn place_signed_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceSignedOrder<'info>>,
+        params: PlaceSignedOrderParams,
+    ) -> Result<PlaceLimitOrderResult> {
+        PlaceSignedOrder::apply(ctx, params)
+    }
+
+    pub fn place_twap_order(
+        ctx: Context<PlaceTwapOrder>,
+        params: PlaceTwapOrderParams,
+    ) -> Result<()> {
+        PlaceTwapOrder::apply(ctx, params)
+    }
+
+    pub fn execute_twap_slice<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteTwapSlice<'info>>,
+    ) -> Result<()> {
+        ExecuteTwapSlice::apply(ctx)
+    }
+
+    pub fn close_twap_order(ctx: Context<CloseTwapOrder>) -> Result<()> {
+        CloseTwapOrder::apply(ctx)
+    }
+
+    pub fn query_next_order_id(ctx: Context<QueryNextOrderId>) -> Result<u64> {
+        QueryNextOrderId::apply(ctx)
+    }
+
+    pub fn query_price_levels(
+        ctx: Context<QueryPriceLevels>,
+        params: QueryPriceLevelsParams,
+    ) -> Result<PriceLevelsPage> {
+        QueryPriceLevels::apply(ctx, params)
+    }
+
+    pub fn query_total_events_pushed(ctx: Context<QueryTotalEventsPushed>) -> Result<u64> {
+        QueryTotalEventsPushed::apply(ctx)
+    }
+
+    pub fn open_trade_cursor(ctx: Context<OpenTradeCursor>) -> Result<()> {
+        OpenTradeCursor::apply(ctx)
+    }
+
+    pub fn read_trades(
+        ctx: Context<ReadTrades>,
+        params: ReadTradesParams,
+    ) -> Result<ReadTradesResult> {
+        ReadTrades::apply(ctx, params)
+    }
+
+    pub fn swap_exact_in(ctx: Context<SwapExactIn>, params: SwapExactInParams) -> Result<()> {
+        SwapExactIn::apply(ctx, params)
+    }
+
+    pub fn quote_order(
+        ctx: Context<QuoteOrder>,
+        params: QuoteOrderParams,
+    ) -> Result<QuoteOrderResult> {
+        QuoteOrder::apply(ctx, params)
+    }
+
     pub fn cancel_order(ctx: Context<CancelOrder>, params: CancelOrderParams) -> Result<()> {
         CancelOrder::apply(ctx, params)
     }
 
-    pub fn consume_events(ctx: Context<ConsumeEvents>, params: ConsumeEventsParams) -> Result<()> {
+    pub fn cancel_order_by_client_id(
+        ctx: Context<CancelOrder>,
+        params: CancelOrderByClientIdParams,
+    ) -> Result<()> {
+        CancelOrder::apply_by_client_id(ctx, params)
+    }
+
+    pub fn cancel_all_my_orders(
+        ctx: Context<CancelAllMyOrders>,
+        params: CancelAllMyOrdersParams,
+    ) -> Result<u32> {
+        CancelAllMyOrders::apply(ctx, params)
+    }
+
+    pub fn register_session(
+        ctx: Context<RegisterSession>,
+        params: RegisterSessionParams,
+    ) -> Result<()> {
+        RegisterSession::apply(ctx, params)
+    }
+
+    pub fn cancel_expired_session_orders(
+        ctx: Context<CancelExpiredSessionOrders>,
+        params: CancelExpiredSessionOrdersParams,
+    ) -> Result<u32> {
+        CancelExpiredSessionOrders::apply(ctx, params)
+    }
+
+    pub fn cancel_and_credit(
+        ctx: Context<CancelAndCredit>,
+        params: CancelAndCreditParams,
+    ) -> Result<()> {
+        CancelAndCredit::apply(ctx, params)
+    }
+
+    pub fn cancel_signed_order(
+        ctx: Context<CancelSignedOrder>,
+        params: CancelSignedOrderParams,
+    ) -> Result<()> {
+        CancelSignedOrder::apply(ctx, params)
+    }
+
+    pub fn increase_order(ctx: Context<IncreaseOrder>, params: IncreaseOrderParams) -> Result<()> {
+        IncreaseOrder::apply(ctx, params)
+    }
+
+    pub fn increase_order_size(
+        ctx: Context<IncreaseOrderSize>,
+        params: IncreaseOrderSizeParams,
+    ) -> Result<u64> {
+        IncreaseOrderSize::apply(ctx, params)
+    }
+
+    pub fn consume_events(ctx: Context<ConsumeEvents>, params: ConsumeEventsParams) -> Result<u8> {
         ConsumeEvents::apply(ctx, params)
     }
+
+    pub fn harvest_withheld_fees(ctx: Context<HarvestWithheldFees>) -> Result<()> {
+        HarvestWithheldFees::apply(ctx)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, params: WithdrawFeesParams) -> Result<u64> {
+        WithdrawFees::apply(ctx, params)
+    }
+
+    pub fn begin_market_close(
+        ctx: Context<BeginMarketClose>,
+        params: BeginMarketCloseParams,
+    ) -> Result<()> {
+        BeginMarketClose::apply(ctx, params)
+    }
+
+    pub fn set_market_flags(
+        ctx: Context<SetMarketFlags>,
+        params: SetMarketFlagsParams,
+    ) -> Result<()> {
+        SetMarketFlags::apply(ctx, params)
+    }
+
+    pub fn settle_market(ctx: Context<SettleMarket>) -> Result<()> {
+        SettleMarket::apply(ctx)
+    }
+
+    pub fn set_pause_flags(ctx: Context<SetPauseFlags>, params: SetPauseFlagsParams) -> Result<()> {
+        SetPauseFlags::apply(ctx, params)
+    }
+
+    pub fn update_market_params<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateMarketParams<'info>>,
+        params: UpdateMarketParamsParams,
+    ) -> Result<()> {
+        UpdateMarketParams::apply(ctx, params)
+    }
+
+    pub fn add_event_queue_shard(ctx: Context<AddEventQueueShard>) -> Result<()> {
+        AddEventQueueShard::apply(ctx)
+    }
+
+    pub fn set_cross_authority(
+        ctx: Context<SetCrossAuthority>,
+        params: SetCrossAuthorityParams,
+    ) -> Result<()> {
+        SetCrossAuthority::apply(ctx, params)
+    }
+
+    pub fn submit_cross(ctx: Context<SubmitCross>, params: SubmitCrossParams) -> Result<()> {
+        SubmitCross::apply(ctx, params)
+    }
+
+    pub fn clear_book(ctx: Context<ClearBook>, params: ClearBookParams) -> Result<()> {
+        ClearBook::apply(ctx, params)
+    }
+
+    pub fn force_cancel_all(
+        ctx: Context<ForceCancelAll>,
+        params: ForceCancelAllParams,
+    ) -> Result<()> {
+        ForceCancelAll::apply(ctx, params)
+    }
+
+    pub fn close_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseMarket<'info>>,
+    ) -> Result<()> {
+        CloseMarket::apply(ctx)
+    }
+
+    pub fn reclaim_orphaned_account(ctx: Context<ReclaimOrphanedAccount>) -> Result<()> {
+        ReclaimOrphanedAccount::apply(ctx)
+    }
 }
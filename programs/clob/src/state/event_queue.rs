@@ -1,14 +1,27 @@
+use super::{Order, Side};
 use anchor_lang::prelude::*;
 
 pub const MAX_EVENTS: usize = 256;
 
+/// A match between a maker and a taker: settle the maker's side
+pub const FILL_EVENT_KIND_FILL: u8 = 0;
+/// An order removed from the book without a fill (clear_book, or cancelled
+/// by a self-trade): `quantity` is the reserved amount in native units to
+/// refund to the owner, `price` the order bond in lamports to return with it
+pub const FILL_EVENT_KIND_REFUND: u8 = 1;
+/// A trade matched off-chain and settled by submit_cross: both balances
+/// already moved, so cranking it only advances the queue
+pub const FILL_EVENT_KIND_CROSS: u8 = 2;
+
 #[account(zero_copy)]
 #[derive(InitSpace)]
 pub struct EventQueue {
     pub head: u64,                       // Queue head index
     pub tail: u64,                       // Queue tail index
     pub capacity: u64,                   // Queue capacity
+    pub total_events_pushed: u64,        // Events ever pushed; never wraps or drains
     pub events: [FillEvent; MAX_EVENTS], // Events array
+    pub total_events_requeued: u64,      // Events the crank moved to the tail; counted apart
 }
 
 #[zero_copy]
@@ -23,16 +36,73 @@ pub struct FillEvent {
     pub maker_owner: Pubkey,
     pub taker_owner: Pubkey,
     pub market: Pubkey,
-    pub maker_side: u8,    // Maker order side (0=Bid, 1=Ask)
-    pub _padding: [u8; 7], // Explicit padding to avoid automatic padding
+    pub maker_side: u8,         // Maker order side (0=Bid, 1=Ask)
+    pub kind: u8,               // FILL_EVENT_KIND_*
+    pub maker_order_closed: u8, // 1 once the maker order has left the book
+    pub retry_count: u8,        // Times the crank moved this event to the tail
+    pub _padding: [u8; 4],      // Explicit padding to avoid automatic padding
+}
+
+impl FillEvent {
+    pub fn refund(
+        order: &Order,
+        side: Side,
+        amount: u64,
+        bond: u64,
+        market: Pubkey,
+        timestamp: i64,
+    ) -> Self {
+        FillEvent {
+            maker_order_id: order.order_id,
+            taker_order_id: 0,
+            price: bond,
+            quantity: amount,
+            timestamp,
+            maker_owner: order.owner,
+            taker_owner: Pubkey::default(),
+            market,
+            maker_side: match side {
+                Side::Bid => 0,
+                Side::Ask => 1,
+            },
+            kind: FILL_EVENT_KIND_REFUND,
+            maker_order_closed: 1,
+            retry_count: 0,
+            _padding: [0; 4],
+        }
+    }
 }
 
 impl EventQueue {
+    /// Set up a freshly zeroed queue as an empty ring of MAX_EVENTS slots.
+    /// Left at zero capacity, every push would be refused as full.
+    pub fn init(&mut self) {
+        self.head = 0;
+        self.tail = 0;
+        self.capacity = MAX_EVENTS as u64;
+        self.total_events_pushed = 0;
+        self.total_events_requeued = 0;
+    }
+
     pub fn push_event(&mut self, event: FillEvent) -> Result<()> {
         require!(!self.is_full(), crate::errors::ErrorCode::EventQueueFull);
 
         self.events[self.tail as usize] = event;
-        self.tail = (self.tail + 1) % self.capacity;
+        self.tail = self.advance(self.tail);
+        self.total_events_pushed = self.total_events_pushed.saturating_add(1);
+
+        Ok(())
+    }
+
+    /// Write a popped event back at the tail. Counted in
+    /// total_events_requeued rather than total_events_pushed, so the pushed
+    /// count stays the number of distinct events.
+    pub fn requeue_event(&mut self, event: FillEvent) -> Result<()> {
+        require!(!self.is_full(), crate::errors::ErrorCode::EventQueueFull);
+
+        self.events[self.tail as usize] = event;
+        self.tail = self.advance(self.tail);
+        self.total_events_requeued = self.total_events_requeued.saturating_add(1);
 
         Ok(())
     }
@@ -41,7 +111,7 @@ impl EventQueue {
         require!(!self.is_empty(), crate::errors::ErrorCode::EventQueueEmpty);
 
         let event = self.events[self.head as usize];
-        self.head = (self.head + 1) % self.capacity;
+        self.head = self.advance(self.head);
 
         Ok(event)
     }
@@ -53,19 +123,47 @@ impl EventQueue {
         Ok(event)
     }
 
+    /// Seconds the oldest event has waited for the crank at `now`; 0 when
+    /// the queue is empty
+    pub fn oldest_event_age(&self, now: i64) -> i64 {
+        if self.is_empty() {
+            return 0;
+        }
+        now.saturating_sub(self.events[self.head as usize].timestamp)
+    }
+
+    /// The slot after `index`, wrapping to 0 at `capacity`. Compares instead
+    /// of taking `(index + 1) % capacity`, so it cannot overflow and an
+    /// out-of-range index still lands back inside the ring.
+    pub fn advance(&self, index: u64) -> u64 {
+        if index >= self.capacity.saturating_sub(1) {
+            0
+        } else {
+            index + 1
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.head == self.tail
     }
 
     pub fn is_full(&self) -> bool {
-        (self.tail + 1) % self.capacity == self.head
+        self.advance(self.tail) == self.head
     }
 
-    pub fn len(&self) -> u64 {
-        if self.tail >= self.head {
-            self.tail - self.head
-        } else {
-            self.capacity - self.head + self.tail
+    /// Events between head and tail. Lifted to u128 and wrapped with one
+    /// modulo, so it holds for either ordering of head and tail.
+    pub fn logical_len(&self) -> u64 {
+        let capacity = self.capacity as u128;
+        if capacity == 0 {
+            return 0;
         }
+        ((self.tail as u128 + capacity - self.head as u128 % capacity) % capacity) as u64
+    }
+
+    /// Events that can still be pushed; one slot always stays empty so a
+    /// full queue is distinguishable from an empty one
+    pub fn free_slots(&self) -> u64 {
+        self.capacity.saturating_sub(1) - self.logical_len()
     }
 }
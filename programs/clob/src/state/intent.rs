@@ -0,0 +1,95 @@
+use super::{Side, TimeInForce};
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+// Prepended to the Borsh bytes before signing, so an intent of one kind can
+// never be replayed as the other
+pub const ORDER_INTENT_DOMAIN: &[u8] = b"clob:order-intent:v1";
+pub const CANCEL_INTENT_DOMAIN: &[u8] = b"clob:cancel-intent:v1";
+
+/// An order a trader signed off-chain for a relayer to submit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OrderIntent {
+    pub market: Pubkey,
+    pub side: Side,
+    pub price: u64,                 // Price in quote_tick_size units
+    pub quantity: u64,              // Quantity in base_lot_size units
+    pub time_in_force: TimeInForce, // Time in force type
+    pub client_order_id: u64,       // Stored on the order and echoed in SignedIntentExecuted
+    pub expiry: i64,                // Unix timestamp after which it is rejected
+    pub nonce: u64,                 // Must be at least the owner's next_intent_nonce
+}
+
+/// A cancel a trader signed off-chain for a relayer to submit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CancelIntent {
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub side: Side,
+    pub expiry: i64, // Unix timestamp after which it is rejected
+    pub nonce: u64,  // Must be at least the owner's next_intent_nonce
+}
+
+impl OrderIntent {
+    /// The bytes the trader signs
+    pub fn message(&self) -> Vec<u8> {
+        [ORDER_INTENT_DOMAIN, &borsh::to_vec(self).unwrap()].concat()
+    }
+}
+
+impl CancelIntent {
+    /// The bytes the trader signs
+    pub fn message(&self) -> Vec<u8> {
+        [CANCEL_INTENT_DOMAIN, &borsh::to_vec(self).unwrap()].concat()
+    }
+}
+
+/// Require that the instruction just before the current one is an ed25519
+/// program instruction verifying one signature by `signer` over exactly
+/// `message`, with all data inline. The runtime has already checked the
+/// signature itself by the time this program runs.
+pub fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidIntentSignature);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidIntentSignature
+    );
+
+    // [num_signatures: u8, padding: u8, offsets: 7 x u16, ...]
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        ErrorCode::InvalidIntentSignature
+    );
+    let field = |index: usize| u16::from_le_bytes([data[2 + 2 * index], data[3 + 2 * index]]);
+    let (signature_ix, public_key_offset, public_key_ix) = (field(1), field(2), field(3));
+    let (message_offset, message_size, message_ix) = (field(4), field(5), field(6));
+    // u16::MAX points each offset at the ed25519 instruction's own data
+    require!(
+        signature_ix == u16::MAX && public_key_ix == u16::MAX && message_ix == u16::MAX,
+        ErrorCode::InvalidIntentSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset as usize..public_key_offset as usize + 32)
+        .ok_or(ErrorCode::InvalidIntentSignature)?;
+    let signed = data
+        .get(message_offset as usize..message_offset as usize + message_size as usize)
+        .ok_or(ErrorCode::InvalidIntentSignature)?;
+    require!(
+        public_key == signer.as_ref() && signed == message,
+        ErrorCode::InvalidIntentSignature
+    );
+    Ok(())
+}
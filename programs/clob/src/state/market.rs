@@ -1,3 +1,5 @@
+use super::{EventQueue, Order, Side, TieBreakMode};
+use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
 
 #[account]
@@ -10,9 +12,291 @@ pub struct Market {
     pub quote_vault: Pubkey,
     pub bids: Pubkey,
     pub asks: Pubkey,
-    pub event_queue: Pubkey,  // Event queue for fill events
-    pub base_lot_size: u64,   // Minimum base asset unit size
-    pub quote_tick_size: u64, // Minimum quote asset price tick size
-    pub next_order_id: u64,   // Auto-incrementing order ID counter
+    pub event_queue: Pubkey,              // Event queue for fill events
+    pub base_lot_size: u64,               // Minimum base asset unit size
+    pub quote_tick_size: u64,             // Minimum quote asset price tick size
+    pub next_order_id: u64,               // Auto-incrementing order ID counter
+    pub rent_payer: Pubkey,               // Funded book and queue accounts, refunded on close
+    pub tie_break_mode: TieBreakMode,     // How equal-price resting orders are prioritised
+    pub close_grace_until: i64,           // Force-cancel allowed from here once closing; 0 = open
+    pub max_orders_per_slot_per_user: u8, // Placement cap per user per slot; 0 = unlimited
+    pub book_seq_num: u64,                // Bumped on every BookDelta
+    pub flags: u8,                        // MARKET_FLAG_* bits
+    pub cross_authority: Pubkey,          // Signs submit_cross; default = disabled
+    pub max_cross_trade_through: u64,     // Ticks a cross may trade through the book
+    pub deposits_paused: bool,            // Emergency stop for deposit
+    pub withdrawals_paused: bool,         // Emergency stop for withdraw
+    pub event_queue_shard_count: u8,      // Fill queues in use: event_queue, then the shards below
+    pub event_queue_shards: [Pubkey; EXTRA_EVENT_QUEUE_SHARDS],
+    pub allow_locked_book: bool, // Let a remainder rest at or through the opposite best
+    pub order_bond_lamports: u64, // Held on the market while an order rests; 0 = none
+    pub max_event_age_secs: u32, // No matching while an older event awaits the crank; 0 = off
+    pub max_orders_per_level: u16, // Resting orders allowed at one price; 0 = unlimited
+    pub last_trade_price: u64,   // Price of the latest fill or cross, in ticks; 0 before any
+    pub last_settlement_ts: i64, // Set by settle_market; 0 = never settled
+    pub maker_fee_bps: u16,      // Charged on what a maker receives, at settlement
+    pub taker_fee_bps: u16,      // Charged on what a taker receives, at matching
+    pub base_fees_accrued: u64,  // Base fees held in the vault for the market
+    pub quote_fees_accrued: u64, // Quote fees held in the vault for the market
     pub bump: u8,
+    pub base_vault_bump: u8,
+    pub quote_vault_bump: u8,
+}
+
+/// Highest maker or taker fee: the whole amount received
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+/// Most event queues a market can spread its maker fills over
+pub const MAX_EVENT_QUEUE_SHARDS: usize = 4;
+/// Shards beyond the market's own event_queue
+pub const EXTRA_EVENT_QUEUE_SHARDS: usize = MAX_EVENT_QUEUE_SHARDS - 1;
+
+/// No new bids (including increases of resting bids)
+pub const MARKET_FLAG_HALT_BIDS: u8 = 1 << 0;
+/// No new asks (including increases of resting asks)
+pub const MARKET_FLAG_HALT_ASKS: u8 = 1 << 1;
+/// No new orders on either side
+pub const MARKET_FLAG_HALT_ALL: u8 = 1 << 2;
+pub const MARKET_FLAGS_ALL: u8 =
+    MARKET_FLAG_HALT_BIDS | MARKET_FLAG_HALT_ASKS | MARKET_FLAG_HALT_ALL;
+
+impl Market {
+    pub fn is_closing(&self) -> bool {
+        self.close_grace_until != 0
+    }
+
+    /// Stored bump of the vault for `mint`, so vault seeds constraints skip
+    /// find_program_address. Any mint other than base gets the quote bump;
+    /// for a foreign mint the seeds then cannot match and the check fails.
+    pub fn vault_bump(&self, mint: &Pubkey) -> u8 {
+        if *mint == self.base_mint {
+            self.base_vault_bump
+        } else {
+            self.quote_vault_bump
+        }
+    }
+
+    /// Quote tokens for `quantity` lots at `price` ticks:
+    /// `price * quantity * quote_tick_size / base_lot_size`, rounded down.
+    /// When the tick is a whole multiple of the lot (e.g. stable pairs with
+    /// equal decimals and tick == lot) there is no division and so no
+    /// rounding at all; otherwise the product is taken in u128 so it only
+    /// fails when the result itself does not fit in a u64.
+    pub fn quote_amount(&self, price: u64, quantity: u64) -> Result<u64> {
+        if self.quote_tick_size.is_multiple_of(self.base_lot_size) {
+            return price
+                .checked_mul(quantity)
+                .and_then(|notional| {
+                    notional.checked_mul(self.quote_tick_size / self.base_lot_size)
+                })
+                .ok_or(error!(ErrorCode::MathOverflow));
+        }
+
+        let quote = (price as u128)
+            .checked_mul(quantity as u128)
+            .and_then(|notional| notional.checked_mul(self.quote_tick_size as u128))
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.base_lot_size as u128;
+        u64::try_from(quote).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Fee a taker pays on `amount` of the asset it receives
+    pub fn taker_fee(&self, amount: u64) -> Result<u64> {
+        fee_amount(amount, self.taker_fee_bps)
+    }
+
+    /// Fee a maker pays on `amount` of the asset it receives
+    pub fn maker_fee(&self, amount: u64) -> Result<u64> {
+        fee_amount(amount, self.maker_fee_bps)
+    }
+
+    /// Book `fee`, taken from what `side` received: base for a bid, quote
+    /// for an ask. The tokens stay in that vault.
+    pub fn accrue_fee(&mut self, side: Side, fee: u64) -> Result<()> {
+        let accrued = match side {
+            Side::Bid => &mut self.base_fees_accrued,
+            Side::Ask => &mut self.quote_fees_accrued,
+        };
+        *accrued = accrued.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Quote tokens a fill of `quantity` lots at `price` moves between taker
+    /// and maker, with `maker_remaining` lots left on the maker order after
+    /// it. Against a resting bid this is the share of the bid's reservation
+    /// the fill releases rather than a rounded amount of its own, so the
+    /// fills and the final refund of a bid always add up to exactly what it
+    /// reserved, with no rounding dust left behind in the vault.
+    pub fn fill_quote_amount(
+        &self,
+        maker_side: Side,
+        price: u64,
+        quantity: u64,
+        maker_remaining: u64,
+    ) -> Result<u64> {
+        match maker_side {
+            Side::Ask => self.quote_amount(price, quantity),
+            Side::Bid => {
+                let before = maker_remaining
+                    .checked_add(quantity)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                Ok(
+                    self.quote_amount(price, before)?
+                        - self.quote_amount(price, maker_remaining)?,
+                )
+            }
+        }
+    }
+
+    /// Base tokens in `quantity` lots
+    pub fn base_amount(&self, quantity: u64) -> Result<u64> {
+        quantity
+            .checked_mul(self.base_lot_size)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Tokens a resting order still has reserved (quote for bids, base for
+    /// asks)
+    pub fn reserved_amount(&self, side: Side, order: &Order) -> Result<u64> {
+        match side {
+            Side::Bid => self.quote_amount(order.price, order.remaining_quantity),
+            Side::Ask => self.base_amount(order.remaining_quantity),
+        }
+    }
+
+    /// Fail if a price level already holding `resting_at_level` orders
+    /// cannot take another under max_orders_per_level
+    pub fn check_level_room(&self, resting_at_level: usize) -> Result<()> {
+        require!(
+            self.max_orders_per_level == 0 || resting_at_level < self.max_orders_per_level as usize,
+            ErrorCode::PriceLevelFull
+        );
+        Ok(())
+    }
+
+    /// Fail if any of `event_queues` holds an event older than
+    /// max_event_age_secs. Takers settle at once but makers only when
+    /// cranked, so a stalled crank stops new fills instead of letting the
+    /// unsettled side grow.
+    pub fn check_settlement_backlog(
+        &self,
+        event_queues: &[AccountLoader<EventQueue>],
+        now: i64,
+    ) -> Result<()> {
+        if self.max_event_age_secs == 0 {
+            return Ok(());
+        }
+        for event_queue in event_queues.iter() {
+            require!(
+                event_queue.load()?.oldest_event_age(now) <= self.max_event_age_secs as i64,
+                ErrorCode::SettlementBacklog
+            );
+        }
+        Ok(())
+    }
+
+    /// Pay `lamports` of order bond held on the market account to `to`, the
+    /// order owner's UserBalance. Bonds collect there and are paid out to
+    /// the owner with its rent when the account is closed.
+    pub fn pay_order_bond(market: &AccountInfo, to: &AccountInfo, lamports: u64) -> Result<()> {
+        if lamports == 0 {
+            return Ok(());
+        }
+        let market_lamports = market
+            .lamports()
+            .checked_sub(lamports)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let to_lamports = to
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ErrorCode::MathOverflow)?;
+        **market.try_borrow_mut_lamports()? = market_lamports;
+        **to.try_borrow_mut_lamports()? = to_lamports;
+        Ok(())
+    }
+
+    /// Ticks for a price given as quote tokens per lot, the inverse of
+    /// `quote_amount(price, 1)`. Fails unless it lands exactly on a tick.
+    pub fn price_from_native(&self, quote_per_lot: u64) -> Result<u64> {
+        let scaled = (quote_per_lot as u128)
+            .checked_mul(self.base_lot_size as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let tick = self.quote_tick_size as u128;
+        require!(scaled.is_multiple_of(tick), ErrorCode::PriceNotTickAligned);
+        u64::try_from(scaled / tick).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Lots in `base` base tokens, the inverse of `base_amount`. Fails unless
+    /// it is a whole number of lots.
+    pub fn lots_from_native(&self, base: u64) -> Result<u64> {
+        require!(
+            base.is_multiple_of(self.base_lot_size),
+            ErrorCode::QuantityNotLotAligned
+        );
+        Ok(base / self.base_lot_size)
+    }
+
+    /// Whether new orders on `side` are currently blocked
+    pub fn is_side_halted(&self, side: Side) -> bool {
+        let side_flag = match side {
+            Side::Bid => MARKET_FLAG_HALT_BIDS,
+            Side::Ask => MARKET_FLAG_HALT_ASKS,
+        };
+        self.flags & (side_flag | MARKET_FLAG_HALT_ALL) != 0
+    }
+
+    /// Both sides halted: the book only shrinks, so it can be cleared
+    pub fn is_paused(&self) -> bool {
+        self.is_side_halted(Side::Bid) && self.is_side_halted(Side::Ask)
+    }
+
+    /// Whether a cross at `price` stays within `max_cross_trade_through`
+    /// ticks of the resting book: a buyer may not pay more than that above
+    /// the best ask, nor a seller accept that much below the best bid
+    pub fn cross_within_bounds(
+        &self,
+        price: u64,
+        best_bid: Option<u64>,
+        best_ask: Option<u64>,
+    ) -> bool {
+        let above_asks =
+            best_ask.is_some_and(|ask| price > ask.saturating_add(self.max_cross_trade_through));
+        let below_bids =
+            best_bid.is_some_and(|bid| price < bid.saturating_sub(self.max_cross_trade_through));
+        !above_asks && !below_bids
+    }
+
+    /// The event queue shard a fill for `maker_owner` goes to. Pubkeys are
+    /// uniformly distributed, so their leading bytes serve as the hash.
+    pub fn event_queue_shard(&self, maker_owner: &Pubkey) -> usize {
+        let mut hash = [0u8; 8];
+        hash.copy_from_slice(&maker_owner.as_ref()[..8]);
+        (u64::from_le_bytes(hash) % self.event_queue_shard_count.max(1) as u64) as usize
+    }
+
+    /// Whether `key` is one of this market's event queues
+    pub fn is_event_queue(&self, key: &Pubkey) -> bool {
+        let extra = self.event_queue_shard_count.saturating_sub(1) as usize;
+        *key == self.event_queue || self.event_queue_shards[..extra].contains(key)
+    }
+
+    /// Advance and return the book sequence number for a new BookDelta
+    pub fn next_book_seq_num(&mut self) -> Result<u64> {
+        self.book_seq_num = self
+            .book_seq_num
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(self.book_seq_num)
+    }
+}
+
+/// `bps` of `amount`, rounded up: any fill at a non-zero rate pays at
+/// least one token rather than rounding its fee away
+fn fee_amount(amount: u64, bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(bps as u128)
+        .and_then(|scaled| scaled.checked_add(MAX_FEE_BPS as u128 - 1))
+        .and_then(|scaled| scaled.checked_div(MAX_FEE_BPS as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    u64::try_from(fee).map_err(|_| error!(ErrorCode::MathOverflow))
 }
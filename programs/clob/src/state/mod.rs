@@ -1,9 +1,15 @@
 pub mod event_queue;
+pub mod intent;
 pub mod market;
 pub mod orderbook;
+pub mod trade_cursor;
+pub mod twap;
 pub mod user_balance;
 
 pub use event_queue::*;
+pub use intent::*;
 pub use market::*;
 pub use orderbook::*;
+pub use trade_cursor::*;
+pub use twap::*;
 pub use user_balance::*;
@@ -1,55 +1,82 @@
 use super::{
-    order::{Fill, Order, Side},
+    order::{Fill, Order, SelfTradeBehavior, Side, TieBreakMode, ORDER_FLAG_SESSION},
     traits::OrderBook,
 };
 use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 use bytemuck::{Pod, Zeroable};
-use std::marker::PhantomData;
+use std::{cmp::Ordering, marker::PhantomData};
 
-const MAX_ORDERS: usize = 1024; // Reduced to fit in Solana's stack limit
+// Sized so a book side account, owner table included, stays within the
+// 80752 bytes it took with 48-byte slots: the most 56-byte slots that fit
+const MAX_ORDERS: usize = 1285;
 
-/// Heap kind marker traits for order comparison
+/// Distinct owners one book side can hold resting orders for at a time
+pub const MAX_BOOK_OWNERS: usize = 256;
+
+/// Version of the book account layout, stamped at initialization. Bumped on
+/// every breaking layout change so a book written under an older layout is
+/// refused instead of misread.
+pub const BOOK_LAYOUT_VERSION: u8 = 2;
+
+/// Heap kind marker traits for price direction; ties at equal price are broken
+/// at runtime by the book's [`TieBreakMode`]
 pub trait Kind: Clone + Default + Copy + 'static {
-    /// Compare two orders based on the heap type (max or min)
-    fn compare(a: &Order, b: &Order) -> bool;
+    /// Order two prices so that `Greater` means `a` has better priority
+    fn compare_price(a: u64, b: u64) -> Ordering;
     const SIDE: Side;
 }
 
-/// Max heap - higher price first, then earlier timestamp (Bid side)
+/// Max heap - higher price first (Bid side)
 #[derive(Clone, Default, Copy)]
 pub struct Max;
 impl Kind for Max {
-    fn compare(a: &Order, b: &Order) -> bool {
-        match a.price.cmp(&b.price) {
-            std::cmp::Ordering::Greater => true,
-            std::cmp::Ordering::Less => false,
-            std::cmp::Ordering::Equal => a.timestamp < b.timestamp,
-        }
+    fn compare_price(a: u64, b: u64) -> Ordering {
+        a.cmp(&b)
     }
     const SIDE: Side = Side::Bid;
 }
 
-/// Min heap - lower price first, then earlier timestamp (Ask side)
+/// Min heap - lower price first (Ask side)
 #[derive(Clone, Default, Copy)]
 pub struct Min;
 impl Kind for Min {
-    fn compare(a: &Order, b: &Order) -> bool {
-        match a.price.cmp(&b.price) {
-            std::cmp::Ordering::Less => true,
-            std::cmp::Ordering::Greater => false,
-            std::cmp::Ordering::Equal => a.timestamp < b.timestamp,
-        }
+    fn compare_price(a: u64, b: u64) -> Ordering {
+        b.cmp(&a)
     }
     const SIDE: Side = Side::Ask;
 }
 
-/// Generic fixed-size orderbook implementation
+/// A resting order as stored on the book: an [`Order`] with the owner
+/// replaced by an index into the book's owner table (56 bytes instead of 88)
+#[derive(Clone, Copy, Default, Pod, Zeroable)]
+#[repr(C)]
+struct OrderSlot {
+    order_id: u64,
+    price: u64,
+    quantity: u64,
+    remaining_quantity: u64,
+    timestamp: i64,
+    client_order_id: u64,
+    owner_index: u16, // Entry in SimpleOrderBook::owners
+    flags: u8,
+    _padding: [u8; 5],
+}
+
+/// Generic fixed-size orderbook implementation. The header comes first so
+/// its version byte sits at a fixed offset across layouts.
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct SimpleOrderBook<K: Kind> {
-    data: [Order; MAX_ORDERS],
     len: u32,
+    tie_break: u8, // TieBreakMode discriminant, copied from the market at init
+    version: u8,   // BOOK_LAYOUT_VERSION the book was initialized under
+    _padding: [u8; 2],
+    hash: [u8; 32], // XOR of per-order hashes over all resting orders
+    owners: [Pubkey; MAX_BOOK_OWNERS], // Owner table, see OrderSlot.owner_index
+    owner_refs: [u16; MAX_BOOK_OWNERS], // Resting orders per owner entry; 0 = free
+    data: [OrderSlot; MAX_ORDERS],
     _kind: PhantomData<K>,
 }
 
@@ -59,8 +86,14 @@ unsafe impl<K: Kind> Zeroable for SimpleOrderBook<K> {}
 impl<K: Kind> Default for SimpleOrderBook<K> {
     fn default() -> Self {
         Self {
-            data: [Order::default(); MAX_ORDERS],
             len: 0,
+            tie_break: TieBreakMode::Time as u8,
+            version: BOOK_LAYOUT_VERSION,
+            _padding: [0; 2],
+            hash: [0; 32],
+            owners: [Pubkey::default(); MAX_BOOK_OWNERS],
+            owner_refs: [0; MAX_BOOK_OWNERS],
+            data: [OrderSlot::default(); MAX_ORDERS],
             _kind: PhantomData,
         }
     }
@@ -71,20 +104,97 @@ impl<K: Kind> SimpleOrderBook<K> {
         Self::default()
     }
 
+    pub fn with_tie_break(mode: TieBreakMode) -> Self {
+        let mut book = Self::default();
+        book.set_tie_break(mode);
+        book
+    }
+
+    /// Stamp a freshly zeroed book account with the current layout version
+    /// and the market's tie-break mode
+    pub fn init(&mut self, mode: TieBreakMode) {
+        self.version = BOOK_LAYOUT_VERSION;
+        self.set_tie_break(mode);
+    }
+
+    /// Refuse a book whose account data predates the current layout
+    pub fn check_layout(&self) -> Result<()> {
+        require!(
+            self.version == BOOK_LAYOUT_VERSION,
+            ErrorCode::BookLayoutMismatch
+        );
+        Ok(())
+    }
+
     pub fn len(&self) -> usize {
         self.len as usize
     }
 
+    pub fn tie_break(&self) -> TieBreakMode {
+        TieBreakMode::from_u8(self.tie_break)
+    }
+
+    /// Only valid on an empty book; changing it would break the heap invariant
+    pub fn set_tie_break(&mut self, mode: TieBreakMode) {
+        debug_assert!(self.is_empty());
+        self.tie_break = mode as u8;
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len == 0
     }
 
-    pub fn peek(&self) -> Option<&Order> {
-        if self.len == 0 {
-            None
-        } else {
-            Some(&self.data[0])
-        }
+    /// Resting orders in heap (not priority) order, owners resolved
+    pub fn orders(&self) -> Vec<Order> {
+        self.slots().iter().map(|slot| self.expand(slot)).collect()
+    }
+
+    /// Distinct owners with resting orders, i.e. occupied owner table entries
+    pub fn owner_count(&self) -> usize {
+        self.owner_refs.iter().filter(|refs| **refs > 0).count()
+    }
+
+    /// Resting orders `owner` has on this side, per the owner table's count
+    pub fn owner_order_count(&self, owner: &Pubkey) -> usize {
+        self.owner_index(owner)
+            .map_or(0, |index| self.owner_refs[index] as usize)
+    }
+
+    /// Ids of `owner`'s resting orders, lowest first
+    pub fn order_ids_of(&self, owner: &Pubkey) -> Vec<u64> {
+        self.order_ids_flagged(owner, 0)
+    }
+
+    /// Ids of `owner`'s resting session orders, as `order_ids_of`
+    pub fn session_order_ids_of(&self, owner: &Pubkey) -> Vec<u64> {
+        self.order_ids_flagged(owner, ORDER_FLAG_SESSION)
+    }
+
+    fn order_ids_flagged(&self, owner: &Pubkey, flags: u8) -> Vec<u64> {
+        let Some(index) = self.owner_index(owner) else {
+            return Vec::new();
+        };
+        let mut order_ids: Vec<u64> = self
+            .slots()
+            .iter()
+            .filter(|slot| slot.owner_index as usize == index && slot.flags & flags == flags)
+            .map(|slot| slot.order_id)
+            .collect();
+        order_ids.sort_unstable();
+        order_ids
+    }
+
+    fn slots(&self) -> &[OrderSlot] {
+        &self.data[..self.len as usize]
+    }
+
+    /// Rolling commitment over the resting orders, see [`compute_book_hash`]
+    pub fn book_hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    pub fn peek(&self) -> Option<Order> {
+        self.slots().first().map(|slot| self.expand(slot))
     }
 
     pub fn push(&mut self, item: Order) -> Result<()> {
@@ -92,15 +202,27 @@ impl<K: Kind> SimpleOrderBook<K> {
             return Err(error!(ErrorCode::OrderbookFull));
         }
 
+        let slot = OrderSlot {
+            order_id: item.order_id,
+            price: item.price,
+            quantity: item.quantity,
+            remaining_quantity: item.remaining_quantity,
+            timestamp: item.timestamp,
+            client_order_id: item.client_order_id,
+            owner_index: self.acquire_owner(&item.owner)?,
+            flags: item.flags,
+            _padding: [0; 5],
+        };
         let index = self.len as usize;
-        self.data[index] = item;
+        self.data[index] = slot;
         self.len += 1;
         self.bubble_up(index);
+        self.toggle_hash(&self.expand(&slot));
         Ok(())
     }
 
     pub fn pop(&mut self) -> Option<Order> {
-        match self.len {
+        let result = match self.len {
             0 => None,
             1 => {
                 self.len = 0;
@@ -114,7 +236,8 @@ impl<K: Kind> SimpleOrderBook<K> {
                 self.bubble_down(0);
                 Some(result)
             }
-        }
+        };
+        result.map(|slot| self.release(&slot))
     }
 
     pub fn remove<F>(&mut self, predicate: F) -> Option<Order>
@@ -122,29 +245,29 @@ impl<K: Kind> SimpleOrderBook<K> {
         F: Fn(&Order) -> bool,
     {
         let len = self.len as usize;
-        let position = (0..len).find(|&i| predicate(&self.data[i]))?;
+        let position = (0..len).find(|&i| predicate(&self.expand(&self.data[i])))?;
+        let slot = self.data[position];
+        let removed = self.release(&slot);
 
         match position {
             pos if pos == len - 1 => {
                 self.len -= 1;
-                Some(self.data[pos])
+                Some(removed)
             }
             0 => {
-                let result = self.data[0];
                 let last_index = (self.len - 1) as usize;
                 self.data[0] = self.data[last_index];
                 self.len -= 1;
                 self.bubble_down(0);
-                Some(result)
+                Some(removed)
             }
             pos => {
-                let removed_item = self.data[pos];
                 let last_index = (self.len - 1) as usize;
                 self.data[pos] = self.data[last_index];
                 self.len -= 1;
 
                 if pos > 0
-                    && K::compare(
+                    && self.outranks(
                         &self.data[pos],
                         &self.data[Self::parent_index(pos).unwrap()],
                     )
@@ -154,25 +277,263 @@ impl<K: Kind> SimpleOrderBook<K> {
                     self.bubble_down(pos);
                 }
 
-                Some(removed_item)
+                Some(removed)
             }
         }
     }
 
-    pub fn find<F>(&self, predicate: F) -> Option<&Order>
+    pub fn find<F>(&self, predicate: F) -> Option<Order>
     where
         F: Fn(&Order) -> bool,
     {
-        let len = self.len as usize;
-        (0..len).find_map(|i| {
-            if predicate(&self.data[i]) {
-                Some(&self.data[i])
-            } else {
-                None
+        self.slots()
+            .iter()
+            .map(|slot| self.expand(slot))
+            .find(|order| predicate(order))
+    }
+
+    /// Rebuild the full order held in `slot`
+    fn expand(&self, slot: &OrderSlot) -> Order {
+        Order {
+            order_id: slot.order_id,
+            owner: self.owners[slot.owner_index as usize],
+            price: slot.price,
+            quantity: slot.quantity,
+            remaining_quantity: slot.remaining_quantity,
+            timestamp: slot.timestamp,
+            flags: slot.flags,
+            _padding: [0; 7],
+            client_order_id: slot.client_order_id,
+        }
+    }
+
+    /// Bookkeeping for a slot leaving the book: drop it from the hash and its
+    /// owner's reference. Returns the full order.
+    fn release(&mut self, slot: &OrderSlot) -> Order {
+        let order = self.expand(slot);
+        self.toggle_hash(&order);
+
+        let index = slot.owner_index as usize;
+        self.owner_refs[index] -= 1;
+        if self.owner_refs[index] == 0 {
+            self.owners[index] = Pubkey::default();
+        }
+        order
+    }
+
+    /// Take a reference on `owner`'s table entry, claiming a free entry when
+    /// the owner has nothing resting on this side yet
+    fn acquire_owner(&mut self, owner: &Pubkey) -> Result<u16> {
+        let index = match self.owner_index(owner) {
+            Some(index) => index,
+            None => {
+                let free = self
+                    .owner_refs
+                    .iter()
+                    .position(|refs| *refs == 0)
+                    .ok_or(ErrorCode::BookOwnersFull)?;
+                self.owners[free] = *owner;
+                free
+            }
+        };
+        self.owner_refs[index] += 1;
+        Ok(index as u16)
+    }
+
+    /// Owner table entry in use by `owner`, if any
+    fn owner_index(&self, owner: &Pubkey) -> Option<usize> {
+        (0..MAX_BOOK_OWNERS).find(|&i| self.owner_refs[i] > 0 && self.owners[i] == *owner)
+    }
+
+    /// Priority of `a` relative to `b` (`Greater` sits higher in the heap):
+    /// better price first, then the tie-break mode, then lower order id so
+    /// equal timestamps stay FIFO
+    fn priority(&self, a: &OrderSlot, b: &OrderSlot) -> Ordering {
+        let tie_break = match self.tie_break() {
+            TieBreakMode::Time => b.timestamp.cmp(&a.timestamp),
+            TieBreakMode::Size => a
+                .remaining_quantity
+                .cmp(&b.remaining_quantity)
+                .then(b.timestamp.cmp(&a.timestamp)),
+        };
+        K::compare_price(a.price, b.price)
+            .then(tie_break)
+            .then(b.order_id.cmp(&a.order_id))
+    }
+
+    fn outranks(&self, a: &OrderSlot, b: &OrderSlot) -> bool {
+        self.priority(a, b) == Ordering::Greater
+    }
+
+    /// Whether a resting order at `resting_price` can fill an incoming order
+    /// limited at `limit_price`
+    pub fn crosses(resting_price: u64, limit_price: u64) -> bool {
+        match K::SIDE {
+            // This is a bid book: incoming ask order matches with bid orders at >= price
+            Side::Bid => resting_price >= limit_price,
+            // This is an ask book: incoming bid order matches with ask orders at <= price
+            Side::Ask => resting_price <= limit_price,
+        }
+    }
+
+    /// Match `incoming_order` against the book. Makers the taker owns are
+    /// handled by `self_trade`; with `None` the taker fills against them
+    /// like anyone else's. Makers removed under CancelProvide are handed
+    /// back in `cancelled` so the caller can refund them. An AbortTransaction
+    /// error leaves the book part way through matching, so the transaction
+    /// must fail with it.
+    pub fn match_orders_evicting(
+        &mut self,
+        incoming_order: &mut Order,
+        cancelled: &mut Vec<Order>,
+        self_trade: Option<SelfTradeBehavior>,
+    ) -> Result<Vec<Fill>> {
+        let mut fills = Vec::new();
+        // All-or-none makers too large for this taker, and the taker's own
+        // under DecrementTake, restored once matching ends
+        let mut skipped = Vec::new();
+
+        while incoming_order.remaining_quantity > 0 {
+            let best_price = match self.slots().first() {
+                Some(slot) => slot.price,
+                None => break,
+            };
+
+            if !Self::crosses(best_price, incoming_order.price) {
+                break; // No more matching possible
+            }
+
+            let mut existing_order = self.pop().unwrap();
+            if existing_order.owner == incoming_order.owner {
+                match self_trade {
+                    None => {}
+                    Some(SelfTradeBehavior::DecrementTake) => {
+                        incoming_order.remaining_quantity -= existing_order
+                            .remaining_quantity
+                            .min(incoming_order.remaining_quantity);
+                        skipped.push(existing_order);
+                        continue;
+                    }
+                    Some(SelfTradeBehavior::CancelProvide) => {
+                        cancelled.push(existing_order);
+                        continue;
+                    }
+                    Some(SelfTradeBehavior::AbortTransaction) => {
+                        return Err(ErrorCode::SelfTradeNotAllowed.into());
+                    }
+                }
+            }
+            if !existing_order.can_fill_against(incoming_order.remaining_quantity) {
+                skipped.push(existing_order);
+                continue;
+            }
+
+            let fill_quantity = existing_order
+                .remaining_quantity
+                .min(incoming_order.remaining_quantity);
+            // Fills trade at the maker's price, which is never past the
+            // taker's limit; any improvement stays with the taker
+            debug_assert!(Self::crosses(existing_order.price, incoming_order.price));
+
+            existing_order.remaining_quantity -= fill_quantity;
+            incoming_order.remaining_quantity -= fill_quantity;
+
+            let maker_order_closed = existing_order.remaining_quantity == 0;
+            fills.push(Fill {
+                maker_order_id: existing_order.order_id,
+                taker_order_id: incoming_order.order_id,
+                maker_owner: existing_order.owner,
+                maker_side: K::SIDE,
+                price: existing_order.price, // Use maker price
+                quantity: fill_quantity,
+                maker_remaining_quantity: existing_order.remaining_quantity,
+                maker_order_closed,
+            });
+
+            if existing_order.remaining_quantity > 0 {
+                self.push(existing_order)?;
             }
+        }
+
+        for order in skipped {
+            self.push(order)?;
+        }
+
+        Ok(fills)
+    }
+
+    /// Number of resting orders an incoming order limited at `limit_price`
+    /// could fill against; `crossing_orders` without the copy and sort
+    pub fn crossing_order_count(&self, limit_price: u64) -> usize {
+        self.slots()
+            .iter()
+            .filter(|slot| Self::crosses(slot.price, limit_price))
+            .count()
+    }
+
+    /// Id of `owner`'s resting order tagged `client_order_id`, if any
+    pub fn order_id_for_client_id(&self, owner: &Pubkey, client_order_id: u64) -> Option<u64> {
+        let index = self.owner_index(owner)?;
+        self.slots()
+            .iter()
+            .find(|slot| {
+                slot.owner_index as usize == index && slot.client_order_id == client_order_id
+            })
+            .map(|slot| slot.order_id)
+    }
+
+    /// Resting orders an incoming order limited at `limit_price` would fill
+    /// against, in the order `match_orders` would fill them. Read-only.
+    pub fn crossing_orders(&self, limit_price: u64) -> Vec<Order> {
+        let mut slots: Vec<OrderSlot> = self
+            .slots()
+            .iter()
+            .filter(|slot| Self::crosses(slot.price, limit_price))
+            .copied()
+            .collect();
+        slots.sort_unstable_by(|a, b| self.priority(b, a));
+        slots.iter().map(|slot| self.expand(slot)).collect()
+    }
+
+    /// Heap self-check: no order outranks its parent. Linear in the book
+    /// size, meant for tests and debugging rather than on-chain paths.
+    pub fn is_valid_heap(&self) -> bool {
+        (1..self.len as usize).all(|index| {
+            let parent = Self::parent_index(index).unwrap();
+            !self.outranks(&self.data[index], &self.data[parent])
         })
     }
 
+    /// Total remaining quantity resting at `price`, i.e. one L2 level
+    pub fn level_quantity(&self, price: u64) -> u64 {
+        self.slots()
+            .iter()
+            .filter(|slot| slot.price == price)
+            .map(|slot| slot.remaining_quantity)
+            .sum()
+    }
+
+    /// Every price with a resting order, ascending
+    pub fn distinct_price_levels(&self) -> Vec<u64> {
+        let mut prices: Vec<u64> = self.slots().iter().map(|slot| slot.price).collect();
+        prices.sort_unstable();
+        prices.dedup();
+        prices
+    }
+
+    /// Number of resting orders at `price`
+    pub fn level_order_count(&self, price: u64) -> usize {
+        self.slots()
+            .iter()
+            .filter(|slot| slot.price == price)
+            .count()
+    }
+
+    // XOR is its own inverse, so the same call adds or removes an order
+    fn toggle_hash(&mut self, order: &Order) {
+        xor_into(&mut self.hash, &order_hash(order));
+    }
+
     fn parent_index(index: usize) -> Option<usize> {
         if index == 0 {
             None
@@ -191,7 +552,7 @@ impl<K: Kind> SimpleOrderBook<K> {
 
     fn bubble_up(&mut self, mut index: usize) {
         while let Some(parent_idx) = Self::parent_index(index) {
-            if K::compare(&self.data[index], &self.data[parent_idx]) {
+            if self.outranks(&self.data[index], &self.data[parent_idx]) {
                 self.data.swap(index, parent_idx);
                 index = parent_idx;
             } else {
@@ -207,11 +568,11 @@ impl<K: Kind> SimpleOrderBook<K> {
             let left = Self::left_child_index(index);
             let right = Self::right_child_index(index);
 
-            if left < len && K::compare(&self.data[left], &self.data[best]) {
+            if left < len && self.outranks(&self.data[left], &self.data[best]) {
                 best = left;
             }
 
-            if right < len && K::compare(&self.data[right], &self.data[best]) {
+            if right < len && self.outranks(&self.data[right], &self.data[best]) {
                 best = right;
             }
 
@@ -228,6 +589,14 @@ impl<K: Kind> SimpleOrderBook<K> {
 // Implement OrderBook trait for the generic SimpleOrderBook
 impl<K: Kind> OrderBook for SimpleOrderBook<K> {
     fn insert_order(&mut self, order: Order) -> Result<()> {
+        // Two live orders with one id would make cancels and lookups ambiguous
+        require!(
+            !self
+                .slots()
+                .iter()
+                .any(|existing| existing.order_id == order.order_id),
+            ErrorCode::DuplicateOrderId
+        );
         self.push(order)
     }
 
@@ -239,59 +608,13 @@ impl<K: Kind> OrderBook for SimpleOrderBook<K> {
         self.peek().map(|order| order.price)
     }
 
+    /// The taker's own makers fill like any other
     fn match_orders(&mut self, incoming_order: &mut Order) -> Result<Vec<Fill>> {
-        let mut fills = Vec::new();
-
-        while incoming_order.remaining_quantity > 0 {
-            let best_order = match self.peek() {
-                Some(order) => *order,
-                None => break,
-            };
-
-            // Check if orders can match based on the Kind's side
-            let can_match = match K::SIDE {
-                Side::Bid => {
-                    // This is a bid book: incoming ask order matches with bid orders at >= price
-                    best_order.price >= incoming_order.price
-                }
-                Side::Ask => {
-                    // This is an ask book: incoming bid order matches with ask orders at <= price
-                    best_order.price <= incoming_order.price
-                }
-            };
-
-            if !can_match {
-                break; // No more matching possible
-            }
-
-            let mut existing_order = self.pop().unwrap();
-            let fill_quantity = existing_order
-                .remaining_quantity
-                .min(incoming_order.remaining_quantity);
-
-            let fill = Fill {
-                maker_order_id: existing_order.order_id,
-                taker_order_id: incoming_order.order_id,
-                maker_owner: existing_order.owner,
-                maker_side: K::SIDE,
-                price: existing_order.price, // Use maker price
-                quantity: fill_quantity,
-            };
-            fills.push(fill);
-
-            existing_order.remaining_quantity -= fill_quantity;
-            incoming_order.remaining_quantity -= fill_quantity;
-
-            if existing_order.remaining_quantity > 0 {
-                self.push(existing_order)?;
-            }
-        }
-
-        Ok(fills)
+        self.match_orders_evicting(incoming_order, &mut Vec::new(), None)
     }
 
     fn find_order_by_id(&self, order_id: u64) -> Option<Order> {
-        self.find(|order| order.order_id == order_id).copied()
+        self.find(|order| order.order_id == order_id)
     }
 
     fn len(&self) -> usize {
@@ -303,6 +626,28 @@ impl<K: Kind> OrderBook for SimpleOrderBook<K> {
     }
 }
 
+/// Hash of a single resting order over its full `Order` byte layout, so
+/// commitments do not depend on where the owner sits in the owner table
+pub fn order_hash(order: &Order) -> [u8; 32] {
+    hashv(&[bytemuck::bytes_of(order)]).to_bytes()
+}
+
+/// Recompute a book hash from a list of resting orders (in any order), e.g. to
+/// validate an off-chain snapshot against the on-chain `book_hash`
+pub fn compute_book_hash(orders: &[Order]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    for order in orders {
+        xor_into(&mut hash, &order_hash(order));
+    }
+    hash
+}
+
+fn xor_into(acc: &mut [u8; 32], value: &[u8; 32]) {
+    for (a, v) in acc.iter_mut().zip(value.iter()) {
+        *a ^= v;
+    }
+}
+
 /// Type aliases for convenience
 pub type BidOrderBook = SimpleOrderBook<Max>;
 pub type AskOrderBook = SimpleOrderBook<Min>;
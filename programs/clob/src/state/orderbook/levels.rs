@@ -0,0 +1,71 @@
+//! Aggregated (L2) view of one side of the book, and the level changes
+//! between two such views
+use super::bookside::{AskSide, BidSide};
+use super::order::{Order, Side};
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use std::collections::BTreeMap;
+
+/// Total remaining quantity resting at each price, in ascending price order
+pub type BookLevels = BTreeMap<u64, u64>;
+
+/// One price level whose resting quantity moved between two book states
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LevelChange {
+    pub price: u64,        // Price in quote_tick_size units
+    pub old_quantity: u64, // Lots resting before; 0 for a new level
+    pub new_quantity: u64, // Lots resting after; 0 for an emptied level
+}
+
+/// Sum the remaining quantity of `orders` per price
+pub fn book_levels(orders: &[Order]) -> BookLevels {
+    let mut levels = BookLevels::new();
+    for order in orders {
+        *levels.entry(order.price).or_insert(0) += order.remaining_quantity;
+    }
+    levels
+}
+
+/// Levels that differ between `old` and `new`, in ascending price order
+pub fn diff_levels(old: &BookLevels, new: &BookLevels) -> Vec<LevelChange> {
+    let mut prices: Vec<u64> = old.keys().chain(new.keys()).copied().collect();
+    prices.sort_unstable();
+    prices.dedup();
+
+    prices
+        .into_iter()
+        .filter_map(|price| {
+            let old_quantity = old.get(&price).copied().unwrap_or(0);
+            let new_quantity = new.get(&price).copied().unwrap_or(0);
+            (old_quantity != new_quantity).then_some(LevelChange {
+                price,
+                old_quantity,
+                new_quantity,
+            })
+        })
+        .collect()
+}
+
+/// Read the resting orders out of raw bids or asks account data, as fetched
+/// over RPC. The discriminator decides the side. The data is copied out, so
+/// it need not be aligned; anything that is not a whole book account is
+/// refused rather than read.
+pub fn decode_book_side(data: &[u8]) -> Result<(Side, Vec<Order>)> {
+    require!(
+        data.len() == 8 + std::mem::size_of::<BidSide>(),
+        ErrorCode::InvalidParameter
+    );
+    let (discriminator, body) = data.split_at(8);
+    if discriminator == BidSide::DISCRIMINATOR {
+        let side: BidSide = bytemuck::pod_read_unaligned(body);
+        side.orderbook.check_layout()?;
+        Ok((Side::Bid, side.orderbook.orders()))
+    } else if discriminator == AskSide::DISCRIMINATOR {
+        let side: AskSide = bytemuck::pod_read_unaligned(body);
+        side.orderbook.check_layout()?;
+        Ok((Side::Ask, side.orderbook.orders()))
+    } else {
+        err!(ErrorCode::InvalidParameter)
+    }
+}
@@ -1,11 +1,13 @@
 pub mod bookside;
 pub mod heap_orderbook;
+pub mod levels;
 pub mod order;
 pub mod traits;
-// pub mod vec_orderbook;
+pub mod vec_orderbook;
 
 pub use bookside::*;
 pub use heap_orderbook::*;
+pub use levels::*;
 pub use order::*;
 pub use traits::*;
-// pub use vec_orderbook::*;
+pub use vec_orderbook::*;
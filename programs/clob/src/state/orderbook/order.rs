@@ -22,6 +22,31 @@ pub struct Order {
     pub quantity: u64,           // Original quantity in base_lot_size units
     pub remaining_quantity: u64, // Remaining unfilled quantity
     pub timestamp: i64,          // Creation timestamp for price-time priority
+    pub flags: u8,               // ORDER_FLAG_* bits
+    pub _padding: [u8; 7],
+    pub client_order_id: u64, // Caller-chosen id, unique per owner and side while resting; 0 = none
+}
+
+/// Resting order only fills when a single taker can take all of its
+/// remaining quantity; smaller takers skip it
+pub const ORDER_FLAG_ALL_OR_NONE: u8 = 1 << 0;
+/// Placed under the owner's session: anyone may cancel it once the
+/// session lapses
+pub const ORDER_FLAG_SESSION: u8 = 1 << 1;
+
+impl Order {
+    pub fn is_all_or_none(&self) -> bool {
+        self.flags & ORDER_FLAG_ALL_OR_NONE != 0
+    }
+
+    pub fn is_session(&self) -> bool {
+        self.flags & ORDER_FLAG_SESSION != 0
+    }
+
+    /// Whether a taker with `taker_remaining` left may fill against this order
+    pub fn can_fill_against(&self, taker_remaining: u64) -> bool {
+        !self.is_all_or_none() || taker_remaining >= self.remaining_quantity
+    }
 }
 
 impl PartialOrd for Order {
@@ -53,12 +78,76 @@ pub enum Side {
 pub enum TimeInForce {
     #[default]
     GTC = 0, // Good-Till-Cancelled: Order remains active until explicitly cancelled
-    IOC = 1, // Immediate-Or-Cancel: Execute immediately, cancel any unfilled portion
-    FOK = 2, // Fill-Or-Kill: Either fill the entire order immediately or cancel it completely
+    IOC = 1,      // Immediate-Or-Cancel: Execute immediately, cancel any unfilled portion
+    FOK = 2,      // Fill-Or-Kill: Either fill the entire order immediately or cancel it completely
+    PostOnly = 3, // Rests like GTC, but fails with PostOnlyWouldCross rather than take liquidity
+}
+
+/// What a taker does on reaching a resting order of its own
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq, Default,
+)]
+#[repr(u8)]
+pub enum SelfTradeBehavior {
+    #[default]
+    DecrementTake = 0, // Leave the resting order; the taker gives up what would have matched it
+    CancelProvide = 1, // Take the resting order off the book and refund it, then keep matching
+    AbortTransaction = 2, // Fail with SelfTradeNotAllowed
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq, Default,
+)]
+#[repr(u8)]
+pub enum TieBreakMode {
+    #[default]
+    Time = 0, // Price-time: earlier orders fill first at equal price
+    Size = 1, // Price-size-time: larger remaining quantity fills first at equal price
+}
+
+impl TieBreakMode {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TieBreakMode::Size,
+            _ => TieBreakMode::Time,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderStatus {
+    Filled,          // Fully matched on entry, nothing rests
+    PartiallyFilled, // Partly matched, remainder rests on the book
+    Resting,         // Nothing matched, whole order rests on the book
+    Cancelled,       // IOC remainder discarded after any fills
+}
+
+/// What became of an order on placement, one value per outcome. Unlike
+/// OrderStatus it tells an IOC that filled nothing from one that filled part.
+/// A FOK that cannot fill in full fails with FillOrKillNotFilled instead.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillStatus {
+    Rejected,                 // Nothing filled and nothing rests
+    FullyFilled,              // Fully matched on entry
+    PartiallyFilledResting,   // Partly matched, remainder rests on the book
+    PartiallyFilledCancelled, // Partly matched, remainder discarded
+    Resting,                  // Nothing matched, whole order rests on the book
+}
+
+impl FillStatus {
+    pub fn new(filled_quantity: u64, remaining_quantity: u64, rests: bool) -> Self {
+        match (filled_quantity > 0, remaining_quantity > 0, rests) {
+            (_, false, _) => FillStatus::FullyFilled,
+            (true, true, true) => FillStatus::PartiallyFilledResting,
+            (true, true, false) => FillStatus::PartiallyFilledCancelled,
+            (false, true, true) => FillStatus::Resting,
+            (false, true, false) => FillStatus::Rejected,
+        }
+    }
 }
 
 // Trade execution result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Fill {
     pub maker_order_id: u64,
     pub taker_order_id: u64,
@@ -66,4 +155,6 @@ pub struct Fill {
     pub maker_side: Side,
     pub price: u64,
     pub quantity: u64,
+    pub maker_remaining_quantity: u64, // Left on the maker order after this fill
+    pub maker_order_closed: bool,      // This fill took the maker order off the book
 }
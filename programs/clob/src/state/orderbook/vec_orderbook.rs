@@ -1,45 +1,96 @@
 use super::{
-    order::{Fill, Order, Side},
+    heap_orderbook::compute_book_hash,
+    order::{Fill, Order, Side, TieBreakMode},
     traits::OrderBook,
 };
 use anchor_lang::prelude::*;
+use std::cmp::Ordering;
 
-// Vec-based implementation for initial version
+// Vec-based reference implementation, kept sorted in priority order. Not used
+// by any on-chain account; it is the model the heap book is tested against.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
 pub struct VecOrderBook {
     #[max_len(50)] // Maximum 50 orders for initial implementation
     pub orders: Vec<Order>,
     pub side: Side, // Bid or Ask
+    pub tie_break: TieBreakMode,
 }
 
 impl VecOrderBook {
     pub fn new(side: Side) -> Self {
+        Self::with_tie_break(side, TieBreakMode::Time)
+    }
+
+    pub fn with_tie_break(side: Side, tie_break: TieBreakMode) -> Self {
         Self {
             orders: Vec::new(),
             side,
+            tie_break,
+        }
+    }
+
+    /// Resting orders in priority order
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    pub fn peek(&self) -> Option<&Order> {
+        self.orders.first()
+    }
+
+    pub fn book_hash(&self) -> [u8; 32] {
+        compute_book_hash(&self.orders)
+    }
+
+    /// Resting orders an incoming order limited at `limit_price` would fill
+    /// against, in fill order
+    pub fn crossing_orders(&self, limit_price: u64) -> Vec<Order> {
+        self.orders
+            .iter()
+            .take_while(|order| self.crosses(order.price, limit_price))
+            .copied()
+            .collect()
+    }
+
+    fn crosses(&self, resting_price: u64, limit_price: u64) -> bool {
+        match self.side {
+            // incoming ask order matches with bid orders at >= price
+            Side::Bid => resting_price >= limit_price,
+            // incoming bid order matches with ask orders at <= price
+            Side::Ask => resting_price <= limit_price,
         }
     }
+
+    /// Whether `a` fills before `b`: better price, then the tie-break mode,
+    /// then lower order id
+    fn ranks_before(&self, a: &Order, b: &Order) -> bool {
+        let price = match self.side {
+            Side::Bid => a.price.cmp(&b.price),
+            Side::Ask => b.price.cmp(&a.price),
+        };
+        let tie_break = match self.tie_break {
+            TieBreakMode::Time => b.timestamp.cmp(&a.timestamp),
+            TieBreakMode::Size => a
+                .remaining_quantity
+                .cmp(&b.remaining_quantity)
+                .then(b.timestamp.cmp(&a.timestamp)),
+        };
+        price.then(tie_break).then(b.order_id.cmp(&a.order_id)) == Ordering::Greater
+    }
 }
 
 impl OrderBook for VecOrderBook {
     fn insert_order(&mut self, order: Order) -> Result<()> {
-        // Find insertion position to maintain price-time priority
-        let insert_pos = match self.side {
-            Side::Bid => {
-                // Bids: highest price first, then earliest time
-                self.orders.iter().position(|existing| {
-                    existing.price < order.price
-                        || (existing.price == order.price && existing.timestamp > order.timestamp)
-                })
-            }
-            Side::Ask => {
-                // Asks: lowest price first, then earliest time
-                self.orders.iter().position(|existing| {
-                    existing.price > order.price
-                        || (existing.price == order.price && existing.timestamp > order.timestamp)
-                })
-            }
-        };
+        require!(
+            self.find_order_by_id(order.order_id).is_none(),
+            crate::errors::ErrorCode::DuplicateOrderId
+        );
+
+        // Find insertion position to maintain priority order
+        let insert_pos = self
+            .orders
+            .iter()
+            .position(|existing| self.ranks_before(&order, existing));
 
         match insert_pos {
             Some(pos) => self.orders.insert(pos, order),
@@ -67,66 +118,54 @@ impl OrderBook for VecOrderBook {
 
     fn match_orders(&mut self, incoming_order: &mut Order) -> Result<Vec<Fill>> {
         let mut fills = Vec::new();
-        let mut orders_to_remove = Vec::new();
-
-        for (index, existing_order) in self.orders.iter_mut().enumerate() {
-            // Check if orders can match
-            let can_match = match self.side {
-                Side::Bid => {
-                    // incoming ask order matches with bid orders at >= price
-                    existing_order.price >= incoming_order.price
-                }
-                Side::Ask => {
-                    // incoming bid order matches with ask orders at <= price
-                    existing_order.price <= incoming_order.price
-                }
-            };
-
-            if !can_match {
-                break; // Orders are sorted, no more matches possible
+        // Index of the best order not skipped as an all-or-none maker
+        let mut next = 0;
+
+        while incoming_order.remaining_quantity > 0 {
+            match self.orders.get(next) {
+                Some(best) if self.crosses(best.price, incoming_order.price) => {}
+                _ => break, // Orders are sorted, no more matches possible
+            }
+
+            if !self.orders[next].can_fill_against(incoming_order.remaining_quantity) {
+                next += 1;
+                continue;
             }
 
-            // Calculate fill quantity
+            let mut existing_order = self.orders.remove(next);
             let fill_quantity = existing_order
                 .remaining_quantity
                 .min(incoming_order.remaining_quantity);
 
-            // Create fill record
-            let fill = Fill {
+            existing_order.remaining_quantity -= fill_quantity;
+            incoming_order.remaining_quantity -= fill_quantity;
+
+            fills.push(Fill {
                 maker_order_id: existing_order.order_id,
                 taker_order_id: incoming_order.order_id,
                 maker_owner: existing_order.owner,
-                maker_side: K::SIDE,
+                maker_side: self.side,
                 price: existing_order.price, // Use maker price
                 quantity: fill_quantity,
-            };
-            fills.push(fill);
-
-            // Update quantities
-            existing_order.remaining_quantity -= fill_quantity;
-            incoming_order.remaining_quantity -= fill_quantity;
-
-            // Mark fully filled orders for removal
-            if existing_order.remaining_quantity == 0 {
-                orders_to_remove.push(index);
-            }
-
-            // If incoming order is fully filled, stop matching
-            if incoming_order.remaining_quantity == 0 {
-                break;
+                maker_remaining_quantity: existing_order.remaining_quantity,
+                maker_order_closed: existing_order.remaining_quantity == 0,
+            });
+
+            // Re-insert rather than update in place: under size priority a
+            // partial fill can change the order's position
+            if existing_order.remaining_quantity > 0 {
+                self.insert_order(existing_order)?;
             }
         }
 
-        // Remove fully filled orders (in reverse order to maintain indices)
-        for &index in orders_to_remove.iter().rev() {
-            self.orders.remove(index);
-        }
-
         Ok(fills)
     }
 
     fn find_order_by_id(&self, order_id: u64) -> Option<Order> {
-        self.orders.iter().find(|order| order.order_id == order_id).copied()
+        self.orders
+            .iter()
+            .find(|order| order.order_id == order_id)
+            .copied()
     }
 
     fn len(&self) -> usize {
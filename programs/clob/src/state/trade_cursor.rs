@@ -0,0 +1,95 @@
+use super::{EventQueue, FillEvent, Side, FILL_EVENT_KIND_CROSS, FILL_EVENT_KIND_FILL};
+use anchor_lang::prelude::*;
+
+/// Most trades one read_trades call returns; keeps the result inside the
+/// 1024 bytes of return data
+pub const MAX_TRADES_PER_READ: usize = 20;
+
+/// A client's position in one event queue's trade stream. Every event written
+/// at the tail, pushed or requeued, is numbered by the queue's `next_seq` at
+/// the time, so the cursor survives the crank draining the queue and the ring
+/// wrapping.
+#[account]
+#[derive(InitSpace)]
+pub struct TradeCursor {
+    pub owner: Pubkey,
+    pub event_queue: Pubkey, // Queue the stream is read from
+    pub next_seq: u64,       // Sequence number of the next event to read
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeRecord {
+    pub seq: u64, // Position in the queue's event stream
+    pub maker_order_id: u64,
+    pub taker_order_id: u64,
+    pub price: u64,    // Price in quote_tick_size units
+    pub quantity: u64, // Lots traded
+    pub timestamp: i64,
+    pub maker_side: Side,
+}
+
+impl EventQueue {
+    /// Sequence number of the next event written at the tail: every push
+    /// and every requeue so far
+    pub fn next_seq(&self) -> u64 {
+        self.total_events_pushed
+            .saturating_add(self.total_events_requeued)
+    }
+
+    /// Oldest sequence number whose slot has not been overwritten. Pushes
+    /// and requeues always write at tail, which starts at 0, so event `seq`
+    /// sits in slot `seq % capacity` until `capacity` more are written over
+    /// it.
+    pub fn oldest_retained_seq(&self) -> u64 {
+        self.next_seq().saturating_sub(self.capacity)
+    }
+
+    /// The event written as number `seq`, if the ring still holds it
+    pub fn event_at_seq(&self, seq: u64) -> Option<&FillEvent> {
+        if self.capacity == 0 || seq < self.oldest_retained_seq() || seq >= self.next_seq() {
+            return None;
+        }
+        self.events.get((seq % self.capacity) as usize)
+    }
+}
+
+impl TradeCursor {
+    /// Read up to `max_trades` trades past the cursor and move it on. Refunds
+    /// are stepped over, as are fills the crank requeued, which repeat one
+    /// already read; fills and crosses are trades. A cursor the ring has
+    /// lapped skips ahead to the oldest retained event; the returned flag
+    /// reports that gap.
+    pub fn read(&mut self, queue: &EventQueue, max_trades: usize) -> (Vec<TradeRecord>, bool) {
+        let oldest = queue.oldest_retained_seq();
+        let gap = self.next_seq < oldest;
+        let mut seq = self.next_seq.max(oldest);
+        let mut trades = Vec::new();
+
+        while trades.len() < max_trades {
+            let Some(event) = queue.event_at_seq(seq) else {
+                break;
+            };
+            let is_trade =
+                event.kind == FILL_EVENT_KIND_FILL || event.kind == FILL_EVENT_KIND_CROSS;
+            if is_trade && event.retry_count == 0 {
+                trades.push(TradeRecord {
+                    seq,
+                    maker_order_id: event.maker_order_id,
+                    taker_order_id: event.taker_order_id,
+                    price: event.price,
+                    quantity: event.quantity,
+                    timestamp: event.timestamp,
+                    maker_side: match event.maker_side {
+                        0 => Side::Bid,
+                        _ => Side::Ask,
+                    },
+                });
+            }
+            seq += 1;
+        }
+
+        self.next_seq = seq;
+        (trades, gap)
+    }
+}
@@ -0,0 +1,89 @@
+use super::Side;
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// A parent order fed into the market as IOC slices by execute_twap_slice,
+/// at most one per interval, until filled or expired
+#[account]
+#[derive(InitSpace)]
+pub struct TwapOrder {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub twap_id: u64, // Owner-chosen, part of the seeds
+    pub side: Side,
+    pub limit_price: u64,     // Slices never fill past this, in ticks
+    pub total_quantity: u64,  // Lots to fill over the whole schedule
+    pub slice_quantity: u64,  // Lots each slice offers at most
+    pub interval_secs: u32,   // Least time between two slices
+    pub expiry: i64,          // No slices after this unix timestamp; 0 = none
+    pub filled_quantity: u64, // Lots filled so far
+    pub slices_executed: u32, // Slices sent to the book so far
+    pub next_slice_at: i64,   // Earliest unix timestamp of the next slice
+    pub reward_lamports: u64, // Paid to whoever executes a slice, from the escrow
+    pub status: TwapStatus,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TwapStatus {
+    Active,   // Slices run as they come due
+    Paused,   // Last slice found too little balance; retried when next due
+    Finished, // total_quantity filled
+    Expired,  // Passed expiry before filling
+}
+
+impl TwapOrder {
+    pub fn is_done(&self) -> bool {
+        matches!(self.status, TwapStatus::Finished | TwapStatus::Expired)
+    }
+
+    /// Lots the next slice offers: a full slice, or whatever is left
+    pub fn next_slice_quantity(&self) -> u64 {
+        self.slice_quantity
+            .min(self.total_quantity.saturating_sub(self.filled_quantity))
+    }
+
+    /// Slices the schedule takes if every slice fills in full, which the
+    /// reward escrow is sized for
+    pub fn planned_slices(&self) -> Result<u64> {
+        require!(self.slice_quantity > 0, ErrorCode::InvalidParameter);
+        Ok(self.total_quantity.div_ceil(self.slice_quantity))
+    }
+
+    /// Record a slice that filled `filled` lots at `now`, finishing the
+    /// parent once everything is filled
+    pub fn record_slice(&mut self, filled: u64, now: i64) -> Result<()> {
+        self.filled_quantity = self
+            .filled_quantity
+            .checked_add(filled)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.slices_executed = self.slices_executed.saturating_add(1);
+        self.next_slice_at = now.saturating_add(self.interval_secs as i64);
+        self.status = if self.filled_quantity >= self.total_quantity {
+            TwapStatus::Finished
+        } else {
+            TwapStatus::Active
+        };
+        Ok(())
+    }
+
+    /// Move up to `reward_lamports` out of the escrow held on `twap` to
+    /// `to`, never dipping into its rent. Returns what was paid, which is
+    /// less once slices that filled short have used up the escrow.
+    pub fn pay_reward(&self, twap: &AccountInfo, to: &AccountInfo) -> Result<u64> {
+        let rent = Rent::get()?.minimum_balance(twap.data_len());
+        let paid = self
+            .reward_lamports
+            .min(twap.lamports().saturating_sub(rent));
+        if paid == 0 {
+            return Ok(0);
+        }
+        let to_lamports = to
+            .lamports()
+            .checked_add(paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        **twap.try_borrow_mut_lamports()? -= paid;
+        **to.try_borrow_mut_lamports()? = to_lamports;
+        Ok(paid)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::errors::ErrorCode;
+use crate::state::Side;
 use anchor_lang::prelude::*;
 
 #[account]
@@ -8,4 +10,137 @@ pub struct UserBalance {
     pub base_balance: u64,
     pub quote_balance: u64,
     pub bump: u8,
+    pub last_order_slot: u64,     // Slot of the most recent order placement
+    pub orders_this_slot: u8,     // Placements so far in last_order_slot
+    pub next_intent_nonce: u64,   // Lowest nonce a signed intent may still use
+    pub position_base_net: i64,   // Base bought minus sold, native units
+    pub position_cost_quote: i64, // Quote cost basis of that position
+    pub realized_pnl_quote: i64,  // Cumulative realized PnL, native quote
+    pub open_order_count: u32,    // Orders resting on the book
+    pub schema_version: u8,       // USER_BALANCE_SCHEMA_VERSION when written
+    pub session_expiry: i64,      // After this, anyone may cancel session orders; 0 = none
 }
+
+impl UserBalance {
+    /// Count one order placement in `slot`, failing once `max_per_slot`
+    /// placements were already made in it. A cap of 0 disables the limit.
+    pub fn record_order_placement(&mut self, slot: u64, max_per_slot: u8) -> Result<()> {
+        if self.last_order_slot != slot {
+            self.last_order_slot = slot;
+            self.orders_this_slot = 0;
+        }
+        require!(
+            max_per_slot == 0 || self.orders_this_slot < max_per_slot,
+            ErrorCode::RateLimited
+        );
+        self.orders_this_slot = self.orders_this_slot.saturating_add(1);
+        Ok(())
+    }
+
+    /// Count an order that rests on the book after placement
+    pub fn record_order_opened(&mut self) {
+        self.open_order_count = self.open_order_count.saturating_add(1);
+    }
+
+    /// Count an order that left the book, whether filled, cancelled or
+    /// refunded
+    pub fn record_order_closed(&mut self) {
+        self.open_order_count = self.open_order_count.saturating_sub(1);
+    }
+
+    /// Whether session orders may be placed at `now`
+    pub fn session_active(&self, now: i64) -> bool {
+        now < self.session_expiry
+    }
+
+    /// Spend a signed intent's nonce. Nonces only need to increase, so a
+    /// trader may skip some, but none can be used twice.
+    pub fn use_intent_nonce(&mut self, nonce: u64) -> Result<()> {
+        require!(nonce >= self.next_intent_nonce, ErrorCode::IntentNonceUsed);
+        self.next_intent_nonce = nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Read a UserBalance from a raw account, e.g. a remaining account
+    /// resolved by PDA. Fails unless the data starts with UserBalance's
+    /// discriminator.
+    pub fn load_from(account_info: &AccountInfo) -> Result<Self> {
+        let data = account_info.try_borrow_data()?;
+        Self::try_deserialize(&mut data.as_ref())
+    }
+
+    /// Write back over the account `load_from` read. The discriminator is
+    /// rewritten ahead of the fields and the layout is fixed size, so
+    /// repeated round trips leave the account a valid UserBalance.
+    pub fn store_to(&self, account_info: &AccountInfo) -> Result<()> {
+        let mut data = account_info.try_borrow_mut_data()?;
+        let mut cursor = std::io::Cursor::new(data.as_mut());
+        self.try_serialize(&mut cursor)
+    }
+
+    /// Whether `account_info` is `owner`'s UserBalance on `market`, read from
+    /// its stored keys instead of re-deriving the PDA. UserBalances are only
+    /// ever created at that PDA, so a program-owned account with this
+    /// discriminator and these keys is it.
+    pub fn is_account_of(account_info: &AccountInfo, owner: &Pubkey, market: &Pubkey) -> bool {
+        if account_info.owner != &crate::ID {
+            return false;
+        }
+        let Ok(data) = account_info.try_borrow_data() else {
+            return false;
+        };
+        data.len() >= USER_BALANCE_SIZE
+            && data[..8] == *Self::DISCRIMINATOR
+            && data[USER_BALANCE_OWNER_OFFSET..USER_BALANCE_OWNER_OFFSET + 32] == owner.as_ref()[..]
+            && data[USER_BALANCE_MARKET_OFFSET..USER_BALANCE_MARKET_OFFSET + 32]
+                == market.as_ref()[..]
+    }
+
+    /// Track realized PnL for one fill that bought (`Bid`) or sold (`Ask`)
+    /// `base` for `quote`, using average cost: a fill in the direction of the
+    /// open position adds to it and its cost basis; an opposite fill closes
+    /// against the average entry (`position_cost_quote / position_base_net`)
+    /// and realizes the difference, and any excess opens a new position at
+    /// the fill's own price. Purely informational: balances never read these
+    /// fields, so the math saturates instead of failing a trade.
+    pub fn record_fill(&mut self, side: Side, base: u64, quote: u64) {
+        let (base, quote) = (base as i128, quote as i128);
+        // Signed so a long position carries a positive cost and a short one
+        // the (negative) proceeds it was opened for
+        let (trade_base, trade_cost) = match side {
+            Side::Bid => (base, quote),
+            Side::Ask => (-base, -quote),
+        };
+        let position = self.position_base_net as i128;
+        let cost = self.position_cost_quote as i128;
+
+        let (closed_cost, closing_cost) =
+            if position == 0 || trade_base == 0 || (position > 0) == (trade_base > 0) {
+                (0, 0)
+            } else {
+                let closing = trade_base.abs().min(position.abs());
+                (
+                    cost * closing / position.abs(),
+                    trade_cost * closing / trade_base.abs(),
+                )
+            };
+
+        self.realized_pnl_quote =
+            saturate_i64(self.realized_pnl_quote as i128 - closing_cost - closed_cost);
+        self.position_base_net = saturate_i64(position + trade_base);
+        self.position_cost_quote = saturate_i64(cost - closed_cost + trade_cost - closing_cost);
+    }
+}
+
+fn saturate_i64(value: i128) -> i64 {
+    value.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+// Byte offsets into UserBalance account data (after the 8-byte discriminator),
+// for getProgramAccounts memcmp filters
+pub const USER_BALANCE_OWNER_OFFSET: usize = 8;
+pub const USER_BALANCE_MARKET_OFFSET: usize = USER_BALANCE_OWNER_OFFSET + 32;
+pub const USER_BALANCE_SIZE: usize = 8 + UserBalance::INIT_SPACE;
+/// Bumped whenever UserBalance gains fields; migrate_user_balance brings
+/// older accounts up to it. Fields are only ever appended.
+pub const USER_BALANCE_SCHEMA_VERSION: u8 = 2;
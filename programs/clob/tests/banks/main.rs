@@ -0,0 +1,18 @@
+// The core suites again, on solana-program-test's bank instead of LiteSVM, to
+// catch where LiteSVM diverges from the validator's runtime (compute
+// metering, sysvars, CPI depth). Run with `cargo test --test banks`.
+
+// The fixtures are shared with the LiteSVM binary; most go unused here
+#![allow(dead_code, unused_imports)]
+
+#[path = "../svm/mod.rs"]
+mod svm;
+
+#[path = "../cases/test_consume_events.rs"]
+mod test_consume_events;
+#[path = "../cases/test_orderbook_workflow.rs"]
+mod test_orderbook_workflow;
+#[path = "../cases/test_time_in_force.rs"]
+mod test_time_in_force;
+
+const BACKEND: svm::Backend = svm::Backend::Banks;
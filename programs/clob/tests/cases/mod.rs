@@ -1,4 +1,71 @@
+pub mod test_all_or_none;
+pub mod test_balance_changed;
+pub mod test_book_delta;
+pub mod test_book_hash;
+pub mod test_book_levels;
+pub mod test_book_owner_table;
+pub mod test_book_state;
+pub mod test_cancel_all_my_orders;
+pub mod test_cancel_and_credit;
+pub mod test_cancel_to_wallet;
+pub mod test_clear_book;
+pub mod test_client_order_id;
+pub mod test_close_market;
 pub mod test_consume_events;
+pub mod test_cross_market_balance;
+pub mod test_delegated_deposit;
+pub mod test_duplicate_order_id;
+pub mod test_error_codes;
+pub mod test_event_ordering;
+pub mod test_event_queue_backlog;
+pub mod test_event_queue_shards;
+pub mod test_event_queue_wrap;
+pub mod test_fees;
+pub mod test_fill_status;
+pub mod test_force_cancel_all;
+pub mod test_golden_layouts;
+pub mod test_heap_remove;
+pub mod test_increase_order;
+pub mod test_increase_order_size;
+pub mod test_invalid_book_account;
+pub mod test_locked_book;
+pub mod test_market_halt;
+pub mod test_market_order;
+pub mod test_migrate_user_balance;
+pub mod test_mixed_token_programs;
+pub mod test_modify_order;
+pub mod test_native_units;
+pub mod test_next_order_id;
+pub mod test_open_order_count;
+pub mod test_order_bond;
+pub mod test_orderbook_differential;
 pub mod test_orderbook_workflow;
+pub mod test_pause_flags;
+pub mod test_pnl_events;
+pub mod test_post_only;
+pub mod test_price_improvement;
+pub mod test_price_level_limit;
+pub mod test_price_levels;
+pub mod test_quote_math;
+pub mod test_quote_order;
+pub mod test_rate_limit;
+pub mod test_realized_pnl;
+pub mod test_reclaim_orphaned_account;
+pub mod test_self_trade;
+pub mod test_session_orders;
+pub mod test_settle_market;
+pub mod test_settlement_backlog;
+pub mod test_signed_intents;
+pub mod test_submit_cross;
+pub mod test_swap_exact_in;
+pub mod test_tie_break;
 pub mod test_time_in_force;
+pub mod test_trade_cursor;
+pub mod test_transfer_fee_vaults;
+pub mod test_twap_orders;
+pub mod test_update_market_params;
+pub mod test_user_balance_created;
+pub mod test_user_balance_layout;
 pub mod test_vault_workflow;
+pub mod test_withdraw_return;
+pub mod test_zero_copy_access;
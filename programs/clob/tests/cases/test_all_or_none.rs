@@ -0,0 +1,84 @@
+use clob::state::{AskOrderBook, OrderBook, Side, ORDER_FLAG_ALL_OR_NONE};
+
+use crate::svm::{order, TradingScenario};
+
+#[test]
+fn test_small_taker_skips_all_or_none_maker() {
+    let mut book = AskOrderBook::new();
+    // The AON ask has the better price, the plain one sits behind it
+    book.insert_order(order(1, 99, 10).flags(ORDER_FLAG_ALL_OR_NONE).build())
+        .unwrap();
+    book.insert_order(order(2, 100, 4).build()).unwrap();
+    let hash_before = book.book_hash();
+
+    let mut taker = order(3, 100, 3).build();
+    let fills = book.match_orders(&mut taker).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].maker_order_id, 2);
+    assert_eq!(fills[0].quantity, 3);
+    assert_eq!(taker.remaining_quantity, 0);
+
+    // The skipped maker is untouched and still best
+    let best = book.peek().unwrap();
+    assert_eq!(best.order_id, 1);
+    assert_eq!(best.remaining_quantity, 10);
+    assert_ne!(book.book_hash(), hash_before);
+
+    // A taker that can take all of it fills it in one go
+    let mut taker = order(4, 99, 10).build();
+    let fills = book.match_orders(&mut taker).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].maker_order_id, 1);
+    assert_eq!(fills[0].quantity, 10);
+    assert_eq!(book.len(), 1);
+}
+
+#[test]
+fn test_all_or_none_maker_with_no_alternative_leaves_taker_unfilled() {
+    let mut book = AskOrderBook::new();
+    book.insert_order(order(1, 100, 10).flags(ORDER_FLAG_ALL_OR_NONE).build())
+        .unwrap();
+    let hash_before = book.book_hash();
+
+    let mut taker = order(2, 100, 9).build();
+    assert!(book.match_orders(&mut taker).unwrap().is_empty());
+    assert_eq!(taker.remaining_quantity, 9);
+    assert_eq!(
+        book.book_hash(),
+        hash_before,
+        "Skipping leaves the book as it was"
+    );
+}
+
+#[tokio::test]
+async fn test_market_all_or_none_ask_is_skipped() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    market
+        .place_all_or_none_order(alice, Side::Ask, 1_000, 10)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Ask, 1_100, 2)
+        .await
+        .unwrap();
+
+    let aon = market.find_order_in_asks(1).unwrap();
+    assert!(aon.is_all_or_none());
+
+    // Charlie's 2-lot bid crosses both but can only take Bob's
+    market
+        .place_limit_order(charlie, Side::Bid, 1_100, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(market.find_order_in_asks(1).unwrap().remaining_quantity, 10);
+    assert!(market.find_order_in_asks(2).is_none());
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+
+    scenario.assert_conservation();
+}
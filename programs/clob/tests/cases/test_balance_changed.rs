@@ -0,0 +1,103 @@
+use anchor_lang::prelude::Pubkey;
+use clob::events::{
+    BalanceChanged, BALANCE_REASON_DEPOSIT, BALANCE_REASON_MAKER_FILL, BALANCE_REASON_ORDER_REFUND,
+    BALANCE_REASON_ORDER_RESERVE, BALANCE_REASON_TAKER_FILL, BALANCE_REASON_WITHDRAW,
+};
+use clob::state::Side;
+
+use crate::svm::{parse_events, TradingScenario};
+
+/// Net (base, quote) movement the events report for `user`
+fn summed_deltas(events: &[BalanceChanged], user: &Pubkey) -> (i64, i64) {
+    events
+        .iter()
+        .filter(|event| event.user == *user)
+        .fold((0, 0), |(base, quote), event| {
+            (base + event.base_delta, quote + event.quote_delta)
+        })
+}
+
+#[tokio::test]
+async fn test_balance_changed_sums_to_net_movement() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+    let base_mint = scenario.fixture.base_mint.mint;
+    let quote_mint = scenario.fixture.quote_mint.mint;
+
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    let mut events = Vec::new();
+    let mut record = |logs: &[String]| events.extend(parse_events::<BalanceChanged>(logs));
+
+    let meta = market
+        .deposit(&alice.keypair, base_mint, alice.base_account, 10_000_000)
+        .await
+        .unwrap();
+    record(&meta.logs);
+    let meta = market
+        .place_limit_order(&alice.keypair, Side::Ask, 5_000, 4)
+        .await
+        .unwrap();
+    record(&meta.logs);
+    let meta = market
+        .increase_order(&alice.keypair, 1, Side::Ask, 2)
+        .await
+        .unwrap();
+    record(&meta.logs);
+    let meta = market
+        .place_limit_order(&bob.keypair, Side::Bid, 5_000, 3)
+        .await
+        .unwrap();
+    record(&meta.logs);
+    let meta = market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    record(&meta.logs);
+    let meta = market
+        .cancel_order(&alice.keypair, 1, Side::Ask)
+        .await
+        .unwrap();
+    record(&meta.logs);
+    let meta = market
+        .withdraw(&bob.keypair, quote_mint, bob.quote_account, 1_000)
+        .await
+        .unwrap();
+    record(&meta.logs);
+
+    let reasons: Vec<u8> = events.iter().map(|event| event.reason).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            BALANCE_REASON_DEPOSIT,
+            BALANCE_REASON_ORDER_RESERVE,
+            BALANCE_REASON_ORDER_RESERVE,
+            BALANCE_REASON_TAKER_FILL,
+            BALANCE_REASON_MAKER_FILL,
+            BALANCE_REASON_ORDER_REFUND,
+            BALANCE_REASON_WITHDRAW,
+        ]
+    );
+    assert!(events
+        .iter()
+        .filter(|event| event.reason != BALANCE_REASON_DEPOSIT
+            && event.reason != BALANCE_REASON_WITHDRAW)
+        .all(|event| event.ref_id != 0));
+
+    for (user, before) in [(alice.pubkey(), alice_before), (bob.pubkey(), bob_before)] {
+        let after = market.get_user_balance(&user);
+        let (base, quote) = summed_deltas(&events, &user);
+        assert_eq!(
+            base,
+            after.base_balance as i64 - before.base_balance as i64,
+            "base for {user}"
+        );
+        assert_eq!(
+            quote,
+            after.quote_balance as i64 - before.quote_balance as i64,
+            "quote for {user}"
+        );
+    }
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use clob::events::BookDelta;
+use clob::state::{Side, TimeInForce};
+
+use crate::svm::{parse_events, TradingScenario};
+
+/// Client-side L2 view: (side, price) -> total resting quantity
+type Levels = BTreeMap<(u8, u64), u64>;
+
+fn side_key(side: Side) -> u8 {
+    match side {
+        Side::Bid => 0,
+        Side::Ask => 1,
+    }
+}
+
+fn apply(levels: &mut Levels, last_seq: &mut u64, logs: &[String]) {
+    for delta in parse_events::<BookDelta>(logs) {
+        assert_eq!(delta.seq_num, *last_seq + 1, "No gaps in the delta stream");
+        assert!(!delta.truncated);
+        *last_seq = delta.seq_num;
+        for change in delta.changes {
+            let key = (side_key(change.side), change.price);
+            if change.new_total_quantity == 0 {
+                levels.remove(&key);
+            } else {
+                levels.insert(key, change.new_total_quantity);
+            }
+        }
+    }
+}
+
+fn actual_levels(scenario: &TradingScenario) -> Levels {
+    let market = &scenario.market;
+    let mut levels = Levels::new();
+    market.with_bids(|bids| {
+        for order in bids.orders() {
+            *levels.entry((0, order.price)).or_default() += order.remaining_quantity;
+        }
+    });
+    market.with_asks(|asks| {
+        for order in asks.orders() {
+            *levels.entry((1, order.price)).or_default() += order.remaining_quantity;
+        }
+    });
+    levels
+}
+
+/// The last delta in `logs` carries each side's hash as it is on chain now
+fn assert_hashes_match(scenario: &TradingScenario, logs: &[String]) {
+    let deltas = parse_events::<BookDelta>(logs);
+    let delta = deltas.last().expect("A BookDelta");
+    let market = &scenario.market;
+    assert_eq!(delta.bids_hash, market.with_bids(|bids| bids.book_hash()));
+    assert_eq!(delta.asks_hash, market.with_asks(|asks| asks.book_hash()));
+}
+
+#[tokio::test]
+async fn test_replayed_deltas_rebuild_the_book() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    let mut levels = Levels::new();
+    let mut seq = 0;
+
+    let steps: Vec<(&_, Side, u64, u64, TimeInForce)> = vec![
+        (alice, Side::Ask, 105, 3, TimeInForce::GTC),
+        (bob, Side::Ask, 105, 2, TimeInForce::GTC),
+        (alice, Side::Ask, 107, 4, TimeInForce::GTC),
+        (charlie, Side::Bid, 100, 5, TimeInForce::GTC),
+        (bob, Side::Bid, 101, 1, TimeInForce::GTC),
+        // Sweeps both ask levels, then rests the last lot at 107
+        (charlie, Side::Bid, 107, 10, TimeInForce::GTC),
+        // Crosses the bids; the IOC remainder never rests
+        (bob, Side::Ask, 100, 9, TimeInForce::IOC),
+    ];
+    for (user, side, price, quantity, time_in_force) in steps {
+        let meta = market
+            .place_limit_order_with_tif(user, side, price, quantity, time_in_force)
+            .await
+            .unwrap();
+        apply(&mut levels, &mut seq, &meta.logs);
+        assert_eq!(levels, actual_levels(&scenario));
+        assert_hashes_match(&scenario, &meta.logs);
+    }
+
+    // Cancels and increases report the level they touched as well
+    let meta = market
+        .place_limit_order(alice, Side::Ask, 110, 2)
+        .await
+        .unwrap();
+    apply(&mut levels, &mut seq, &meta.logs);
+    let placed = market.get_market().next_order_id - 1;
+
+    let meta = market
+        .increase_order(alice, placed, Side::Ask, 3)
+        .await
+        .unwrap();
+    apply(&mut levels, &mut seq, &meta.logs);
+    assert_eq!(levels, actual_levels(&scenario));
+    assert_hashes_match(&scenario, &meta.logs);
+
+    let meta = market.cancel_order(alice, placed, Side::Ask).await.unwrap();
+    apply(&mut levels, &mut seq, &meta.logs);
+    assert_eq!(levels, actual_levels(&scenario));
+    assert_hashes_match(&scenario, &meta.logs);
+    assert_eq!(seq, market.get_market().book_seq_num);
+
+    scenario.assert_conservation();
+}
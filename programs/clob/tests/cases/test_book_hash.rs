@@ -0,0 +1,54 @@
+use clob::state::{compute_book_hash, AskOrderBook, BidOrderBook, OrderBook};
+
+use crate::svm::order;
+
+#[test]
+fn test_book_hash_insert_remove_roundtrip() {
+    let mut book = BidOrderBook::new();
+    assert_eq!(book.book_hash(), [0; 32], "Empty book hashes to zero");
+
+    book.insert_order(order(1, 100, 5).build()).unwrap();
+    book.insert_order(order(2, 101, 3).build()).unwrap();
+    let before = book.book_hash();
+    assert_eq!(before, compute_book_hash(&book.orders()));
+
+    book.insert_order(order(3, 99, 7).build()).unwrap();
+    assert_ne!(book.book_hash(), before, "Insert must change the hash");
+
+    let removed = book.remove_order(3).unwrap();
+    assert!(removed.is_some());
+    assert_eq!(
+        book.book_hash(),
+        before,
+        "Insert+remove of the same order must restore the prior hash"
+    );
+
+    // Removing the root takes a different branch in remove()
+    book.remove_order(2).unwrap().unwrap();
+    book.remove_order(1).unwrap().unwrap();
+    assert_eq!(book.book_hash(), [0; 32]);
+}
+
+#[test]
+fn test_book_hash_tracks_fills() {
+    let mut book = AskOrderBook::new();
+    book.insert_order(order(1, 10, 5).build()).unwrap();
+    book.insert_order(order(2, 11, 5).build()).unwrap();
+    book.insert_order(order(3, 12, 5).build()).unwrap();
+
+    // Partially fill the second level so one order is re-pushed with a new size
+    let mut taker = order(4, 11, 7).build();
+    let fills = book.match_orders(&mut taker).unwrap();
+    assert_eq!(fills.len(), 2);
+
+    assert_eq!(
+        book.book_hash(),
+        compute_book_hash(&book.orders()),
+        "Hash must match a full recompute after fills"
+    );
+
+    // Snapshot order does not matter
+    let mut snapshot = book.orders();
+    snapshot.reverse();
+    assert_eq!(book.book_hash(), compute_book_hash(&snapshot));
+}
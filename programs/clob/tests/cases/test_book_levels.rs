@@ -0,0 +1,93 @@
+use anchor_lang::Discriminator;
+use clob::state::{
+    book_levels, decode_book_side, diff_levels, AskSide, BidSide, LevelChange, Order, OrderBook,
+    Side,
+};
+
+use crate::svm::order;
+
+// Account data as an RPC notification would deliver it
+fn serialize_bids(orders: &[Order]) -> Vec<u8> {
+    let mut side = BidSide::default();
+    for order in orders {
+        side.orderbook.insert_order(*order).unwrap();
+    }
+    let mut data = BidSide::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&side));
+    data
+}
+
+fn serialize_asks(orders: &[Order]) -> Vec<u8> {
+    let mut side = AskSide::default();
+    for order in orders {
+        side.orderbook.insert_order(*order).unwrap();
+    }
+    let mut data = AskSide::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&side));
+    data
+}
+
+fn change(price: u64, old_quantity: u64, new_quantity: u64) -> LevelChange {
+    LevelChange {
+        price,
+        old_quantity,
+        new_quantity,
+    }
+}
+
+#[test]
+fn test_diff_between_two_serialized_bid_states() {
+    let before = serialize_bids(&[
+        order(1, 100, 5).build(),
+        order(2, 100, 3).build(),
+        order(3, 99, 4).build(),
+        order(4, 97, 1).build(),
+    ]);
+    // Order 2 partially filled, order 3 cancelled, a new level at 101 and
+    // order 4's level untouched
+    let after = serialize_bids(&[
+        order(1, 100, 5).build(),
+        order(2, 100, 1).build(),
+        order(4, 97, 1).build(),
+        order(5, 101, 2).build(),
+    ]);
+
+    let (side, old_orders) = decode_book_side(&before).unwrap();
+    assert_eq!(side, Side::Bid);
+    let (_, new_orders) = decode_book_side(&after).unwrap();
+
+    let changes = diff_levels(&book_levels(&old_orders), &book_levels(&new_orders));
+    assert_eq!(
+        changes,
+        vec![change(99, 4, 0), change(100, 8, 6), change(101, 0, 2)]
+    );
+}
+
+#[test]
+fn test_first_snapshot_diffs_against_empty_book() {
+    let data = serialize_asks(&[
+        order(1, 110, 2).build(),
+        order(2, 120, 3).build(),
+        order(3, 110, 1).build(),
+    ]);
+    let (side, orders) = decode_book_side(&data).unwrap();
+    assert_eq!(side, Side::Ask);
+
+    let levels = book_levels(&orders);
+    assert_eq!(
+        diff_levels(&Default::default(), &levels),
+        vec![change(110, 0, 3), change(120, 0, 3)]
+    );
+    // An unchanged book yields nothing
+    assert!(diff_levels(&levels, &levels).is_empty());
+}
+
+#[test]
+fn test_decode_rejects_other_accounts() {
+    let mut data = serialize_bids(&[order(1, 100, 1).build()]);
+    data[..8].copy_from_slice(&[0; 8]);
+    assert!(decode_book_side(&data).is_err());
+
+    let data = serialize_bids(&[order(1, 100, 1).build()]);
+    assert!(decode_book_side(&data[..data.len() - 1]).is_err());
+}
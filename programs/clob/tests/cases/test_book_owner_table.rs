@@ -0,0 +1,88 @@
+use anchor_lang::prelude::Pubkey;
+use bytemuck::Zeroable;
+use clob::errors::ErrorCode;
+use clob::state::{
+    compute_book_hash, AskOrderBook, BidOrderBook, BidSide, Order, OrderBook, MAX_BOOK_OWNERS,
+};
+
+use crate::svm::order;
+
+#[test]
+fn test_owner_table_fills_and_frees_entries() {
+    let mut book = BidOrderBook::new();
+    let owners: Vec<Pubkey> = (0..MAX_BOOK_OWNERS).map(|_| Pubkey::new_unique()).collect();
+    for (i, owner) in owners.iter().enumerate() {
+        book.insert_order(order(i as u64 + 1, 100, 1).owner(*owner).build())
+            .unwrap();
+    }
+    assert_eq!(book.owner_count(), MAX_BOOK_OWNERS);
+
+    // Known owners still fit, a new one does not
+    book.insert_order(order(1_000, 101, 1).owner(owners[7]).build())
+        .unwrap();
+    assert_eq!(book.owner_order_count(&owners[7]), 2);
+    let err = book.insert_order(order(1_001, 100, 1).build()).unwrap_err();
+    assert_eq!(err, ErrorCode::BookOwnersFull.into());
+
+    // The entry is only freed with the owner's last order
+    book.remove_order(8).unwrap();
+    assert_eq!(book.owner_count(), MAX_BOOK_OWNERS);
+    book.remove_order(1_000).unwrap();
+    assert_eq!(book.owner_count(), MAX_BOOK_OWNERS - 1);
+    assert_eq!(book.owner_order_count(&owners[7]), 0);
+
+    let newcomer = Pubkey::new_unique();
+    book.insert_order(order(1_002, 102, 1).owner(newcomer).build())
+        .unwrap();
+    assert_eq!(book.peek().unwrap().owner, newcomer);
+    assert_eq!(book.owner_order_count(&newcomer), 1);
+
+    // Owners resolve through the table everywhere orders come back out
+    let orders = book.orders();
+    assert_eq!(orders.len(), MAX_BOOK_OWNERS);
+    assert!(orders.iter().all(|order| order.owner != owners[7]));
+    assert_eq!(compute_book_hash(&orders), book.book_hash());
+    assert!(book.is_valid_heap());
+}
+
+#[test]
+fn test_matching_keeps_owner_refs_until_orders_leave() {
+    let mut book = AskOrderBook::new();
+    let maker = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    book.insert_order(order(1, 10, 5).owner(maker).build())
+        .unwrap();
+    book.insert_order(order(2, 11, 5).owner(maker).build())
+        .unwrap();
+    book.insert_order(order(3, 12, 5).owner(other).build())
+        .unwrap();
+
+    // Fully fills order 1 and partially fills order 2, which rests again
+    let mut taker = order(4, 11, 7).build();
+    let fills = book.match_orders(&mut taker).unwrap();
+    assert_eq!(fills.len(), 2);
+    assert!(fills.iter().all(|fill| fill.maker_owner == maker));
+    assert_eq!(book.owner_order_count(&maker), 1);
+    assert_eq!(book.find_order_by_id(2).unwrap().owner, maker);
+
+    let mut taker = order(5, 12, 10).build();
+    book.match_orders(&mut taker).unwrap();
+    assert!(book.is_empty());
+    assert_eq!(book.owner_count(), 0);
+    assert_eq!(book.book_hash(), [0; 32]);
+}
+
+#[test]
+fn test_book_layout_version_gate() {
+    // A freshly allocated (zeroed) account carries no layout version until
+    // initialize stamps it
+    let mut book = BidOrderBook::zeroed();
+    let err = book.check_layout().unwrap_err();
+    assert_eq!(err, ErrorCode::BookLayoutMismatch.into());
+    book.init(Default::default());
+    book.check_layout().unwrap();
+    BidOrderBook::new().check_layout().unwrap();
+
+    // Compact slots raised capacity without growing the account
+    assert!(std::mem::size_of::<BidSide>() <= 1024 * std::mem::size_of::<Order>() + 40);
+}
@@ -0,0 +1,79 @@
+use clob::state::Side;
+
+use crate::svm::market::BookState;
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_book_state_matches_individual_getters() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    let empty = market.get_book_state();
+    assert_eq!(
+        empty,
+        BookState {
+            bids: vec![],
+            asks: vec![],
+            best_bid: None,
+            best_ask: None,
+            spread: None,
+            event_queue_len: 0,
+            bid_count: 0,
+            ask_count: 0,
+        }
+    );
+
+    market
+        .place_limit_order(alice, Side::Ask, 12, 4)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Ask, 11, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(charlie, Side::Ask, 11, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 9, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 8, 1)
+        .await
+        .unwrap();
+    // Takes 1 from Alice's order at 11, leaving one fill to crank
+    market
+        .place_limit_order(bob, Side::Bid, 11, 1)
+        .await
+        .unwrap();
+
+    let state = market.get_book_state();
+    assert_eq!(
+        state,
+        BookState {
+            bids: vec![(9, 5), (8, 1)],
+            asks: vec![(11, 4), (12, 4)],
+            best_bid: Some(9),
+            best_ask: Some(11),
+            spread: Some(2),
+            event_queue_len: 1,
+            bid_count: 2,
+            ask_count: 3,
+        }
+    );
+
+    assert_eq!(state.bids, market.get_l2(Side::Bid));
+    assert_eq!(state.asks, market.get_l2(Side::Ask));
+    assert_eq!(state.best_bid, market.best_bid());
+    assert_eq!(state.best_ask, market.best_ask());
+    assert_eq!(state.event_queue_len, market.event_queue_len());
+    assert_eq!(state.bid_count, market.get_orderbook_order_count(Side::Bid));
+    assert_eq!(state.ask_count, market.get_orderbook_order_count(Side::Ask));
+
+    scenario.assert_conservation();
+}
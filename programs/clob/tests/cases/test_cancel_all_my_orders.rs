@@ -0,0 +1,58 @@
+use clob::state::Side;
+use clob::OrderCancelled;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_cancel_all_my_orders_in_batches() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let before = market.get_user_balance(&alice.pubkey());
+
+    for price in [900_000, 950_000, 990_000] {
+        market
+            .place_limit_order(alice, Side::Bid, price, 2)
+            .await
+            .unwrap();
+    }
+    for price in [1_100_000, 1_200_000] {
+        market
+            .place_limit_order(alice, Side::Ask, price, 3)
+            .await
+            .unwrap();
+    }
+    // A topped-up order is still one order
+    market.increase_order(alice, 1, Side::Bid, 1).await.unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 980_000, 1)
+        .await
+        .unwrap();
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 5);
+
+    let mut cancelled = 0;
+    for expected_remaining in [3u32, 1, 0] {
+        let outcome = market.cancel_all_my_orders(alice, 2).await;
+        assert!(outcome.success, "{:?}", outcome.logs);
+        assert_eq!(outcome.return_value::<u32>(), expected_remaining);
+        cancelled += outcome.events::<OrderCancelled>().len();
+    }
+    assert_eq!(cancelled, 5);
+
+    // Nothing left to do is not an error
+    let outcome = market.cancel_all_my_orders(alice, 2).await;
+    assert_eq!(outcome.return_value::<u32>(), 0);
+    assert!(outcome.events::<OrderCancelled>().is_empty());
+
+    // Every reservation came back and only the other user's order rests
+    let after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(after.base_balance, before.base_balance);
+    assert_eq!(after.quote_balance, before.quote_balance);
+    assert_eq!(after.open_order_count, 0);
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+    assert_eq!(market.get_l2(Side::Bid), vec![(980_000, 1)]);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,86 @@
+use clob::errors::ErrorCode;
+use clob::events::{BalanceChanged, OrderCancelled, BALANCE_REASON_SETTLEMENT_CREDIT};
+use clob::state::{Side, MARKET_FLAG_HALT_ALL};
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_authority_cancels_and_credits_settlement_account() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    // Charlie's balance stands in for the designated settlement account
+    let settlement = scenario.charlie.pubkey();
+
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 10_000_000, 3)
+        .await
+        .unwrap();
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let settlement_before = market.get_user_balance(&settlement);
+
+    // Only while the market is paused
+    assert_clob_error(
+        market
+            .cancel_and_credit(None, &alice.pubkey(), 1, Side::Bid, &settlement)
+            .await,
+        ErrorCode::MarketNotPaused,
+    );
+    market
+        .set_market_flags(None, MARKET_FLAG_HALT_ALL)
+        .await
+        .unwrap();
+
+    // Only the market authority
+    let bob = &scenario.bob;
+    assert_clob_error(
+        market
+            .cancel_and_credit(
+                Some(&bob.keypair),
+                &alice.pubkey(),
+                1,
+                Side::Bid,
+                &bob.pubkey(),
+            )
+            .await,
+        ErrorCode::Unauthorized,
+    );
+
+    // The owner must match the order
+    assert_clob_error(
+        market
+            .cancel_and_credit(None, &settlement, 1, Side::Bid, &settlement)
+            .await,
+        ErrorCode::Unauthorized,
+    );
+
+    let meta = market
+        .cancel_and_credit(None, &alice.pubkey(), 1, Side::Bid, &settlement)
+        .await
+        .expect("Authority cancel with credit");
+
+    // 3 lots at 10_000 quote each were reserved
+    let refund = 30_000;
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).quote_balance,
+        alice_before.quote_balance
+    );
+    assert_eq!(
+        market.get_user_balance(&settlement).quote_balance,
+        settlement_before.quote_balance + refund
+    );
+
+    let cancelled = parse_events::<OrderCancelled>(&meta.logs);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].owner, alice.pubkey());
+    assert_eq!(cancelled[0].remaining_quantity, 3);
+    let changes = parse_events::<BalanceChanged>(&meta.logs);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].user, settlement);
+    assert_eq!(changes[0].quote_delta, refund as i64);
+    assert_eq!(changes[0].reason, BALANCE_REASON_SETTLEMENT_CREDIT);
+    assert_eq!(changes[0].ref_id, 1);
+
+    scenario.assert_conservation();
+}
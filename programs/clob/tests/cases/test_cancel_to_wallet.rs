@@ -0,0 +1,50 @@
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_cancel_refunds_to_wallet() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let alice = &scenario.alice;
+
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 2_000, 5)
+        .await
+        .unwrap();
+
+    let balance_before = market.get_user_balance(&alice.keypair.pubkey());
+    let wallet_before = quote_mint.balance(alice.quote_account).await;
+    let vault_before = quote_mint.balance(market.quote_vault).await;
+
+    // The refund has to come out of the vault for the reserved asset
+    assert!(market
+        .cancel_order_to_wallet(&alice.keypair, 1, Side::Bid, alice.base_account)
+        .await
+        .is_err());
+
+    market
+        .cancel_order_to_wallet(&alice.keypair, 1, Side::Bid, alice.quote_account)
+        .await
+        .expect("Cancel with wallet refund");
+
+    // 5 lots at 2000 ticks: 2000 * 5 * 1_000 / 1_000_000 quote
+    let reserved = 10;
+    assert_eq!(
+        quote_mint.balance(alice.quote_account).await,
+        wallet_before + reserved
+    );
+    assert_eq!(
+        quote_mint.balance(market.quote_vault).await,
+        vault_before - reserved
+    );
+
+    let balance_after = market.get_user_balance(&alice.keypair.pubkey());
+    assert_eq!(balance_after.quote_balance, balance_before.quote_balance);
+    assert_eq!(balance_after.base_balance, balance_before.base_balance);
+    assert!(market.find_order_in_bids(1).is_none());
+
+    scenario.assert_conservation();
+}
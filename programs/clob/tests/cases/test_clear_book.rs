@@ -0,0 +1,92 @@
+use clob::errors::ErrorCode;
+use clob::events::{BookCleared, OrderCancelled};
+use clob::state::{Side, MARKET_FLAG_HALT_ALL, MARKET_FLAG_HALT_BIDS};
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_clear_book_in_batches_refunds_through_crank() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    for price in [10, 11, 12] {
+        market
+            .place_limit_order(&alice.keypair, Side::Bid, price, 2)
+            .await
+            .unwrap();
+    }
+    for price in [20, 21] {
+        market
+            .place_limit_order(&bob.keypair, Side::Ask, price, 3)
+            .await
+            .unwrap();
+    }
+
+    market
+        .set_market_flags(None, MARKET_FLAG_HALT_ALL)
+        .await
+        .unwrap();
+
+    // Two orders per call: bids go first, best price first
+    let meta = market.clear_book(2).await.expect("First batch");
+    let cancelled = parse_events::<OrderCancelled>(&meta.logs);
+    assert_eq!(
+        cancelled.iter().map(|c| c.order_id).collect::<Vec<_>>(),
+        vec![3, 2]
+    );
+    let progress = parse_events::<BookCleared>(&meta.logs);
+    assert_eq!(progress[0].cancelled, 2);
+    assert_eq!(progress[0].bids_remaining, 1);
+    assert_eq!(progress[0].asks_remaining, 2);
+
+    market.clear_book(2).await.expect("Second batch");
+    let meta = market.clear_book(2).await.expect("Last batch");
+    let progress = parse_events::<BookCleared>(&meta.logs);
+    assert_eq!(progress[0].cancelled, 1);
+    assert_eq!(progress[0].bids_remaining, 0);
+    assert_eq!(progress[0].asks_remaining, 0);
+    assert!(market.orderbooks_are_empty());
+
+    // Reservations are still held until the crank settles the refunds
+    assert!(market.get_user_balance(&alice.pubkey()).quote_balance < alice_before.quote_balance);
+
+    market
+        .consume_events(10, &[&alice.keypair, &bob.keypair])
+        .await
+        .unwrap();
+    for (user, before) in [(alice.pubkey(), alice_before), (bob.pubkey(), bob_before)] {
+        let after = market.get_user_balance(&user);
+        assert_eq!(after.base_balance, before.base_balance);
+        assert_eq!(after.quote_balance, before.quote_balance);
+    }
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_clear_book_requires_full_pause() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Bid, 10, 1)
+        .await
+        .unwrap();
+
+    assert_clob_error(market.clear_book(10).await, ErrorCode::MarketNotPaused);
+
+    // Halting one side still lets the other take new orders
+    market
+        .set_market_flags(None, MARKET_FLAG_HALT_BIDS)
+        .await
+        .unwrap();
+    assert_clob_error(market.clear_book(10).await, ErrorCode::MarketNotPaused);
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 1);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::Pubkey;
+use clob::errors::ErrorCode;
+use clob::events::{OrderCancelled, OrderPlaced};
+use clob::state::{AskOrderBook, OrderBook, Side};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, order, parse_events, TradingScenario};
+
+#[test]
+fn test_book_finds_client_id_per_owner() {
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut book = AskOrderBook::new();
+    book.insert_order(order(1, 100, 1).owner(alice).client_order_id(7).build())
+        .unwrap();
+    book.insert_order(order(2, 100, 1).owner(bob).client_order_id(7).build())
+        .unwrap();
+
+    assert_eq!(book.order_id_for_client_id(&alice, 7), Some(1));
+    assert_eq!(book.order_id_for_client_id(&bob, 7), Some(2));
+    assert_eq!(book.order_id_for_client_id(&alice, 8), None);
+    assert_eq!(book.order_id_for_client_id(&Pubkey::new_unique(), 7), None);
+    // Slots keep the client id through the round trip
+    assert_eq!(book.find_order_by_id(2).unwrap().client_order_id, 7);
+}
+
+#[tokio::test]
+async fn test_cancel_by_client_id_is_scoped_to_the_signer() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    // Both pick client id 7
+    let outcome = market
+        .place_limit_order_with_client_id(alice, Side::Ask, 1_000_000, 2, 7)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    assert_eq!(outcome.events::<OrderPlaced>()[0].client_order_id, 7);
+    let outcome = market
+        .place_limit_order_with_client_id(bob, Side::Ask, 1_100_000, 3, 7)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let bob_order = market
+        .find_order_in_asks_by_client_id(&bob.pubkey(), 7)
+        .unwrap();
+
+    let meta = market
+        .cancel_order_by_client_id(bob, 7, Side::Ask)
+        .await
+        .unwrap();
+    let cancelled = parse_events::<OrderCancelled>(&meta.logs);
+    assert_eq!(cancelled[0].order_id, bob_order.order_id);
+    assert!(market
+        .find_order_in_asks_by_client_id(&bob.pubkey(), 7)
+        .is_none());
+    let alice_order = market
+        .find_order_in_asks_by_client_id(&alice.pubkey(), 7)
+        .unwrap();
+    assert_eq!(alice_order.remaining_quantity, 2);
+    assert_eq!(market.get_l2(Side::Ask), vec![(1_000_000, 2)]);
+
+    // Bob has nothing left under 7, and Alice's is not his to find
+    assert_clob_error(
+        market.cancel_order_by_client_id(bob, 7, Side::Ask).await,
+        ErrorCode::OrderNotFound,
+    );
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_client_id_rules() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    market
+        .place_limit_order_with_client_id(alice, Side::Bid, 900_000, 1, 5)
+        .await;
+    // One resting order per client id, owner and side
+    assert_clob_error(
+        market
+            .place_limit_order_with_client_id(alice, Side::Bid, 800_000, 1, 5)
+            .await,
+        ErrorCode::DuplicateClientOrderId,
+    );
+    let outcome = market
+        .place_limit_order_with_client_id(alice, Side::Ask, 1_000_000, 1, 5)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+
+    assert_clob_error(
+        market.cancel_order_by_client_id(alice, 6, Side::Bid).await,
+        ErrorCode::OrderNotFound,
+    );
+    // 0 means no client id, so it never names an order
+    assert_clob_error(
+        market.cancel_order_by_client_id(alice, 0, Side::Bid).await,
+        ErrorCode::InvalidParameter,
+    );
+
+    // The id is free again once the order leaves the book
+    market
+        .cancel_order_by_client_id(alice, 5, Side::Bid)
+        .await
+        .unwrap();
+    let outcome = market
+        .place_limit_order_with_client_id(alice, Side::Bid, 800_000, 1, 5)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let order = market
+        .find_order_in_bids_by_client_id(&alice.pubkey(), 5)
+        .unwrap();
+    assert_eq!(order.price, 800_000);
+    assert_eq!(market.get_l2(Side::Bid), vec![(800_000, 1)]);
+}
@@ -0,0 +1,79 @@
+use crate::svm::{assert_conservation, market::MarketFixture, test::TestFixture, TradingUser};
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+use std::rc::Rc;
+
+#[tokio::test]
+async fn test_close_market_refunds_rent_payer() {
+    let fixture = TestFixture::new().await;
+    let ctx = Rc::clone(&fixture.ctx);
+
+    // A third party (not the market authority) funds the large accounts
+    let funder = ctx.borrow_mut().gen_and_fund_key();
+    let market = MarketFixture::new_with_params(
+        ctx.clone(),
+        &fixture.base_mint,
+        &fixture.quote_mint,
+        Some(&funder),
+        |_| {},
+    )
+    .await;
+    let funder_key = funder.pubkey();
+
+    assert_eq!(
+        market.get_market().rent_payer,
+        funder_key,
+        "Market should record who paid for the books"
+    );
+
+    let user = TradingUser::new(ctx.clone(), &fixture, &market, "alice").await;
+
+    // Closing is rejected while an order rests on the book
+    market
+        .place_limit_order(&user.keypair, Side::Ask, 10, 1)
+        .await
+        .unwrap();
+    assert!(
+        market.close_market(None, funder_key).await.is_err(),
+        "Close should fail with a non-empty book"
+    );
+    market
+        .cancel_order(&user.keypair, 1, Side::Ask)
+        .await
+        .unwrap();
+
+    // Only the authority may close, and only to the recorded rent payer
+    assert!(
+        market
+            .close_market(Some(&user.keypair), funder_key)
+            .await
+            .is_err(),
+        "Non-authority close should fail"
+    );
+    assert!(
+        market.close_market(None, user.pubkey()).await.is_err(),
+        "Refund to anyone but the rent payer should fail"
+    );
+
+    let (rent_total, funder_before) = {
+        let ctx = ctx.borrow();
+        let rent_total = ctx.lamports(&market.bids)
+            + ctx.lamports(&market.asks)
+            + ctx.lamports(&market.event_queue);
+        (rent_total, ctx.lamports(&funder_key))
+    };
+
+    market.close_market(None, funder_key).await.unwrap();
+
+    let ctx = ctx.borrow();
+    assert_eq!(
+        ctx.lamports(&funder_key),
+        funder_before + rent_total,
+        "Rent payer should get back exactly the book and queue rent"
+    );
+    assert_eq!(ctx.lamports(&market.bids), 0);
+    assert_eq!(ctx.lamports(&market.asks), 0);
+    assert_eq!(ctx.lamports(&market.event_queue), 0);
+
+    assert_conservation(&market, &[user.pubkey()]);
+}
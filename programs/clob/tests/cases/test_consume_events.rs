@@ -1,6 +1,10 @@
-use crate::svm::TwoUserScenario;
-use clob::state::Side;
-use solana_sdk::signature::Signer;
+use crate::svm::{assert_clob_error, parse_events, TradingScenario, TwoUserScenario};
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use clob::errors::ErrorCode;
+use clob::events::EventsConsumed;
+use clob::instructions::{MAX_CONSUME_EVENTS_ACCOUNTS, MAX_EVENT_RETRIES};
+use clob::state::{Side, UserBalance, USER_BALANCE_SIZE};
+use solana_sdk::signature::{Keypair, Signer};
 
 #[tokio::test]
 pub async fn test_consume_events_basic() {
@@ -73,3 +77,307 @@ pub async fn test_consume_events_basic() {
 
     println!("=== Test Complete ===");
 }
+
+#[tokio::test]
+async fn test_repeated_cranks_keep_maker_account_valid() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let before = market.get_user_balance(&alice.pubkey());
+
+    // Two fills against Alice, each cranked in its own transaction
+    for (price, quantity) in [(2_000_000, 5), (3_000_000, 2)] {
+        market
+            .place_limit_order(alice, Side::Ask, price, quantity)
+            .await
+            .unwrap();
+        market
+            .place_limit_order(bob, Side::Bid, price, quantity)
+            .await
+            .unwrap();
+        market.consume_events(10, &[alice]).await.unwrap();
+
+        // Rewritten in place: same size, discriminator intact, still loads
+        let data = market.get_user_balance_data(&alice.pubkey());
+        assert_eq!(data.len(), USER_BALANCE_SIZE);
+        assert_eq!(&data[..8], UserBalance::DISCRIMINATOR);
+        let balance = UserBalance::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(balance.owner, alice.pubkey());
+        assert_eq!(balance.market, market.market);
+        assert_eq!(balance.bump, before.bump);
+    }
+
+    // 5 lots at 2_000 quote plus 2 lots at 3_000 quote
+    let after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(after.base_balance, before.base_balance - 7_000_000);
+    assert_eq!(after.quote_balance, before.quote_balance + 16_000);
+
+    // Instructions that load it through Anchor still accept it
+    market
+        .withdraw(
+            alice,
+            market.quote_mint,
+            scenario.alice.quote_account,
+            16_000,
+        )
+        .await
+        .expect("Withdraw after repeated cranks");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_crank_cost_per_event_stays_flat() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    // Eight one-lot fills against the same maker
+    for _ in 0..8 {
+        market
+            .place_limit_order(alice, Side::Ask, 2_000_000, 1)
+            .await
+            .unwrap();
+    }
+    market
+        .place_limit_order(bob, Side::Bid, 2_000_000, 8)
+        .await
+        .unwrap();
+
+    let one = market.consume_events(1, &[alice]).await.unwrap();
+    let seven = market.consume_events(7, &[alice]).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+
+    // Makers are matched on their stored keys, so an event costs the
+    // settlement itself and no PDA derivation (a find_program_address alone
+    // is several 1_500 CU hashing attempts)
+    let per_event = (seven.compute_units_consumed - one.compute_units_consumed) / 6;
+    println!(
+        "consume_events: 1 event {} CU, 7 events {} CU, {} CU per event",
+        one.compute_units_consumed, seven.compute_units_consumed, per_event
+    );
+    assert!(per_event < 12_000, "{per_event} CU per event");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_crank_cost_with_two_makers() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    // Eight one-lot fills alternating between two makers
+    for _ in 0..4 {
+        for maker in [alice, bob] {
+            market
+                .place_limit_order(maker, Side::Ask, 2_000_000, 1)
+                .await
+                .unwrap();
+        }
+    }
+    market
+        .place_limit_order(charlie, Side::Bid, 2_000_000, 8)
+        .await
+        .unwrap();
+
+    let two = market.consume_events(2, &[alice, bob]).await.unwrap();
+    let six = market.consume_events(6, &[alice, bob]).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+
+    // Each maker's account is located once per crank, so the marginal event
+    // costs the same as with a single maker
+    let per_event = (six.compute_units_consumed - two.compute_units_consumed) / 4;
+    println!(
+        "consume_events, 2 makers: 2 events {} CU, 6 events {} CU, {} CU per event",
+        two.compute_units_consumed, six.compute_units_consumed, per_event
+    );
+    assert!(per_event < 12_000, "{per_event} CU per event");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_total_events_pushed_outlives_the_crank() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    for round in 1..=3u64 {
+        market
+            .place_limit_order(alice, Side::Ask, 2_000_000, 2)
+            .await
+            .unwrap();
+        market
+            .place_limit_order(bob, Side::Bid, 2_000_000, 2)
+            .await
+            .unwrap();
+        market.consume_events(10, &[alice]).await.unwrap();
+        assert_eq!(market.event_queue_len(), 0);
+
+        let total: u64 = market
+            .query_total_events_pushed(market.event_queue)
+            .await
+            .return_value();
+        assert_eq!(total, round);
+    }
+
+    // Only the market's own queues can be queried
+    assert!(!market.query_total_events_pushed(market.bids).await.success);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_consume_events_bounds_remaining_accounts() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 2000, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 2000, 5)
+        .await
+        .unwrap();
+
+    // Alice behind a padding of strangers, one account over the bound
+    let padding: Vec<Keypair> = (0..MAX_CONSUME_EVENTS_ACCOUNTS)
+        .map(|_| Keypair::new())
+        .collect();
+    let mut makers: Vec<&Keypair> = padding.iter().collect();
+    makers.push(alice);
+    assert_clob_error(
+        market.consume_events(10, &makers).await,
+        ErrorCode::TooManyAccounts,
+    );
+    assert_eq!(market.event_queue_len(), 1);
+
+    // Exactly at the bound is accepted
+    makers.remove(0);
+    market.consume_events(10, &makers).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_missing_maker_is_requeued_behind_the_next() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 2_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Ask, 2_000_000, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(charlie, Side::Bid, 2_000_000, 5)
+        .await
+        .unwrap();
+    let bob_quote = market.get_user_balance(&bob.pubkey()).quote_balance;
+
+    // Only Bob's account: his fill settles, Alice's waits at the tail
+    market.consume_events(10, &[bob]).await.unwrap();
+    assert_eq!(
+        market.get_user_balance(&bob.pubkey()).quote_balance - bob_quote,
+        6_000
+    );
+    let pending = market.pending_events();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        (pending[0].maker_owner, pending[0].retry_count),
+        (alice.pubkey(), 1)
+    );
+    // Moving an event to the tail does not count as a new one
+    let pushed = market
+        .query_total_events_pushed(market.event_queue)
+        .await
+        .return_value::<u64>();
+    assert_eq!(pushed, 2);
+
+    market.consume_events(10, &[alice]).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_event_requeued_past_the_cap_fails_the_crank() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 2_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 2_000_000, 2)
+        .await
+        .unwrap();
+
+    // Each crank visits the event once
+    for retries in 1..=MAX_EVENT_RETRIES {
+        market.consume_events(10, &[bob]).await.unwrap();
+        assert_eq!(market.pending_events()[0].retry_count, retries);
+    }
+    assert_clob_error(
+        market.consume_events(10, &[bob]).await,
+        ErrorCode::EventRetriesExhausted,
+    );
+
+    // Its maker's account still settles it
+    market.consume_events(10, &[alice]).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_consume_events_reports_processed_and_remaining() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    for _ in 0..4 {
+        market
+            .place_limit_order(alice, Side::Ask, 2_000_000, 1)
+            .await
+            .unwrap();
+    }
+    market
+        .place_limit_order(bob, Side::Bid, 2_000_000, 4)
+        .await
+        .unwrap();
+    assert_eq!(market.event_queue_len(), 4);
+
+    for (processed, remaining) in [(3, 1), (1, 0), (0, 0)] {
+        let meta = market.consume_events(3, &[alice]).await.unwrap();
+        let consumed = &parse_events::<EventsConsumed>(&meta.logs)[0];
+        assert_eq!(
+            (consumed.processed, consumed.remaining),
+            (processed, remaining)
+        );
+        assert_eq!(consumed.event_queue, market.event_queue);
+        assert_eq!(
+            u8::try_from_slice(&meta.return_data.data).unwrap(),
+            processed
+        );
+    }
+
+    scenario.assert_conservation();
+}
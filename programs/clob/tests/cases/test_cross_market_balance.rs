@@ -0,0 +1,58 @@
+use anchor_spl::token::spl_token;
+use clob::state::Side;
+use solana_sdk::signature::Keypair;
+use std::rc::Rc;
+
+use crate::svm::{
+    market::{get_user_balance_pda, MarketFixture},
+    spl::MintFixture,
+    TradingScenario,
+};
+
+#[tokio::test]
+async fn test_place_rejects_another_markets_balance() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let ctx = Rc::clone(&scenario.fixture.ctx);
+
+    // A second market sharing the quote mint, where Alice also has funds
+    let other_base =
+        MintFixture::new_with_program(ctx.clone(), Keypair::new(), 6, spl_token::ID).await;
+    let other = MarketFixture::new(ctx.clone(), &other_base, &scenario.fixture.quote_mint).await;
+    other
+        .deposit(
+            &alice.keypair,
+            scenario.fixture.quote_mint.mint,
+            alice.quote_account,
+            50_000_000,
+        )
+        .await
+        .unwrap();
+    let (other_balance, _) = get_user_balance_pda(&alice.pubkey(), &other.market);
+    let other_before = other.get_user_balance(&alice.pubkey());
+    let before = market.get_user_balance(&alice.pubkey());
+
+    // This market's book, the other market's balance
+    let outcome = market
+        .place_limit_order_with_user_balance(&alice.keypair, other_balance, Side::Bid, 1_000_000, 1)
+        .await;
+    assert!(!outcome.success);
+    assert!(
+        outcome
+            .logs
+            .iter()
+            .any(|line| line.contains("caused by account: user_balance")),
+        "{:?}",
+        outcome.logs
+    );
+
+    // Nothing moved on either market
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+    let after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(after.quote_balance, before.quote_balance);
+    let other_after = other.get_user_balance(&alice.pubkey());
+    assert_eq!(other_after.quote_balance, other_before.quote_balance);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_delegate_deposits_into_owner_balance() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let base_mint = &scenario.fixture.base_mint;
+    let delegate = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+
+    base_mint
+        .approve(
+            &alice.base_account,
+            &alice.keypair,
+            &delegate.pubkey(),
+            5_000_000,
+        )
+        .await;
+
+    let balance_before = market.get_user_balance(&alice.pubkey());
+    let wallet_before = base_mint.balance(alice.base_account).await;
+
+    market
+        .deposit_for(
+            &delegate,
+            &alice.pubkey(),
+            base_mint.mint,
+            alice.base_account,
+            3_000_000,
+        )
+        .await
+        .expect("Approved delegate can fund the owner's balance");
+
+    let balance_after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(
+        balance_after.base_balance - balance_before.base_balance,
+        3_000_000
+    );
+    assert_eq!(
+        wallet_before - base_mint.balance(alice.base_account).await,
+        3_000_000
+    );
+
+    // Only 2_000_000 of the allowance is left
+    market
+        .deposit_for(
+            &delegate,
+            &alice.pubkey(),
+            base_mint.mint,
+            alice.base_account,
+            3_000_000,
+        )
+        .await
+        .expect_err("Deposit beyond the allowance fails");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_delegate_can_open_a_custodied_balance() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let delegate = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+    let custodied = Pubkey::new_unique();
+
+    quote_mint
+        .approve(
+            &alice.quote_account,
+            &alice.keypair,
+            &delegate.pubkey(),
+            1_000_000,
+        )
+        .await;
+
+    market
+        .deposit_for(
+            &delegate,
+            &custodied,
+            quote_mint.mint,
+            alice.quote_account,
+            1_000_000,
+        )
+        .await
+        .expect("Delegate pays for and funds the new balance");
+
+    let balance = market.get_user_balance(&custodied);
+    assert_eq!(balance.owner, custodied);
+    assert_eq!(balance.quote_balance, 1_000_000);
+
+    scenario.assert_conservation_with(&[custodied]);
+}
+
+#[tokio::test]
+async fn test_unapproved_signer_cannot_deposit() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let stranger = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+
+    market
+        .deposit_for(
+            &stranger,
+            &stranger.pubkey(),
+            scenario.fixture.base_mint.mint,
+            alice.base_account,
+            1_000_000,
+        )
+        .await
+        .expect_err("Neither owner nor delegate of the token account");
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,44 @@
+use clob::state::{AskOrderBook, BidOrderBook, OrderBook, Side, VecOrderBook};
+
+use crate::svm::order;
+
+#[test]
+fn test_insert_rejects_duplicate_order_id() {
+    let mut bids = BidOrderBook::new();
+    bids.insert_order(order(7, 100, 5).timestamp(1).build())
+        .unwrap();
+    let hash = bids.book_hash();
+
+    // Same id at another price is still a duplicate
+    let err = bids
+        .insert_order(order(7, 101, 5).timestamp(1).build())
+        .unwrap_err();
+    assert!(err.to_string().contains("DuplicateOrderId"));
+    assert_eq!(bids.len(), 1);
+    assert_eq!(
+        bids.book_hash(),
+        hash,
+        "A rejected insert leaves the book as it was"
+    );
+
+    // Once the original leaves the book its id is free again
+    bids.remove_order(7).unwrap();
+    bids.insert_order(order(7, 101, 5).timestamp(1).build())
+        .unwrap();
+
+    let mut asks = AskOrderBook::new();
+    asks.insert_order(order(1, 100, 5).timestamp(1).build())
+        .unwrap();
+    assert!(asks
+        .insert_order(order(1, 100, 5).timestamp(1).build())
+        .is_err());
+
+    let mut model = VecOrderBook::new(Side::Ask);
+    model
+        .insert_order(order(1, 100, 5).timestamp(1).build())
+        .unwrap();
+    assert!(model
+        .insert_order(order(1, 100, 5).timestamp(1).build())
+        .is_err());
+    assert_eq!(model.len(), 1);
+}
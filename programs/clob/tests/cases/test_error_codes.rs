@@ -0,0 +1,107 @@
+use anchor_lang::solana_program::instruction::InstructionError;
+use clob::errors::ErrorCode;
+
+/// Every variant in code order. Codes are part of the program's interface,
+/// so a new variant must be appended here too, and nothing may move.
+const EXPECTED: &[&str] = &[
+    "MathOverflow",
+    "InsufficientBalance",
+    "InvalidTokenMint",
+    "Unauthorized",
+    "SameMintAddresses",
+    "InvalidAmount",
+    "InvalidParameter",
+    "OrderNotFound",
+    "OrderbookFull",
+    "InvalidOrderSize",
+    "InvalidPrice",
+    "EventQueueFull",
+    "EventQueueEmpty",
+    "FillOrKillNotFilled",
+    "OrderbookNotEmpty",
+    "EventQueueNotEmpty",
+    "MarketClosing",
+    "MarketNotClosing",
+    "CloseGraceActive",
+    "NotOrphanedAccount",
+    "RateLimited",
+    "DuplicateOrderId",
+    "RefundAccountsMissing",
+    "SideHalted",
+    "PriceNotTickAligned",
+    "QuantityNotLotAligned",
+    "MarketNotPaused",
+    "SlippageExceeded",
+    "InvalidIntentSignature",
+    "IntentExpired",
+    "IntentNonceUsed",
+    "CrossTradesThrough",
+    "CrossAccountMissing",
+    "MarketPaused",
+    "InvalidEventQueueShard",
+    "BookOwnersFull",
+    "BookLayoutMismatch",
+    "BookWouldLock",
+    "SettlementBacklog",
+    "PriceLevelFull",
+    "TwapSliceNotDue",
+    "TwapNotActive",
+    "SessionNotActive",
+    "SessionNotExpired",
+    "PostOnlyWouldCross",
+    "InvalidBookAccount",
+    "SelfTradeNotAllowed",
+    "SettlementNotDue",
+    "DuplicateClientOrderId",
+    "TooManyAccounts",
+    "EventRetriesExhausted",
+];
+
+#[test]
+fn test_error_table_matches_expected_codes() {
+    let names: Vec<&str> = ErrorCode::ALL.iter().map(|e| e.variant_name()).collect();
+    assert_eq!(names, EXPECTED);
+
+    for (index, error) in ErrorCode::ALL.iter().enumerate() {
+        let code = u32::from(*error);
+        assert_eq!(code, 6000 + index as u32);
+        assert_eq!(
+            ErrorCode::from_code(code).map(|e| e.variant_name()),
+            Some(error.variant_name())
+        );
+        // The static strings agree with what Anchor itself reports
+        assert_eq!(error.variant_name(), error.name());
+        assert_eq!(error.message(), error.to_string());
+    }
+
+    assert!(ErrorCode::from_code(5999).is_none());
+    assert!(ErrorCode::from_code(6000 + EXPECTED.len() as u32).is_none());
+}
+
+#[test]
+fn test_error_code_parsing() {
+    let unauthorized = Some("Unauthorized");
+    assert_eq!(
+        ErrorCode::from_code(0x1773).map(|e| e.variant_name()),
+        unauthorized
+    );
+    assert_eq!(
+        ErrorCode::from_instruction_error(&InstructionError::Custom(6003))
+            .map(|e| e.variant_name()),
+        unauthorized
+    );
+    assert!(ErrorCode::from_instruction_error(&InstructionError::InvalidArgument).is_none());
+
+    let runtime = "Program failed: Error processing Instruction 0: custom program error: 0x1773";
+    assert_eq!(
+        ErrorCode::from_log(runtime).map(|e| e.variant_name()),
+        unauthorized
+    );
+    let anchor = "Program log: AnchorError caused by account: user_balance. \
+                  Error Code: Unauthorized. Error Number: 6003. Error Message: Unauthorized.";
+    assert_eq!(
+        ErrorCode::from_log(anchor).map(|e| e.variant_name()),
+        unauthorized
+    );
+    assert!(ErrorCode::from_log("Program log: Instruction: Deposit").is_none());
+}
@@ -0,0 +1,86 @@
+use clob::events::{BalanceChanged, BookDelta, OrderComplete, OrderFilled, OrderPlaced};
+use clob::state::{OrderStatus, Side, TimeInForce};
+
+use crate::svm::{event_data, is_event, parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_partial_fill_emits_fills_then_placement_then_summary() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    // Two asks at different levels, then a bid that takes both and rests the rest
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, 5, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&scenario.bob.keypair, Side::Ask, 6, 3)
+        .await
+        .unwrap();
+    let meta = market
+        .place_limit_order(&scenario.charlie.keypair, Side::Bid, 6, 8)
+        .await
+        .unwrap();
+
+    let kinds: Vec<&str> = event_data(&meta.logs)
+        .iter()
+        .map(|data| {
+            if is_event::<OrderFilled>(data) {
+                "filled"
+            } else if is_event::<OrderPlaced>(data) {
+                "placed"
+            } else if is_event::<BalanceChanged>(data) {
+                "balance"
+            } else if is_event::<BookDelta>(data) {
+                "delta"
+            } else if is_event::<OrderComplete>(data) {
+                "complete"
+            } else {
+                "other"
+            }
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["filled", "balance", "filled", "balance", "placed", "balance", "delta", "complete"]
+    );
+
+    let fills = parse_events::<OrderFilled>(&meta.logs);
+    assert_eq!(
+        fills.iter().map(|f| f.maker_order_id).collect::<Vec<_>>(),
+        vec![1, 2],
+        "Fills are reported in match order"
+    );
+
+    let complete = parse_events::<OrderComplete>(&meta.logs);
+    assert_eq!(complete.len(), 1);
+    assert_eq!(complete[0].order_id, 3);
+    assert_eq!(complete[0].filled_quantity, 5);
+    assert_eq!(complete[0].resting_quantity, 3);
+    assert_eq!(complete[0].status, OrderStatus::PartiallyFilled);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_ioc_remainder_reports_cancelled() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, 5, 2)
+        .await
+        .unwrap();
+    let outcome = market
+        .place_limit_order_meta(&scenario.bob.keypair, Side::Bid, 5, 4, TimeInForce::IOC)
+        .await;
+    assert!(outcome.success);
+
+    assert!(outcome.events::<OrderPlaced>().is_empty());
+    let complete = outcome.events::<OrderComplete>();
+    assert_eq!(complete[0].filled_quantity, 2);
+    assert_eq!(complete[0].resting_quantity, 0);
+    assert_eq!(complete[0].status, OrderStatus::Cancelled);
+
+    scenario.assert_conservation();
+}
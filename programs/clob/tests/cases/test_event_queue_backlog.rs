@@ -0,0 +1,73 @@
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_near_full_queue_rejects_large_crossing_order() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    // Three resting asks, so a sweeping bid can produce three fills
+    for price in [10, 11, 12] {
+        market
+            .place_limit_order(alice, Side::Ask, price, 1)
+            .await
+            .unwrap();
+    }
+
+    // 256 slots, one always kept empty: room for two more fills
+    market.set_event_queue_len(253);
+
+    let bob_before = market.get_user_balance(&bob.pubkey());
+    let asks_before = market.with_asks(|asks| asks.orders());
+    let market_before = market.get_market();
+
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Bid, 12, 3, clob::state::TimeInForce::GTC)
+        .await;
+    assert!(!outcome.success);
+    assert!(outcome.logs_contain("EventQueueFull"));
+
+    // Rejected before matching, so nothing moved
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.base_balance, bob_before.base_balance);
+    assert_eq!(bob_after.quote_balance, bob_before.quote_balance);
+    assert_eq!(market.with_asks(|asks| asks.orders()), asks_before);
+    assert_eq!(
+        market.get_market().next_order_id,
+        market_before.next_order_id
+    );
+
+    // Two crossing asks fit the remaining room
+    market
+        .place_limit_order(bob, Side::Bid, 11, 2)
+        .await
+        .expect("Worst case fits the queue");
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 1);
+
+    // The padded slots were never written, so they owe nothing
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_non_crossing_order_ignores_backlog() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, 10, 1)
+        .await
+        .unwrap();
+    market.set_event_queue_len(255);
+
+    // Nothing to match, so a full queue does not block resting orders
+    market
+        .place_limit_order(&scenario.bob.keypair, Side::Bid, 9, 5)
+        .await
+        .expect("Resting order needs no queue room");
+
+    scenario.assert_conservation();
+}
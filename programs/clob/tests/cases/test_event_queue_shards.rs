@@ -0,0 +1,117 @@
+use clob::errors::ErrorCode;
+use clob::state::{Side, MAX_EVENT_QUEUE_SHARDS};
+
+use crate::svm::{assert_clob_error, TradingScenario, TradingUser};
+
+/// Fresh funded makers, one whose fills go to shard 0 and one for shard 1
+async fn makers_in_shards_0_and_1(scenario: &TradingScenario) -> (TradingUser, TradingUser) {
+    let market_state = scenario.market.get_market();
+    let mut shard_0 = None;
+    let mut shard_1 = None;
+    while shard_0.is_none() || shard_1.is_none() {
+        let user = TradingUser::new(
+            scenario.fixture.ctx.clone(),
+            &scenario.fixture,
+            &scenario.market,
+            "maker",
+        )
+        .await;
+        match market_state.event_queue_shard(&user.pubkey()) {
+            0 if shard_0.is_none() => shard_0 = Some(user),
+            1 if shard_1.is_none() => shard_1 = Some(user),
+            _ => {}
+        }
+    }
+    (shard_0.unwrap(), shard_1.unwrap())
+}
+
+#[tokio::test]
+async fn test_fills_land_in_the_makers_shard_and_crank_independently() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let bob = &scenario.bob.keypair;
+
+    market.add_event_queue_shard().await.unwrap();
+    assert_eq!(market.get_market().event_queue_shard_count, 2);
+    let (maker_0, maker_1) = makers_in_shards_0_and_1(&scenario).await;
+
+    market
+        .place_limit_order(&maker_0.keypair, Side::Ask, 10_000, 1)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&maker_1.keypair, Side::Ask, 10_000, 1)
+        .await
+        .unwrap();
+    // Two fills of a lot at 10_000 ticks, 10 quote each
+    market
+        .place_limit_order(bob, Side::Bid, 10_000, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(market.event_queue_shard_len(0), 1);
+    assert_eq!(market.event_queue_shard_len(1), 1);
+
+    // The queues must all be drained before params can change
+    assert_clob_error(
+        market.update_market_params(2_000_000, 1_000).await,
+        ErrorCode::EventQueueNotEmpty,
+    );
+
+    // Each shard only needs its own makers' balances
+    let maker_1_before = market.get_user_balance(&maker_1.pubkey());
+    market
+        .consume_events_on(market.event_queue_shard(1), 10, &[&maker_1.keypair])
+        .await
+        .expect("Crank shard 1");
+    assert_eq!(market.event_queue_shard_len(1), 0);
+    assert_eq!(market.event_queue_shard_len(0), 1);
+    assert_eq!(
+        market.get_user_balance(&maker_1.pubkey()).quote_balance,
+        maker_1_before.quote_balance + 10
+    );
+
+    let maker_0_before = market.get_user_balance(&maker_0.pubkey());
+    market
+        .consume_events_on(market.event_queue_shard(0), 10, &[&maker_0.keypair])
+        .await
+        .expect("Crank shard 0");
+    assert_eq!(market.event_queue_shard_len(0), 0);
+    assert_eq!(
+        market.get_user_balance(&maker_0.pubkey()).quote_balance,
+        maker_0_before.quote_balance + 10
+    );
+
+    market
+        .update_market_params(2_000_000, 1_000)
+        .await
+        .expect("All shards drained");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_shard_count_is_capped() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    for _ in 1..MAX_EVENT_QUEUE_SHARDS {
+        market.add_event_queue_shard().await.unwrap();
+    }
+    assert_eq!(
+        market.get_market().event_queue_shard_count as usize,
+        MAX_EVENT_QUEUE_SHARDS
+    );
+    assert_clob_error(
+        market.add_event_queue_shard().await,
+        ErrorCode::InvalidParameter,
+    );
+
+    // Orders still place with every shard passed along
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, 10_000, 1)
+        .await
+        .expect("Placement on a fully sharded market");
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,171 @@
+use bytemuck::Zeroable;
+use clob::errors::ErrorCode;
+use clob::state::{EventQueue, FillEvent, MAX_EVENTS};
+
+fn queue(capacity: u64, head: u64, tail: u64) -> EventQueue {
+    let mut queue = EventQueue::zeroed();
+    queue.capacity = capacity;
+    queue.head = head;
+    queue.tail = tail;
+    queue
+}
+
+fn event(seq: u64) -> FillEvent {
+    FillEvent {
+        maker_order_id: seq,
+        ..FillEvent::zeroed()
+    }
+}
+
+#[test]
+fn test_advance_wraps_at_capacity() {
+    let q = queue(8, 0, 0);
+    assert_eq!(q.advance(0), 1);
+    assert_eq!(q.advance(6), 7);
+    assert_eq!(q.advance(7), 0);
+    // Out-of-range indexes land back in the ring instead of overflowing
+    assert_eq!(q.advance(8), 0);
+    assert_eq!(q.advance(u64::MAX), 0);
+
+    let q = queue(MAX_EVENTS as u64, 0, 0);
+    assert_eq!(q.advance(MAX_EVENTS as u64 - 2), MAX_EVENTS as u64 - 1);
+    assert_eq!(q.advance(MAX_EVENTS as u64 - 1), 0);
+}
+
+#[test]
+fn test_logical_len_for_every_head_tail_ordering() {
+    let empty = queue(8, 5, 5);
+    assert_eq!(empty.logical_len(), 0);
+    assert!(empty.is_empty());
+    assert_eq!(empty.free_slots(), 7);
+
+    let head_before_tail = queue(8, 2, 6);
+    assert_eq!(head_before_tail.logical_len(), 4);
+
+    let head_after_tail = queue(8, 6, 2);
+    assert_eq!(head_after_tail.logical_len(), 4);
+    assert_eq!(head_after_tail.free_slots(), 3);
+
+    // Full without wrapping, and full with the tail just behind the head
+    for (head, tail) in [(0, 7), (5, 4), (1, 0)] {
+        let full = queue(8, head, tail);
+        assert_eq!(full.logical_len(), 7, "head {} tail {}", head, tail);
+        assert!(full.is_full());
+        assert_eq!(full.free_slots(), 0);
+    }
+
+    let capacity = MAX_EVENTS as u64;
+    let full = queue(capacity, capacity - 1, capacity - 2);
+    assert_eq!(full.logical_len(), capacity - 1);
+    assert!(full.is_full());
+    let one_left = queue(capacity, capacity - 1, capacity - 3);
+    assert_eq!(one_left.free_slots(), 1);
+    assert!(!one_left.is_full());
+}
+
+#[test]
+fn test_push_and_pop_stop_at_the_bounds() {
+    let mut q = queue(4, 3, 3);
+    assert!(q.pop_event().is_err());
+    for seq in 0..3 {
+        q.push_event(event(seq)).unwrap();
+    }
+    assert!(q.is_full());
+    assert!(q.push_event(event(3)).is_err());
+    assert_eq!(q.logical_len(), 3);
+    assert_eq!(q.peek_event().unwrap().maker_order_id, 0);
+}
+
+#[test]
+fn test_initialized_queue_fills_to_one_below_max_events() {
+    let mut q = EventQueue::zeroed();
+    // Untouched, a zero-capacity queue refuses pushes and pops with errors
+    // instead of dividing by zero
+    let err = q.push_event(event(0)).unwrap_err();
+    assert_eq!(err, ErrorCode::EventQueueFull.into());
+    let err = q.pop_event().err().unwrap();
+    assert_eq!(err, ErrorCode::EventQueueEmpty.into());
+    assert_eq!(q.logical_len(), 0);
+
+    q.init();
+    assert_eq!(q.capacity, MAX_EVENTS as u64);
+    assert!(q.is_empty());
+    for seq in 0..MAX_EVENTS as u64 - 1 {
+        assert!(!q.is_full());
+        q.push_event(event(seq)).unwrap();
+    }
+    assert!(q.is_full());
+    assert_eq!(q.logical_len(), MAX_EVENTS as u64 - 1);
+    let err = q.push_event(event(MAX_EVENTS as u64)).unwrap_err();
+    assert_eq!(err, ErrorCode::EventQueueFull.into());
+
+    // Draining one slot lets the tail wrap onto index 0
+    assert_eq!(q.pop_event().unwrap().maker_order_id, 0);
+    q.push_event(event(MAX_EVENTS as u64)).unwrap();
+    assert_eq!(q.tail, 0);
+    assert!(q.is_full());
+}
+
+#[test]
+fn test_fifo_order_survives_repeated_wraparound() {
+    let capacity = 8;
+    let mut q = queue(capacity, 0, 0);
+    let mut next_push = 0u64;
+    let mut next_pop = 0u64;
+
+    for round in 0..500u64 {
+        // Vary how far each round fills so head and tail meet the boundary
+        // at every offset, including completely full
+        let fill = (round % capacity).min(q.free_slots());
+        for _ in 0..fill {
+            q.push_event(event(next_push)).unwrap();
+            next_push += 1;
+            assert_eq!(q.logical_len(), next_push - next_pop);
+        }
+        assert!(q.logical_len() < capacity);
+
+        let drain = if round % 3 == 0 {
+            q.logical_len()
+        } else {
+            fill / 2
+        };
+        for _ in 0..drain {
+            let popped = q.pop_event().unwrap();
+            assert_eq!(popped.maker_order_id, next_pop, "round {}", round);
+            next_pop += 1;
+            assert_eq!(q.logical_len(), next_push - next_pop);
+        }
+        assert!(q.head < capacity && q.tail < capacity);
+    }
+
+    while !q.is_empty() {
+        assert_eq!(q.pop_event().unwrap().maker_order_id, next_pop);
+        next_pop += 1;
+    }
+    assert_eq!(next_pop, next_push);
+}
+
+#[test]
+fn test_lifetime_push_count_survives_drains() {
+    let capacity = 8;
+    let mut q = queue(capacity, 0, 0);
+    let mut pushed = 0u64;
+
+    for round in 0..200u64 {
+        let fill = (round % capacity).min(q.free_slots());
+        for _ in 0..fill {
+            q.push_event(event(pushed)).unwrap();
+            pushed += 1;
+        }
+        // A rejected push is not counted
+        if q.is_full() {
+            assert!(q.push_event(event(pushed)).is_err());
+        }
+        while !q.is_empty() {
+            q.pop_event().unwrap();
+        }
+        assert_eq!(q.logical_len(), 0);
+        assert_eq!(q.total_events_pushed, pushed);
+    }
+    assert!(pushed > 10 * capacity);
+}
@@ -0,0 +1,162 @@
+use clob::errors::ErrorCode;
+use clob::events::{FeesWithdrawn, MakerFillSettled, OrderFilled};
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+// 5 lots at 2000 ticks is 5_000_000 base against 10 quote
+async fn scenario_with_fees() -> TradingScenario {
+    TradingScenario::with_market_params(|params| {
+        params.maker_fee_bps = 10;
+        params.taker_fee_bps = 20;
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_bid_taker_pays_in_base_and_ask_maker_in_quote() {
+    let scenario = scenario_with_fees().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    market
+        .place_limit_order(alice, Side::Ask, 2000, 5)
+        .await
+        .unwrap();
+    let meta = market
+        .place_limit_order(bob, Side::Bid, 2000, 5)
+        .await
+        .unwrap();
+
+    // 20 bps of 5_000_000 base
+    let fill = &parse_events::<OrderFilled>(&meta.logs)[0];
+    assert_eq!((fill.fee_base, fill.fee_quote), (10_000, 0));
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.base_balance - bob_before.base_balance, 4_990_000);
+    assert_eq!(bob_before.quote_balance - bob_after.quote_balance, 10);
+
+    // 10 bps of 10 quote is 0.01, rounded up to a whole token
+    let meta = market.consume_events(10, &[alice]).await.unwrap();
+    let settled = &parse_events::<MakerFillSettled>(&meta.logs)[0];
+    assert_eq!(
+        (settled.gross_quote, settled.fee_quote, settled.net_quote),
+        (10, 1, 9)
+    );
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(alice_after.quote_balance - alice_before.quote_balance, 9);
+    assert_eq!(
+        alice_before.base_balance - alice_after.base_balance,
+        5_000_000
+    );
+
+    let state = market.get_market();
+    assert_eq!(
+        (state.base_fees_accrued, state.quote_fees_accrued),
+        (10_000, 1)
+    );
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_ask_taker_pays_in_quote_and_bid_maker_in_base() {
+    let scenario = scenario_with_fees().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    market
+        .place_limit_order(bob, Side::Bid, 2000, 5)
+        .await
+        .unwrap();
+    let meta = market
+        .place_limit_order(alice, Side::Ask, 2000, 5)
+        .await
+        .unwrap();
+
+    // 20 bps of 10 quote is 0.02, rounded up to a whole token
+    let fill = &parse_events::<OrderFilled>(&meta.logs)[0];
+    assert_eq!((fill.fee_base, fill.fee_quote, fill.net_quote), (0, 1, 9));
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(alice_after.quote_balance - alice_before.quote_balance, 9);
+    assert_eq!(
+        alice_before.base_balance - alice_after.base_balance,
+        5_000_000
+    );
+
+    // 10 bps of 5_000_000 base
+    let meta = market.consume_events(10, &[bob]).await.unwrap();
+    let settled = &parse_events::<MakerFillSettled>(&meta.logs)[0];
+    assert_eq!((settled.fee_base, settled.fee_quote), (5_000, 0));
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.base_balance - bob_before.base_balance, 4_995_000);
+    assert_eq!(bob_before.quote_balance - bob_after.quote_balance, 10);
+
+    let state = market.get_market();
+    assert_eq!(
+        (state.base_fees_accrued, state.quote_fees_accrued),
+        (5_000, 1)
+    );
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_authority_withdraws_accrued_quote_fees() {
+    let scenario = scenario_with_fees().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let quote_mint = &scenario.fixture.quote_mint;
+
+    // Alice pays 1 quote as the ask taker, then 1 more as the ask maker
+    market
+        .place_limit_order(bob, Side::Bid, 2000, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Ask, 2000, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Ask, 2000, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 2000, 5)
+        .await
+        .unwrap();
+    market.consume_events(10, &[alice, bob]).await.unwrap();
+    assert_eq!(market.get_market().quote_fees_accrued, 2);
+
+    let payer = scenario.fixture.ctx.borrow().payer.pubkey();
+    let fee_account = quote_mint.create_token_account(&payer).await;
+    assert_clob_error(
+        market
+            .withdraw_fees(Some(bob), quote_mint.mint, scenario.bob.quote_account, 2)
+            .await,
+        ErrorCode::Unauthorized,
+    );
+    assert_clob_error(
+        market
+            .withdraw_fees(None, quote_mint.mint, fee_account, 3)
+            .await,
+        ErrorCode::InsufficientBalance,
+    );
+
+    let meta = market
+        .withdraw_fees(None, quote_mint.mint, fee_account, 2)
+        .await
+        .unwrap();
+    let withdrawn = &parse_events::<FeesWithdrawn>(&meta.logs)[0];
+    assert_eq!((withdrawn.amount, withdrawn.remaining), (2, 0));
+    assert_eq!(quote_mint.balance(fee_account).await, 2);
+    let state = market.get_market();
+    assert_eq!(state.quote_fees_accrued, 0);
+    assert_eq!(state.base_fees_accrued, 15_000);
+    scenario.assert_conservation();
+}
@@ -0,0 +1,61 @@
+use clob::errors::ErrorCode;
+use clob::instructions::PlaceLimitOrderResult;
+use clob::state::{FillStatus, Side, TimeInForce};
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+#[tokio::test]
+async fn test_fill_status_for_each_outcome() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 10, 10)
+        .await
+        .unwrap();
+
+    let place = |price, quantity, time_in_force| {
+        market.place_limit_order_meta(bob, Side::Bid, price, quantity, time_in_force)
+    };
+
+    // GTC that fills in full
+    let outcome = place(10, 4, TimeInForce::GTC).await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.fill_status, FillStatus::FullyFilled);
+
+    // IOC that fills the 6 left and drops the rest
+    let outcome = place(10, 8, TimeInForce::IOC).await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.fill_status, FillStatus::PartiallyFilledCancelled);
+    assert_eq!((result.filled_quantity, result.resting_quantity), (6, 0));
+
+    // IOC with nothing to fill against
+    let outcome = place(10, 5, TimeInForce::IOC).await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.fill_status, FillStatus::Rejected);
+
+    // FOK short of liquidity fails outright, so there is no result to read
+    market
+        .place_limit_order(alice, Side::Ask, 10, 2)
+        .await
+        .unwrap();
+    let outcome = place(10, 5, TimeInForce::FOK).await;
+    assert!(outcome.return_data.is_empty());
+    assert_clob_error(outcome, ErrorCode::FillOrKillNotFilled);
+
+    // GTC that fills those 2 and rests 3
+    let outcome = place(10, 5, TimeInForce::GTC).await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.fill_status, FillStatus::PartiallyFilledResting);
+    assert_eq!((result.filled_quantity, result.resting_quantity), (2, 3));
+
+    // GTC that only rests
+    let outcome = place(9, 1, TimeInForce::GTC).await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.fill_status, FillStatus::Resting);
+
+    market.consume_events(10, &[alice]).await.unwrap();
+    scenario.assert_conservation();
+}
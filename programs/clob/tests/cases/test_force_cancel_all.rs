@@ -0,0 +1,88 @@
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+const GRACE_PERIOD: i64 = 3_600;
+
+#[tokio::test]
+async fn test_force_cancel_all_after_grace() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let ctx = &scenario.fixture.ctx;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    market
+        .place_limit_order(alice, Side::Bid, 1_000, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Ask, 2_000, 7)
+        .await
+        .unwrap();
+
+    // Only the authority can start the close
+    assert!(market
+        .begin_market_close(Some(&scenario.charlie.keypair), GRACE_PERIOD)
+        .await
+        .is_err());
+    let start = ctx.borrow().clock().unix_timestamp;
+    market
+        .begin_market_close(None, GRACE_PERIOD)
+        .await
+        .expect("Authority begins closing");
+    assert_eq!(market.get_market().close_grace_until, start + GRACE_PERIOD);
+
+    // New orders are refused while closing
+    assert!(market
+        .place_limit_order(alice, Side::Bid, 1_000, 1)
+        .await
+        .is_err());
+
+    // Too early: users still have time to cancel themselves
+    assert!(market.force_cancel_all(10, &[alice, bob]).await.is_err());
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 1);
+
+    ctx.borrow_mut().set_clock(start + GRACE_PERIOD);
+    market
+        .force_cancel_all(10, &[alice, bob])
+        .await
+        .expect("Force cancel allowed once grace expires");
+
+    assert!(market.orderbooks_are_empty());
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(alice_after.quote_balance, alice_before.quote_balance);
+    assert_eq!(bob_after.base_balance, bob_before.base_balance);
+
+    // With the books swept the rent can be recovered
+    market
+        .close_market(None, market.get_market().rent_payer)
+        .await
+        .expect("Close after force cancel");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_force_cancel_requires_closing_market() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    market
+        .place_limit_order(alice, Side::Bid, 10, 5)
+        .await
+        .unwrap();
+    assert!(
+        market.force_cancel_all(10, &[alice]).await.is_err(),
+        "Open markets cannot be force-cancelled"
+    );
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 1);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,283 @@
+//! Byte-for-byte snapshots of every account and record the program writes,
+//! checked against the committed files in tests/golden. See the README
+//! there for how to regenerate them after an intentional layout change.
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AccountSerialize, Discriminator};
+use bytemuck::Zeroable;
+use clob::state::{
+    decode_book_side, AskOrderBook, AskSide, BidOrderBook, BidSide, EventQueue, FillEvent, Market,
+    Order, OrderBook, Side, TieBreakMode, UserBalance, BOOK_LAYOUT_VERSION, FILL_EVENT_KIND_FILL,
+    MAX_EVENTS, ORDER_FLAG_ALL_OR_NONE, ORDER_FLAG_SESSION, USER_BALANCE_MARKET_OFFSET,
+    USER_BALANCE_OWNER_OFFSET, USER_BALANCE_SCHEMA_VERSION, USER_BALANCE_SIZE,
+};
+use std::path::PathBuf;
+
+use crate::svm::order;
+
+/// Set to rewrite the golden files from the current layouts
+const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.bin"))
+}
+
+fn read_golden(name: &str) -> Vec<u8> {
+    let path = golden_path(name);
+    std::fs::read(&path).unwrap_or_else(|e| panic!("missing golden {}: {e}", path.display()))
+}
+
+/// Compare `bytes` with the golden file `name`, or rewrite it when
+/// UPDATE_GOLDEN is set
+fn assert_golden(name: &str, bytes: &[u8]) {
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        std::fs::write(golden_path(name), bytes).unwrap();
+        return;
+    }
+    let golden = read_golden(name);
+    if golden == bytes {
+        return;
+    }
+    let first_difference = golden
+        .iter()
+        .zip(bytes)
+        .position(|(a, b)| a != b)
+        .unwrap_or(golden.len().min(bytes.len()));
+    panic!(
+        "{name} layout changed: golden is {} bytes, now {} bytes, first difference at byte {}. \
+         If intended, bump the layout version and rerun with {UPDATE_ENV}=1",
+        golden.len(),
+        bytes.len(),
+        first_difference
+    );
+}
+
+fn market() -> Market {
+    Market {
+        authority: key(1),
+        base_mint: key(2),
+        quote_mint: key(3),
+        base_vault: key(4),
+        quote_vault: key(5),
+        bids: key(6),
+        asks: key(7),
+        event_queue: key(8),
+        base_lot_size: 1_000,
+        quote_tick_size: 10,
+        next_order_id: 42,
+        rent_payer: key(9),
+        tie_break_mode: TieBreakMode::Size,
+        close_grace_until: 1_700_000_100,
+        max_orders_per_slot_per_user: 3,
+        book_seq_num: 77,
+        flags: 0b101,
+        cross_authority: key(10),
+        max_cross_trade_through: 4,
+        deposits_paused: true,
+        withdrawals_paused: false,
+        event_queue_shard_count: 2,
+        event_queue_shards: [key(11), Pubkey::default(), Pubkey::default()],
+        allow_locked_book: true,
+        order_bond_lamports: 5_000_000,
+        max_event_age_secs: 30,
+        max_orders_per_level: 64,
+        last_trade_price: 1_234,
+        last_settlement_ts: 1_700_000_200,
+        maker_fee_bps: 10,
+        taker_fee_bps: 20,
+        base_fees_accrued: 3_000_000,
+        quote_fees_accrued: 4_500,
+        bump: 254,
+        base_vault_bump: 253,
+        quote_vault_bump: 252,
+    }
+}
+
+fn user_balance() -> UserBalance {
+    UserBalance {
+        owner: key(20),
+        market: key(21),
+        base_balance: 1_000_000,
+        quote_balance: 2_000_000,
+        bump: 251,
+        last_order_slot: 99,
+        orders_this_slot: 2,
+        next_intent_nonce: 7,
+        position_base_net: -3_000,
+        position_cost_quote: -30_000,
+        realized_pnl_quote: 1_234,
+        open_order_count: 5,
+        schema_version: USER_BALANCE_SCHEMA_VERSION,
+        session_expiry: 1_700_000_600,
+    }
+}
+
+fn fill_event() -> FillEvent {
+    FillEvent {
+        maker_order_id: 1,
+        taker_order_id: 2,
+        price: 1_000,
+        quantity: 5,
+        timestamp: 1_700_000_010,
+        maker_owner: key(30),
+        taker_owner: key(31),
+        market: key(32),
+        maker_side: 1,
+        kind: FILL_EVENT_KIND_FILL,
+        maker_order_closed: 1,
+        retry_count: 2,
+        _padding: [0; 4],
+    }
+}
+
+fn order_record() -> Order {
+    order(9, 1_000, 10)
+        .owner(key(50))
+        .timestamp(1_700_000_009)
+        .flags(ORDER_FLAG_SESSION)
+        .remaining_quantity(9)
+        .client_order_id(77)
+        .build()
+}
+
+fn book_orders() -> Vec<Order> {
+    vec![
+        order(1, 1_000, 10)
+            .owner(key(40))
+            .timestamp(1_700_000_001)
+            .remaining_quantity(9)
+            .client_order_id(77)
+            .build(),
+        order(2, 1_010, 20)
+            .owner(key(41))
+            .timestamp(1_700_000_002)
+            .flags(ORDER_FLAG_ALL_OR_NONE)
+            .remaining_quantity(19)
+            .client_order_id(77)
+            .build(),
+        order(3, 990, 30)
+            .owner(key(40))
+            .timestamp(1_700_000_003)
+            .flags(ORDER_FLAG_SESSION)
+            .remaining_quantity(29)
+            .client_order_id(77)
+            .build(),
+    ]
+}
+
+fn bid_side_account() -> Vec<u8> {
+    let mut side = Box::new(BidSide {
+        orderbook: BidOrderBook::with_tie_break(TieBreakMode::Size),
+    });
+    for order in book_orders() {
+        side.orderbook.insert_order(order).unwrap();
+    }
+    [BidSide::DISCRIMINATOR, bytemuck::bytes_of(side.as_ref())].concat()
+}
+
+fn ask_side_account() -> Vec<u8> {
+    let mut side = Box::new(AskSide {
+        orderbook: AskOrderBook::new(),
+    });
+    for order in book_orders() {
+        side.orderbook.insert_order(order).unwrap();
+    }
+    [AskSide::DISCRIMINATOR, bytemuck::bytes_of(side.as_ref())].concat()
+}
+
+fn event_queue_account() -> Vec<u8> {
+    let mut queue = Box::new(EventQueue {
+        head: 1,
+        tail: 3,
+        capacity: MAX_EVENTS as u64,
+        total_events_pushed: 3,
+        events: [FillEvent::zeroed(); MAX_EVENTS],
+        total_events_requeued: 0,
+    });
+    for (i, event) in queue.events.iter_mut().take(3).enumerate() {
+        *event = fill_event();
+        event.maker_order_id = i as u64 + 1;
+    }
+    [
+        EventQueue::DISCRIMINATOR,
+        bytemuck::bytes_of(queue.as_ref()),
+    ]
+    .concat()
+}
+
+fn borsh_account<T: AccountSerialize>(account: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_account_layouts_match_golden_files() {
+    assert_golden("market", &borsh_account(&market()));
+    assert_golden("user_balance", &borsh_account(&user_balance()));
+    assert_golden("order", bytemuck::bytes_of(&order_record()));
+    assert_golden("fill_event", bytemuck::bytes_of(&fill_event()));
+    assert_golden("event_queue", &event_queue_account());
+    assert_golden("bid_side", &bid_side_account());
+    assert_golden("ask_side", &ask_side_account());
+}
+
+/// The decoders clients use read the golden bytes back to the same values,
+/// so a layout the program writes and one clients read cannot drift apart
+#[test]
+fn test_client_decoders_read_golden_files() {
+    let data = read_golden("market");
+    let decoded = Market::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(borsh_account(&decoded), borsh_account(&market()));
+
+    // What fetch_user_balances decodes, behind user_balance_filters
+    let data = read_golden("user_balance");
+    assert_eq!(data.len(), USER_BALANCE_SIZE);
+    assert_eq!(&data[..8], UserBalance::DISCRIMINATOR);
+    assert_eq!(
+        &data[USER_BALANCE_OWNER_OFFSET..USER_BALANCE_OWNER_OFFSET + 32],
+        key(20).as_ref()
+    );
+    assert_eq!(
+        &data[USER_BALANCE_MARKET_OFFSET..USER_BALANCE_MARKET_OFFSET + 32],
+        key(21).as_ref()
+    );
+    let balance = UserBalance::try_deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!(borsh_account(&balance), borsh_account(&user_balance()));
+
+    let data = read_golden("order");
+    let decoded: Order = bytemuck::pod_read_unaligned(&data);
+    assert_eq!(decoded, order_record());
+
+    let data = read_golden("fill_event");
+    let decoded: FillEvent = bytemuck::pod_read_unaligned(&data);
+    assert_eq!(
+        bytemuck::bytes_of(&decoded),
+        bytemuck::bytes_of(&fill_event())
+    );
+
+    let data = read_golden("event_queue");
+    assert_eq!(&data[..8], EventQueue::DISCRIMINATOR);
+    let queue: Box<EventQueue> = Box::new(bytemuck::pod_read_unaligned(&data[8..]));
+    assert_eq!(
+        (queue.head, queue.tail, queue.capacity),
+        (1, 3, MAX_EVENTS as u64)
+    );
+    assert_eq!(queue.events[2].maker_order_id, 3);
+
+    // What the book watcher decodes
+    let mut expected = book_orders();
+    expected.sort_by_key(|order| order.order_id);
+    for (name, side) in [("bid_side", Side::Bid), ("ask_side", Side::Ask)] {
+        let data = read_golden(name);
+        assert_eq!(data[8 + 5], BOOK_LAYOUT_VERSION);
+        let (decoded_side, mut orders) = decode_book_side(&data).unwrap();
+        assert_eq!(decoded_side, side);
+        orders.sort_by_key(|order| order.order_id);
+        assert_eq!(orders, expected);
+    }
+}
@@ -0,0 +1,46 @@
+use clob::state::{AskOrderBook, BidOrderBook, OrderBook};
+
+use crate::svm::order;
+
+#[test]
+fn test_remove_root_with_equal_prices() {
+    let mut book = BidOrderBook::new();
+    // All at one price, inserted out of time order so the heap has to sort them
+    for (order_id, timestamp) in [(1, 5), (2, 3), (3, 9), (4, 1), (5, 7), (6, 2), (7, 4)] {
+        book.insert_order(order(order_id, 100, 1).timestamp(timestamp).build())
+            .unwrap();
+        assert!(book.is_valid_heap());
+    }
+    assert_eq!(book.peek().unwrap().order_id, 4);
+
+    // The root branch swaps the last slot in and bubbles it down
+    let mut expected = [6, 2, 7, 1, 5, 3].into_iter();
+    while let Some(root) = book.peek() {
+        assert_eq!(book.remove_order(root.order_id).unwrap(), Some(root));
+        assert!(book.is_valid_heap());
+        match expected.next() {
+            Some(next) => assert_eq!(book.peek().unwrap().order_id, next),
+            None => assert!(book.is_empty()),
+        }
+    }
+}
+
+#[test]
+fn test_remove_root_ties_on_timestamp_fall_back_to_id() {
+    let mut book = AskOrderBook::new();
+    for order_id in [3, 1, 4, 2] {
+        book.insert_order(order(order_id, 50, 1).timestamp(1).build())
+            .unwrap();
+    }
+    // A better price that leaves first
+    book.insert_order(order(9, 49, 1).timestamp(8).build())
+        .unwrap();
+
+    book.remove_order(9).unwrap();
+    assert!(book.is_valid_heap());
+    assert_eq!(book.peek().unwrap().order_id, 1);
+
+    book.remove_order(1).unwrap();
+    assert!(book.is_valid_heap());
+    assert_eq!(book.peek().unwrap().order_id, 2);
+}
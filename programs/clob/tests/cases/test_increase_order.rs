@@ -0,0 +1,107 @@
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+const BASE_LOT: u64 = 1_000_000;
+
+#[tokio::test]
+async fn test_increase_ask_keeps_original_priority() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let start = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+
+    // Alice (id 1) then Bob (id 2) rest asks at the same price
+    market
+        .place_limit_order(alice, Side::Ask, 10, 10)
+        .await
+        .unwrap();
+    scenario.fixture.ctx.borrow_mut().set_clock(start + 1);
+    market
+        .place_limit_order(bob, Side::Ask, 10, 10)
+        .await
+        .unwrap();
+
+    // Alice tops up her order later
+    scenario.fixture.ctx.borrow_mut().set_clock(start + 2);
+    let base_before = market.get_user_balance(&alice.pubkey()).base_balance;
+    market
+        .increase_order(alice, 1, Side::Ask, 5)
+        .await
+        .expect("Owner can increase a resting order");
+    let base_after = market.get_user_balance(&alice.pubkey()).base_balance;
+    assert_eq!(
+        base_before - base_after,
+        5 * BASE_LOT,
+        "Only the added quantity is reserved"
+    );
+
+    // Still one order under the same id, with its original timestamp
+    let order = market.find_order_in_asks(1).unwrap();
+    assert_eq!((order.quantity, order.remaining_quantity), (15, 15));
+    assert_eq!(order.timestamp, start);
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 2);
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 1);
+
+    // Charlie takes 12: Alice's order is still first in line
+    market
+        .place_limit_order(charlie, Side::Bid, 10, 12)
+        .await
+        .unwrap();
+    assert_eq!(market.find_order_in_asks(1).unwrap().remaining_quantity, 3);
+    assert_eq!(
+        market.find_order_in_asks(2).unwrap().remaining_quantity,
+        10,
+        "Bob is untouched"
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_increase_order_rejects_other_owner() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Bid, 10, 10)
+        .await
+        .unwrap();
+
+    let result = market.increase_order(bob, 1, Side::Bid, 5).await;
+    assert!(result.is_err(), "Cannot increase someone else's order");
+    assert_eq!(market.find_order_in_bids(1).unwrap().remaining_quantity, 10);
+
+    let result = market.increase_order(alice, 99, Side::Bid, 5).await;
+    assert!(result.is_err(), "Unknown order id is rejected");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_cancel_increased_order_refunds_everything() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    let before = market.get_user_balance(&alice.pubkey()).base_balance;
+    market
+        .place_limit_order(alice, Side::Ask, 10, 10)
+        .await
+        .unwrap();
+    market.increase_order(alice, 1, Side::Ask, 5).await.unwrap();
+
+    market.cancel_order(alice, 1, Side::Ask).await.unwrap();
+    assert!(market.find_order_in_asks(1).is_none());
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).base_balance,
+        before
+    );
+
+    scenario.assert_conservation();
+}
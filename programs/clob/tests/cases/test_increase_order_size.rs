@@ -0,0 +1,119 @@
+use clob::errors::ErrorCode;
+use clob::state::Side;
+use clob::OrderModified;
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+// Not a whole number of quote tokens per lot, so reservations round
+const PRICE: u64 = 10_300;
+
+#[tokio::test]
+async fn test_increase_order_size_moves_order_behind_later_orders() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let state = market.get_market();
+    let quote_before = market.get_user_balance(&alice.pubkey()).quote_balance;
+
+    // Alice (id 1, topped up in place) then Bob (id 2), in the same second
+    market
+        .place_limit_order(alice, Side::Bid, PRICE, 10)
+        .await
+        .unwrap();
+    market.increase_order(alice, 1, Side::Bid, 1).await.unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, PRICE, 10)
+        .await
+        .unwrap();
+    let quote_reserved = quote_before - market.get_user_balance(&alice.pubkey()).quote_balance;
+
+    let outcome = market.increase_order_size(alice, 1, 5).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let order_id = outcome.return_value::<u64>();
+    assert_eq!(order_id, 3);
+    let modified = &outcome.events::<OrderModified>()[0];
+    assert_eq!((modified.old_order_id, modified.order_id), (1, order_id));
+    assert_eq!(
+        (
+            modified.old_remaining_quantity,
+            modified.new_remaining_quantity
+        ),
+        (11, 16)
+    );
+
+    // One order under the new id, reserving exactly its full size
+    assert!(market.find_order_in_bids(1).is_none());
+    let order = market.find_order_in_bids(order_id).unwrap();
+    assert_eq!((order.quantity, order.remaining_quantity), (16, 16));
+    let quote_after = market.get_user_balance(&alice.pubkey()).quote_balance;
+    assert_eq!(
+        quote_before - quote_after,
+        state.quote_amount(PRICE, 16).unwrap()
+    );
+    assert!(quote_before - quote_after > quote_reserved);
+
+    // Bob now fills first
+    market
+        .place_limit_order(charlie, Side::Ask, PRICE, 10)
+        .await
+        .unwrap();
+    assert!(market.find_order_in_bids(2).is_none());
+    assert_eq!(
+        market
+            .find_order_in_bids(order_id)
+            .unwrap()
+            .remaining_quantity,
+        16
+    );
+
+    // Cancelling under the new id hands back everything
+    market
+        .cancel_order(alice, order_id, Side::Bid)
+        .await
+        .unwrap();
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).quote_balance,
+        quote_before
+    );
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 0);
+
+    market.consume_events(10, &[bob]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_increase_order_size_checks_owner_and_order() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, PRICE, 10)
+        .await
+        .unwrap();
+
+    assert_clob_error(
+        market.increase_order_size(bob, 1, 5).await,
+        ErrorCode::Unauthorized,
+    );
+    assert_clob_error(
+        market.increase_order_size(alice, 7, 5).await,
+        ErrorCode::OrderNotFound,
+    );
+
+    // An ask reserves base, lot for lot
+    let base_before = market.get_user_balance(&alice.pubkey()).base_balance;
+    let outcome = market.increase_order_size(alice, 1, 5).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    assert_eq!(
+        base_before - market.get_user_balance(&alice.pubkey()).base_balance,
+        5 * market.get_market().base_lot_size
+    );
+    assert_eq!(market.get_l2(Side::Ask), vec![(PRICE, 15)]);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,31 @@
+use clob::errors::ErrorCode;
+use clob::state::BidSide;
+use std::rc::Rc;
+
+use crate::svm::{assert_clob_error, market::MarketFixture, test::TestFixture};
+
+#[tokio::test]
+async fn test_initialize_rejects_wrongly_sized_book() {
+    let fixture = TestFixture::new().await;
+    let book_size = 8 + std::mem::size_of::<BidSide>();
+
+    for bids_size in [book_size - 1024, book_size + 8] {
+        let result = MarketFixture::initialize_with_bids_size(
+            Rc::clone(&fixture.ctx),
+            &fixture.base_mint,
+            &fixture.quote_mint,
+            bids_size,
+        )
+        .await;
+        assert_clob_error(result, ErrorCode::InvalidBookAccount);
+    }
+
+    // Nothing was left behind, so the market can still be set up properly
+    let market = MarketFixture::new(
+        Rc::clone(&fixture.ctx),
+        &fixture.base_mint,
+        &fixture.quote_mint,
+    )
+    .await;
+    assert_eq!(market.get_market().bids, market.bids);
+}
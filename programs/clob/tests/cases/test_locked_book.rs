@@ -0,0 +1,64 @@
+use clob::errors::ErrorCode;
+use clob::state::{Side, TimeInForce};
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+#[tokio::test]
+async fn test_order_that_would_lock_the_book_is_rejected() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let charlie = &scenario.charlie.keypair;
+    assert!(!market.get_market().allow_locked_book);
+
+    // A 10-lot all-or-none ask that a 2-lot bid skips without filling
+    market
+        .place_all_or_none_order(alice, Side::Ask, 1_000_000, 10)
+        .await
+        .unwrap();
+
+    // Resting at the ask would lock the book, above it would cross
+    for price in [1_000_000, 1_100_000] {
+        assert_clob_error(
+            market.place_limit_order(charlie, Side::Bid, price, 2).await,
+            ErrorCode::BookWouldLock,
+        );
+    }
+
+    // Nothing rests from an IOC, and a bid below the ask is fine
+    market
+        .place_limit_order_with_tif(charlie, Side::Bid, 1_000_000, 2, TimeInForce::IOC)
+        .await
+        .expect("IOC remainder is discarded, not rested");
+    market
+        .place_limit_order(charlie, Side::Bid, 999_000, 2)
+        .await
+        .expect("Bid below the ask rests");
+    assert_eq!(market.best_bid(), Some(999_000));
+    assert_eq!(market.best_ask(), Some(1_000_000));
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_locked_book_allowed_when_configured() {
+    let scenario =
+        TradingScenario::with_market_params(|params| params.allow_locked_book = true).await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    market
+        .place_all_or_none_order(alice, Side::Bid, 1_000_000, 10)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(charlie, Side::Ask, 1_000_000, 2)
+        .await
+        .expect("Locking ask rests when allowed");
+
+    assert_eq!(market.best_bid(), Some(1_000_000));
+    assert_eq!(market.best_ask(), Some(1_000_000));
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,139 @@
+use clob::errors::ErrorCode;
+use clob::state::{Side, MARKET_FLAG_HALT_ALL, MARKET_FLAG_HALT_ASKS, MARKET_FLAG_HALT_BIDS};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+#[tokio::test]
+async fn test_halt_flag_matrix() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    // (flags, bids allowed, asks allowed)
+    let matrix = [
+        (0, true, true),
+        (MARKET_FLAG_HALT_BIDS, false, true),
+        (MARKET_FLAG_HALT_ASKS, true, false),
+        (MARKET_FLAG_HALT_BIDS | MARKET_FLAG_HALT_ASKS, false, false),
+        (MARKET_FLAG_HALT_ALL, false, false),
+    ];
+
+    for (flags, bids_allowed, asks_allowed) in matrix {
+        market
+            .set_market_flags(None, flags)
+            .await
+            .expect("Authority sets flags");
+        assert_eq!(market.get_market().flags, flags);
+
+        let bid = market.place_limit_order(alice, Side::Bid, 10, 1).await;
+        assert_eq!(bid.is_ok(), bids_allowed, "bid with flags {flags:#04x}");
+        let ask = market.place_limit_order(alice, Side::Ask, 1_000, 1).await;
+        assert_eq!(ask.is_ok(), asks_allowed, "ask with flags {flags:#04x}");
+    }
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_halted_side_still_cancels_and_withdraws() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 10, 3)
+        .await
+        .unwrap();
+
+    // Only the authority may halt, and unknown bits are refused
+    assert!(market
+        .set_market_flags(Some(&scenario.bob.keypair), MARKET_FLAG_HALT_BIDS)
+        .await
+        .is_err());
+    assert!(market.set_market_flags(None, 1 << 7).await.is_err());
+
+    market
+        .set_market_flags(None, MARKET_FLAG_HALT_BIDS)
+        .await
+        .unwrap();
+
+    // No adding to a resting bid either
+    assert!(market
+        .increase_order(&alice.keypair, 1, Side::Bid, 1)
+        .await
+        .is_err());
+
+    market
+        .cancel_order(&alice.keypair, 1, Side::Bid)
+        .await
+        .expect("Cancels work on a halted side");
+    market
+        .withdraw(
+            &alice.keypair,
+            market.quote_mint,
+            alice.quote_account,
+            1_000,
+        )
+        .await
+        .expect("Withdrawals work while halted");
+
+    // Lifting the halt re-opens the side
+    market.set_market_flags(None, 0).await.unwrap();
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 10, 1)
+        .await
+        .expect("Bids accepted again");
+    assert_eq!(
+        market.find_order_in_bids(2).map(|order| order.owner),
+        Some(alice.keypair.pubkey())
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_halt_all_is_a_kill_switch_users_can_exit_through() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 1_000_000, 1)
+        .await
+        .unwrap();
+    assert_eq!(market.get_market().flags, 0);
+
+    market
+        .set_market_flags(None, MARKET_FLAG_HALT_ALL)
+        .await
+        .unwrap();
+    assert_clob_error(
+        market.place_limit_order(bob, Side::Bid, 900_000, 1).await,
+        ErrorCode::SideHalted,
+    );
+
+    // The crank settles the fill from before the halt, and the resting
+    // ask can still be cancelled
+    market.consume_events(10, &[alice]).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+    market
+        .cancel_order(alice, 1, Side::Ask)
+        .await
+        .expect("Cancels work while halted");
+    assert!(market.orderbooks_are_empty());
+
+    market.set_market_flags(None, 0).await.unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 900_000, 1)
+        .await
+        .expect("Orders accepted again");
+    assert_eq!(market.get_l2(Side::Bid), vec![(900_000, 1)]);
+
+    scenario.assert_conservation();
+}
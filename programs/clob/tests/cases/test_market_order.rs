@@ -0,0 +1,224 @@
+use anchor_lang::prelude::Pubkey;
+use clob::errors::ErrorCode;
+use clob::instructions::{LevelFill, PlaceMarketOrderResult};
+use clob::state::{Fill, Side};
+use clob::{MarketOrderComplete, OrderFilled};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+#[tokio::test]
+async fn test_market_bid_sweeps_every_level() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    for (price, quantity) in [(1_000_000, 2), (1_100_000, 3), (1_200_000, 5)] {
+        market
+            .place_limit_order(alice, Side::Ask, price, quantity)
+            .await
+            .unwrap();
+    }
+    let quote_before = market.get_user_balance(&bob.pubkey()).quote_balance;
+
+    let outcome = market.place_market_order(bob, Side::Bid, 8, None).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceMarketOrderResult = outcome.return_value();
+    let fills = outcome.events::<OrderFilled>();
+    assert_eq!(
+        fills.iter().map(|fill| fill.price).collect::<Vec<_>>(),
+        vec![1_000_000, 1_100_000, 1_200_000]
+    );
+    // 2 * 1000 + 3 * 1100 + 3 * 1200 quote
+    assert_eq!((result.filled_quantity, result.quote_amount), (8, 8_900));
+    assert_eq!(result.avg_price, 1_112_500);
+    assert_eq!(
+        quote_before - market.get_user_balance(&bob.pubkey()).quote_balance,
+        8_900
+    );
+    let complete = &outcome.events::<MarketOrderComplete>()[0];
+    assert_eq!(complete.order_id, result.order_id);
+    assert_eq!(
+        (complete.requested_quantity, complete.filled_quantity),
+        (8, 8)
+    );
+    assert_eq!(market.get_l2(Side::Ask), vec![(1_200_000, 2)]);
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+
+    // A quote cap stops the sweep once it cannot afford another lot
+    let outcome = market
+        .place_market_order(charlie, Side::Bid, 2, Some(1_500))
+        .await;
+    let result: PlaceMarketOrderResult = outcome.return_value();
+    assert_eq!((result.filled_quantity, result.quote_amount), (1, 1_200));
+    assert_eq!(market.get_l2(Side::Ask), vec![(1_200_000, 1)]);
+
+    market.consume_events(10, &[alice]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_market_ask_stops_when_the_book_runs_out() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    // Nothing to sell into: no order is placed
+    let outcome = market.place_market_order(alice, Side::Ask, 3, None).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceMarketOrderResult = outcome.return_value();
+    assert_eq!((result.order_id, result.filled_quantity), (0, 0));
+    assert_eq!(outcome.events::<MarketOrderComplete>().len(), 1);
+
+    market
+        .place_limit_order(bob, Side::Bid, 900_000, 1)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 800_000, 4)
+        .await
+        .unwrap();
+    let outcome = market.place_market_order(alice, Side::Ask, 10, None).await;
+    let result: PlaceMarketOrderResult = outcome.return_value();
+    assert_eq!((result.filled_quantity, result.quote_amount), (5, 4_100));
+    assert_eq!(result.avg_price, 820_000);
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+
+    // The quote cap is for bids only
+    assert_clob_error(
+        market
+            .place_market_order(alice, Side::Ask, 1, Some(1_000))
+            .await,
+        ErrorCode::InvalidParameter,
+    );
+
+    market.consume_events(10, &[bob]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_market_bid_short_of_quote_fails_whole() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let charlie = &scenario.charlie;
+
+    for (price, quantity) in [(1_000_000, 2), (1_100_000, 3)] {
+        market
+            .place_limit_order(alice, Side::Ask, price, quantity)
+            .await
+            .unwrap();
+    }
+    // Leave Charlie 3000 quote: two lots at 1000, not a third at 1100
+    let quote = market.get_user_balance(&charlie.pubkey()).quote_balance;
+    market
+        .withdraw(
+            &charlie.keypair,
+            market.quote_mint,
+            charlie.quote_account,
+            quote - 3_000,
+        )
+        .await
+        .unwrap();
+
+    assert_clob_error(
+        market
+            .place_market_order(&charlie.keypair, Side::Bid, 4, None)
+            .await,
+        ErrorCode::InsufficientBalance,
+    );
+    // Rolled back: the first two fills never happened
+    assert_eq!(
+        market.get_l2(Side::Ask),
+        vec![(1_000_000, 2), (1_100_000, 3)]
+    );
+    assert_eq!(
+        market.get_user_balance(&charlie.pubkey()).quote_balance,
+        3_000
+    );
+
+    let outcome = market
+        .place_market_order(&charlie.keypair, Side::Bid, 2, None)
+        .await;
+    let result: PlaceMarketOrderResult = outcome.return_value();
+    assert_eq!((result.filled_quantity, result.quote_amount), (2, 2_000));
+
+    market.consume_events(10, &[alice]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+fn level(price: u64, quantity: u64) -> LevelFill {
+    LevelFill { price, quantity }
+}
+
+#[test]
+fn test_level_fills_sum_adjacent_fills_per_price() {
+    let fill = |price, quantity| Fill {
+        maker_order_id: 1,
+        taker_order_id: 2,
+        maker_owner: Pubkey::default(),
+        maker_side: Side::Ask,
+        price,
+        quantity,
+        maker_remaining_quantity: 0,
+        maker_order_closed: true,
+    };
+    let fills = [fill(100, 1), fill(100, 2), fill(101, 4), fill(105, 1)];
+    assert_eq!(
+        LevelFill::from_fills(&fills),
+        vec![level(100, 3), level(101, 4), level(105, 1)]
+    );
+    assert!(LevelFill::from_fills(&[]).is_empty());
+}
+
+#[tokio::test]
+async fn test_market_bid_reports_fills_per_level() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    // Two makers share the first level
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Ask, 1_000_000, 1)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Ask, 1_100_000, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Ask, 1_200_000, 4)
+        .await
+        .unwrap();
+
+    let outcome = market.place_market_order(charlie, Side::Bid, 8, None).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceMarketOrderResult = outcome.return_value();
+    assert_eq!(
+        result.levels,
+        vec![
+            level(1_000_000, 3),
+            level(1_100_000, 3),
+            level(1_200_000, 2)
+        ]
+    );
+    assert!(!result.levels_truncated);
+    assert_eq!(
+        result.levels.iter().map(|l| l.quantity).sum::<u64>(),
+        result.filled_quantity
+    );
+    assert_eq!(market.get_l2(Side::Ask), vec![(1_200_000, 2)]);
+
+    market.consume_events(10, &[alice, bob]).await.unwrap();
+    scenario.assert_conservation();
+}
@@ -0,0 +1,77 @@
+use clob::events::UserBalanceMigrated;
+use clob::state::{Side, USER_BALANCE_SCHEMA_VERSION, USER_BALANCE_SIZE};
+
+use crate::svm::TradingScenario;
+
+// UserBalance up to next_intent_nonce, before the PnL, open order count and
+// schema version fields were appended
+const LEGACY_USER_BALANCE_SIZE: usize = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 8;
+
+#[tokio::test]
+async fn test_migrate_legacy_user_balance() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+
+    // Two orders, one of them increased
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 9_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 11_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .increase_order(&alice.keypair, 2, Side::Ask, 1)
+        .await
+        .unwrap();
+    let before = market.get_user_balance(&alice.pubkey());
+
+    market.truncate_user_balance(&alice.pubkey(), LEGACY_USER_BALANCE_SIZE);
+    assert_eq!(
+        market.get_user_balance_data(&alice.pubkey()).len(),
+        LEGACY_USER_BALANCE_SIZE
+    );
+
+    // Only the owner can migrate their balance
+    let bob = &scenario.bob;
+    assert!(
+        !market
+            .migrate_user_balance(&bob.keypair, &alice.pubkey())
+            .await
+            .success
+    );
+
+    let outcome = market
+        .migrate_user_balance(&alice.keypair, &alice.pubkey())
+        .await;
+    assert!(outcome.success, "{:#?}", outcome.logs);
+
+    assert_eq!(
+        market.get_user_balance_data(&alice.pubkey()).len(),
+        USER_BALANCE_SIZE
+    );
+    let migrated = market.get_user_balance(&alice.pubkey());
+    assert_eq!(migrated.base_balance, before.base_balance);
+    assert_eq!(migrated.quote_balance, before.quote_balance);
+    assert_eq!(migrated.next_intent_nonce, before.next_intent_nonce);
+    assert_eq!(migrated.open_order_count, 2);
+    assert_eq!(migrated.schema_version, USER_BALANCE_SCHEMA_VERSION);
+    assert_eq!(migrated.realized_pnl_quote, 0);
+
+    let events = outcome.events::<UserBalanceMigrated>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].previous_size as usize, LEGACY_USER_BALANCE_SIZE);
+
+    // Migrating again changes nothing
+    let migrated_data = market.get_user_balance_data(&alice.pubkey());
+    let outcome = market
+        .migrate_user_balance(&alice.keypair, &alice.pubkey())
+        .await;
+    assert!(outcome.success);
+    assert!(outcome.events::<UserBalanceMigrated>().is_empty());
+    assert_eq!(market.get_user_balance_data(&alice.pubkey()), migrated_data);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,83 @@
+use crate::svm::{market::get_vault_pda, test::TestFixture, TradingScenario};
+use anchor_spl::{token::spl_token, token_2022::spl_token_2022};
+use clob::state::Side;
+
+#[tokio::test]
+async fn test_mixed_token_program_market_trades_and_withdraws() {
+    // Classic SPL base, Token-2022 quote
+    let fixture = TestFixture::new_with_token_programs(spl_token::ID, spl_token_2022::ID).await;
+    let scenario = TradingScenario::with_fixture(fixture, |_| {}).await;
+    let market = &scenario.market;
+    let base_mint = &scenario.fixture.base_mint;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+
+    // Each vault is the same PDA either way, owned by its mint's program
+    assert_eq!(
+        market.base_vault,
+        get_vault_pda(&market.market, &base_mint.mint).0
+    );
+    assert_eq!(
+        market.quote_vault,
+        get_vault_pda(&market.market, &quote_mint.mint).0
+    );
+    {
+        let ctx = scenario.fixture.ctx.borrow();
+        assert_eq!(
+            ctx.svm.get_account(&market.base_vault).unwrap().owner,
+            spl_token::ID
+        );
+        assert_eq!(
+            ctx.svm.get_account(&market.quote_vault).unwrap().owner,
+            spl_token_2022::ID
+        );
+    }
+    assert_eq!(base_mint.balance(market.base_vault).await, 300_000_000);
+    assert_eq!(quote_mint.balance(market.quote_vault).await, 300_000_000);
+
+    // 2 lots at 10_000 quote each: 2_000_000 base for 20_000 quote
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 10_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 10_000_000, 2)
+        .await
+        .unwrap();
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+
+    let alice_balance = market.get_user_balance(&alice.pubkey());
+    let bob_balance = market.get_user_balance(&bob.pubkey());
+    assert_eq!(alice_balance.base_balance, 98_000_000);
+    assert_eq!(alice_balance.quote_balance, 100_020_000);
+    assert_eq!(bob_balance.base_balance, 102_000_000);
+    assert_eq!(bob_balance.quote_balance, 99_980_000);
+
+    // Withdraw the proceeds through each side's own token program
+    market
+        .withdraw(&alice.keypair, quote_mint.mint, alice.quote_account, 20_000)
+        .await
+        .expect("Token-2022 quote withdrawal");
+    market
+        .withdraw(&bob.keypair, base_mint.mint, bob.base_account, 2_000_000)
+        .await
+        .expect("SPL base withdrawal");
+
+    assert_eq!(quote_mint.balance(alice.quote_account).await, 900_020_000);
+    assert_eq!(base_mint.balance(bob.base_account).await, 902_000_000);
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).quote_balance,
+        100_000_000
+    );
+    assert_eq!(
+        market.get_user_balance(&bob.pubkey()).base_balance,
+        100_000_000
+    );
+
+    // The vaults still back every ledger balance
+    assert_eq!(base_mint.balance(market.base_vault).await, 298_000_000);
+    assert_eq!(quote_mint.balance(market.quote_vault).await, 299_980_000);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,219 @@
+use clob::errors::ErrorCode;
+use clob::events::{OrderCancelled, OrderModified};
+use clob::instructions::PlaceLimitOrderResult;
+use clob::state::{OrderStatus, Side};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+const BASE_LOT: u64 = 1_000_000;
+
+#[tokio::test]
+async fn test_quantity_down_keeps_priority_and_refunds() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let start = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+
+    // Alice (id 1) then Bob (id 2) rest asks at the same price
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+    scenario.fixture.ctx.borrow_mut().set_clock(start + 1);
+    market
+        .place_limit_order(bob, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+
+    scenario.fixture.ctx.borrow_mut().set_clock(start + 2);
+    let base_before = market.get_user_balance(&alice.pubkey()).base_balance;
+    let outcome = market
+        .modify_order(alice, 1, Side::Ask, None, Some(2))
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.order_id, 1);
+    assert_eq!(
+        (result.resting_quantity, result.status),
+        (2, OrderStatus::Resting)
+    );
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).base_balance - base_before,
+        3 * BASE_LOT
+    );
+    let order = market.find_order_in_asks(1).unwrap();
+    assert_eq!((order.remaining_quantity, order.timestamp), (2, start));
+    let modified = &outcome.events::<OrderModified>()[0];
+    assert_eq!((modified.old_order_id, modified.order_id), (1, 1));
+    assert_eq!(
+        (
+            modified.old_remaining_quantity,
+            modified.new_remaining_quantity
+        ),
+        (5, 2)
+    );
+
+    // Still ahead of Bob: a 3 lot taker clears Alice first
+    market
+        .place_limit_order(charlie, Side::Bid, 1_000_000, 3)
+        .await
+        .unwrap();
+    assert!(market.find_order_in_asks(1).is_none());
+    assert_eq!(market.find_order_in_asks(2).unwrap().remaining_quantity, 4);
+
+    market.consume_events(10, &[alice, bob]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_price_up_requeues_under_new_id() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let start = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+
+    market
+        .place_limit_order(alice, Side::Bid, 900_000, 4)
+        .await
+        .unwrap();
+    scenario.fixture.ctx.borrow_mut().set_clock(start + 5);
+    let quote_before = market.get_user_balance(&alice.pubkey()).quote_balance;
+
+    let outcome = market
+        .modify_order(alice, 1, Side::Bid, Some(950_000), None)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_ne!(result.order_id, 1);
+    assert_eq!(
+        (result.resting_quantity, result.status),
+        (4, OrderStatus::Resting)
+    );
+
+    // 4 lots now reserve 3_800 quote instead of 3_600
+    let balance = market.get_user_balance(&alice.pubkey());
+    assert_eq!(quote_before - balance.quote_balance, 200);
+    assert_eq!(balance.open_order_count, 1);
+    assert!(market.find_order_in_bids(1).is_none());
+    let order = market.find_order_in_bids(result.order_id).unwrap();
+    assert_eq!((order.price, order.timestamp), (950_000, start + 5));
+    assert_eq!(market.get_l2(Side::Bid), vec![(950_000, 4)]);
+
+    let cancelled = outcome.events::<OrderCancelled>();
+    assert_eq!(
+        (cancelled[0].order_id, cancelled[0].remaining_quantity),
+        (1, 4)
+    );
+    let modified = &outcome.events::<OrderModified>()[0];
+    assert_eq!(
+        (modified.old_order_id, modified.order_id, modified.price),
+        (1, result.order_id, 950_000)
+    );
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_modify_that_crosses_fills_then_rests() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(bob, Side::Ask, 1_000_000, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Bid, 900_000, 5)
+        .await
+        .unwrap();
+
+    let outcome = market
+        .modify_order(alice, 2, Side::Bid, Some(1_000_000), None)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!((result.filled_quantity, result.resting_quantity), (3, 2));
+    assert_eq!(result.status, OrderStatus::PartiallyFilled);
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+    assert_eq!(market.get_l2(Side::Bid), vec![(1_000_000, 2)]);
+
+    market.consume_events(10, &[bob]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_modify_rejects_other_owners_and_no_ops() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+
+    assert_clob_error(
+        market.modify_order(bob, 1, Side::Ask, None, Some(2)).await,
+        ErrorCode::Unauthorized,
+    );
+    assert_clob_error(
+        market
+            .modify_order(alice, 1, Side::Ask, None, Some(5))
+            .await,
+        ErrorCode::InvalidParameter,
+    );
+    assert_clob_error(
+        market
+            .modify_order(alice, 1, Side::Ask, None, Some(0))
+            .await,
+        ErrorCode::InvalidOrderSize,
+    );
+    assert_clob_error(
+        market
+            .modify_order(alice, 9, Side::Ask, None, Some(2))
+            .await,
+        ErrorCode::OrderNotFound,
+    );
+    assert_eq!(market.get_l2(Side::Ask), vec![(1_000_000, 5)]);
+}
+
+#[tokio::test]
+async fn test_modify_beyond_balance_fails_and_keeps_order() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    // 50 lots at 1_000_000 reserve 50_000_000 of Alice's 100_000_000 quote
+    market
+        .place_limit_order(alice, Side::Bid, 1_000_000, 50)
+        .await
+        .unwrap();
+    let before = market.get_user_balance(&alice.pubkey());
+
+    // 120 lots would need 120_000_000 even with the old reservation back
+    assert_clob_error(
+        market
+            .modify_order(alice, 1, Side::Bid, None, Some(120))
+            .await,
+        ErrorCode::InsufficientBalance,
+    );
+    assert_clob_error(
+        market
+            .modify_order(alice, 1, Side::Bid, Some(2_100_000), None)
+            .await,
+        ErrorCode::InsufficientBalance,
+    );
+
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).quote_balance,
+        before.quote_balance
+    );
+    assert_eq!(market.find_order_in_bids(1).unwrap().remaining_quantity, 50);
+    assert_eq!(market.get_l2(Side::Bid), vec![(1_000_000, 50)]);
+    scenario.assert_conservation();
+}
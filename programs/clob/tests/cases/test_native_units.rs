@@ -0,0 +1,87 @@
+use clob::events::{NativeOrderConverted, OrderPlaced};
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_native_order_converts_and_settles_exactly() {
+    // Default market: 1_000_000 base per lot, 1_000 quote per tick
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    // 3 lots at 5_000 quote tokens per lot
+    let outcome = market
+        .place_limit_order_native(alice, Side::Ask, 5_000, 3_000_000)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    let converted = outcome.events::<NativeOrderConverted>();
+    assert_eq!(converted.len(), 1);
+    assert_eq!(converted[0].price_native_quote_per_lot, 5_000);
+    assert_eq!(converted[0].quantity_native_base, 3_000_000);
+    assert_eq!(converted[0].price, 5_000_000);
+    assert_eq!(converted[0].quantity, 3);
+
+    let placed = outcome.events::<OrderPlaced>();
+    assert_eq!(placed[0].order_id, converted[0].order_id);
+    assert_eq!(placed[0].price, 5_000_000);
+    assert_eq!(placed[0].quantity, 3);
+
+    let bob_before = market.get_user_balance(&bob.pubkey());
+    let outcome = market
+        .place_limit_order_native(bob, Side::Bid, 5_000, 2_000_000)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    // Two lots at 5_000 per lot cost exactly 10_000 quote tokens
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_before.quote_balance - bob_after.quote_balance, 10_000);
+    assert_eq!(bob_after.base_balance - bob_before.base_balance, 2_000_000);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_native_quantity_must_be_whole_lots() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    let outcome = market
+        .place_limit_order_native(&scenario.alice.keypair, Side::Ask, 5_000, 1_500_000)
+        .await;
+    assert!(!outcome.success);
+    assert!(outcome.logs_contain("QuantityNotLotAligned"));
+    assert!(market.orderbooks_are_empty());
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_native_price_must_be_whole_ticks() {
+    // A tick is 1_000 quote tokens per lot here
+    let scenario = TradingScenario::with_market_params(|params| {
+        params.base_lot_size = 1_000;
+        params.quote_tick_size = 1_000_000;
+    })
+    .await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    let outcome = market
+        .place_limit_order_native(alice, Side::Ask, 2_500, 1_000)
+        .await;
+    assert!(!outcome.success);
+    assert!(outcome.logs_contain("PriceNotTickAligned"));
+    assert!(market.orderbooks_are_empty());
+
+    let outcome = market
+        .place_limit_order_native(alice, Side::Ask, 3_000, 1_000)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+    assert_eq!(outcome.events::<NativeOrderConverted>()[0].price, 3);
+
+    scenario.assert_conservation();
+}
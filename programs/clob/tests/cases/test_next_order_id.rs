@@ -0,0 +1,51 @@
+use clob::events::OrderPlaced;
+use clob::instructions::PlaceLimitOrderResult;
+use clob::state::{FillStatus, OrderStatus, Side, TimeInForce};
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_query_matches_the_id_placement_assigns() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 10, 5)
+        .await
+        .unwrap();
+
+    let next_id: u64 = market.query_next_order_id().await.return_value();
+    assert_eq!(next_id, 2);
+
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Bid, 10, 2, TimeInForce::GTC)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(
+        result,
+        PlaceLimitOrderResult {
+            order_id: next_id,
+            filled_quantity: 2,
+            resting_quantity: 0,
+            status: OrderStatus::Filled,
+            fill_status: FillStatus::FullyFilled,
+        }
+    );
+
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Bid, 9, 1, TimeInForce::GTC)
+        .await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.order_id, next_id + 1);
+    assert_eq!(result.status, OrderStatus::Resting);
+    assert_eq!(outcome.events::<OrderPlaced>()[0].order_id, result.order_id);
+    assert_eq!(
+        market.query_next_order_id().await.return_value::<u64>(),
+        next_id + 2
+    );
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,75 @@
+use clob::state::Side;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_open_order_count_across_place_cancel_and_fill() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+
+    // Orders 1, 2 and 3, each at its own level
+    for price in [10_000_000, 11_000_000, 12_000_000] {
+        market
+            .place_limit_order(&alice.keypair, Side::Ask, price, 2)
+            .await
+            .unwrap();
+    }
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 3);
+
+    market
+        .cancel_order(&alice.keypair, 3, Side::Ask)
+        .await
+        .unwrap();
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 2);
+
+    // Bob takes all of order 1 and nothing rests for him
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 10_000_000, 2)
+        .await
+        .unwrap();
+    assert_eq!(market.get_open_order_count(&bob.pubkey()), 0);
+
+    // The maker's count moves when the fill settles
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 2);
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 1);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_increased_order_counts_once_until_filled() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 10_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .increase_order(&alice.keypair, 1, Side::Ask, 3)
+        .await
+        .unwrap();
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 1);
+
+    // A partial fill leaves the increased order resting
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 10_000_000, 2)
+        .await
+        .unwrap();
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 1);
+
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 10_000_000, 3)
+        .await
+        .unwrap();
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 0);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::Pubkey;
+use clob::state::{Side, TimeInForce};
+
+use crate::svm::{market::get_user_balance_pda, TradingScenario};
+
+const BOND: u64 = 5_000_000;
+
+fn lamports(scenario: &TradingScenario, address: &Pubkey) -> u64 {
+    scenario.fixture.ctx.borrow().lamports(address)
+}
+
+async fn bonded_scenario() -> TradingScenario {
+    TradingScenario::with_market_params(|params| params.order_bond_lamports = BOND).await
+}
+
+#[tokio::test]
+async fn test_bond_round_trips_on_cancel() {
+    let scenario = bonded_scenario().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+    let (alice_balance, _) = get_user_balance_pda(&alice.pubkey(), &market.market);
+    let alice_before = lamports(&scenario, &alice.pubkey());
+    let market_before = lamports(&scenario, &market.market);
+    let balance_rent = lamports(&scenario, &alice_balance);
+
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 10_000_000, 2)
+        .await
+        .unwrap();
+    assert_eq!(lamports(&scenario, &alice.pubkey()), alice_before - BOND);
+    assert_eq!(lamports(&scenario, &market.market), market_before + BOND);
+
+    // An order that never rests pays nothing
+    let bob_before = lamports(&scenario, &bob.pubkey());
+    market
+        .place_limit_order_with_tif(&bob.keypair, Side::Bid, 9_000_000, 2, TimeInForce::IOC)
+        .await
+        .unwrap();
+    assert_eq!(lamports(&scenario, &bob.pubkey()), bob_before);
+
+    market
+        .cancel_order(&alice.keypair, 1, Side::Ask)
+        .await
+        .unwrap();
+    assert_eq!(lamports(&scenario, &market.market), market_before);
+    assert_eq!(lamports(&scenario, &alice_balance), balance_rent + BOND);
+
+    // Closing the balance pays the bond out with the rent
+    let balance = market.get_user_balance(&alice.pubkey());
+    market
+        .withdraw(
+            &alice.keypair,
+            market.base_mint,
+            alice.base_account,
+            balance.base_balance,
+        )
+        .await
+        .unwrap();
+    market
+        .withdraw(
+            &alice.keypair,
+            market.quote_mint,
+            alice.quote_account,
+            balance.quote_balance,
+        )
+        .await
+        .unwrap();
+    market.close_user_balance(&alice.keypair).await.unwrap();
+    assert_eq!(
+        lamports(&scenario, &alice.pubkey()),
+        alice_before + balance_rent
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_bond_returned_when_fill_settles() {
+    let scenario = bonded_scenario().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+    let (alice_balance, _) = get_user_balance_pda(&alice.pubkey(), &market.market);
+    let market_before = lamports(&scenario, &market.market);
+    let balance_before = lamports(&scenario, &alice_balance);
+
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 10_000_000, 3)
+        .await
+        .unwrap();
+
+    // A partial fill leaves the order, and its bond, on the book
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 10_000_000, 1)
+        .await
+        .unwrap();
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    assert_eq!(lamports(&scenario, &market.market), market_before + BOND);
+
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 10_000_000, 2)
+        .await
+        .unwrap();
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    assert_eq!(lamports(&scenario, &market.market), market_before);
+    assert_eq!(lamports(&scenario, &alice_balance), balance_before + BOND);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_updated_bond_applies_to_orders_placed_after() {
+    let scenario = bonded_scenario().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let (alice_balance, _) = get_user_balance_pda(&alice.pubkey(), &market.market);
+    let market_before = lamports(&scenario, &market.market);
+    let balance_before = lamports(&scenario, &alice_balance);
+
+    // Same lot and tick, a bigger bond from now on
+    let market_state = market.get_market();
+    market
+        .update_market_params_with_bond(
+            market_state.base_lot_size,
+            market_state.quote_tick_size,
+            3 * BOND,
+        )
+        .await
+        .unwrap();
+
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 10_000_000, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        lamports(&scenario, &market.market),
+        market_before + 3 * BOND
+    );
+
+    market
+        .cancel_order(&alice.keypair, 1, Side::Ask)
+        .await
+        .unwrap();
+    assert_eq!(lamports(&scenario, &market.market), market_before);
+    assert_eq!(
+        lamports(&scenario, &alice_balance),
+        balance_before + 3 * BOND
+    );
+
+    scenario.assert_conservation();
+}
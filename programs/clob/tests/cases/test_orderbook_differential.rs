@@ -0,0 +1,181 @@
+use anchor_lang::prelude::Pubkey;
+use clob::state::{
+    compute_book_hash, AskOrderBook, BidOrderBook, Order, OrderBook, Side, TieBreakMode,
+    VecOrderBook, ORDER_FLAG_ALL_OR_NONE,
+};
+
+/// xorshift64*, so runs are reproducible without an rng dependency
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low + 1)
+    }
+}
+
+fn sorted_by_id(orders: &[Order]) -> Vec<Order> {
+    let mut orders = orders.to_vec();
+    orders.sort_by_key(|order| order.order_id);
+    orders
+}
+
+/// Replay the same random place/cancel sequence against both books and
+/// compare every fill, the surviving orders and the heap's owner table
+/// reference counts after each step
+fn run_differential<B: OrderBook>(
+    seed: u64,
+    side: Side,
+    tie_break: TieBreakMode,
+    heap: &mut B,
+    heap_orders: impl Fn(&B) -> Vec<Order>,
+    heap_owner_orders: impl Fn(&B, &Pubkey) -> usize,
+) {
+    let mut rng = Rng(seed);
+    let mut model = VecOrderBook::with_tie_break(side, tie_break);
+    let owners: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let mut next_id = 1;
+    let mut timestamp = 0;
+
+    for step in 0..600 {
+        // Coarse timestamps so plenty of orders tie on time as well as price
+        timestamp += rng.range(0, 1) as i64;
+
+        if rng.range(0, 4) == 0 && !model.is_empty() {
+            // Cancel a random resting order (or a missing id now and then)
+            let order_id = if rng.range(0, 9) == 0 {
+                next_id + 1_000
+            } else {
+                model.orders()[rng.range(0, model.len() as u64 - 1) as usize].order_id
+            };
+            assert_eq!(
+                heap.remove_order(order_id).unwrap(),
+                model.remove_order(order_id).unwrap(),
+                "seed {seed} step {step}: remove {order_id}"
+            );
+        } else {
+            // Place: a resting order on this side, or a taker from the other
+            let quantity = rng.range(1, 20);
+            let mut order = Order {
+                order_id: next_id,
+                owner: owners[rng.range(0, 3) as usize],
+                price: rng.range(95, 105),
+                quantity,
+                remaining_quantity: quantity,
+                timestamp,
+                flags: if rng.range(0, 4) == 0 {
+                    ORDER_FLAG_ALL_OR_NONE
+                } else {
+                    0
+                },
+                ..Default::default()
+            };
+            next_id += 1;
+
+            if rng.range(0, 2) == 0 {
+                let mut model_taker = order;
+                let heap_fills = heap.match_orders(&mut order).unwrap();
+                let model_fills = model.match_orders(&mut model_taker).unwrap();
+                assert_eq!(heap_fills, model_fills, "seed {seed} step {step}: fills");
+                assert_eq!(order, model_taker);
+            } else if !model
+                .peek()
+                .is_some_and(|best| crossing(side, best.price, order.price))
+            {
+                heap.insert_order(order).unwrap();
+                model.insert_order(order).unwrap();
+            }
+        }
+
+        let heap_orders = heap_orders(heap);
+        assert_eq!(
+            sorted_by_id(&heap_orders),
+            sorted_by_id(model.orders()),
+            "seed {seed} step {step}: surviving orders"
+        );
+        assert_eq!(compute_book_hash(&heap_orders), model.book_hash());
+        assert_eq!(heap.get_best_price(), model.get_best_price());
+        for owner in owners.iter() {
+            assert_eq!(
+                heap_owner_orders(heap, owner),
+                model
+                    .orders()
+                    .iter()
+                    .filter(|order| order.owner == *owner)
+                    .count(),
+                "seed {seed} step {step}: owner table refs"
+            );
+        }
+    }
+}
+
+/// Whether a resting order at `resting` would cross an incoming `limit`,
+/// i.e. the order is a taker rather than a maker
+fn crossing(side: Side, resting: u64, limit: u64) -> bool {
+    match side {
+        Side::Bid => resting >= limit,
+        Side::Ask => resting <= limit,
+    }
+}
+
+#[test]
+fn test_vec_and_heap_books_agree() {
+    for seed in 1..=8u64 {
+        for tie_break in [TieBreakMode::Time, TieBreakMode::Size] {
+            let mut bids = BidOrderBook::with_tie_break(tie_break);
+            run_differential(
+                seed,
+                Side::Bid,
+                tie_break,
+                &mut bids,
+                |book| book.orders(),
+                |book, owner| book.owner_order_count(owner),
+            );
+
+            let mut asks = AskOrderBook::with_tie_break(tie_break);
+            run_differential(
+                seed * 7919,
+                Side::Ask,
+                tie_break,
+                &mut asks,
+                |book| book.orders(),
+                |book, owner| book.owner_order_count(owner),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_vec_book_crossing_orders_match_heap() {
+    let mut heap = AskOrderBook::new();
+    let mut model = VecOrderBook::new(Side::Ask);
+    for (id, price, timestamp) in [(1, 10, 3), (2, 9, 4), (3, 10, 1), (4, 12, 2), (5, 9, 4)] {
+        let order = Order {
+            order_id: id,
+            owner: Pubkey::new_unique(),
+            price,
+            quantity: 1,
+            remaining_quantity: 1,
+            timestamp,
+            ..Default::default()
+        };
+        heap.insert_order(order).unwrap();
+        model.insert_order(order).unwrap();
+    }
+
+    assert_eq!(heap.crossing_orders(10), model.crossing_orders(10));
+    assert_eq!(
+        model
+            .crossing_orders(10)
+            .iter()
+            .map(|order| order.order_id)
+            .collect::<Vec<_>>(),
+        vec![2, 5, 3, 1]
+    );
+}
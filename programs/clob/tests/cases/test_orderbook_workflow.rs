@@ -1,7 +1,9 @@
+use clob::errors::ErrorCode;
+use clob::events::OrderCancelled;
 use clob::state::Side;
 use solana_sdk::signature::Signer;
 
-use crate::svm::TwoUserScenario;
+use crate::svm::{assert_clob_error, parse_events, TradingScenario, TwoUserScenario};
 
 #[tokio::test]
 async fn test_orderbook_basic_matching() {
@@ -94,6 +96,8 @@ async fn test_orderbook_basic_matching() {
         alice_order_updated.remaining_quantity
     );
 
+    scenario.assert_conservation();
+
     println!("=== Test 2 Completed: Basic matching works ===");
 
     // Test 3: Place non-matching order
@@ -123,15 +127,15 @@ async fn test_orderbook_basic_matching() {
     );
     println!("Verified Bob's non-matching order is correctly stored in bids orderbook");
 
-    // Verify orderbook counts
-    let bids_count = market.get_orderbook_order_count(Side::Bid);
-    let asks_count = market.get_orderbook_order_count(Side::Ask);
-    assert_eq!(bids_count, 1, "Should have 1 bid order");
-    assert_eq!(asks_count, 1, "Should have 1 ask order");
-    println!(
-        "Verified orderbook counts: {} bids, {} asks",
-        bids_count, asks_count
-    );
+    // Alice's remaining 5 @ 5 rests opposite Bob's 3 @ 4
+    let book = market.get_book_state();
+    assert_eq!(book.bids, vec![(4, 3)]);
+    assert_eq!(book.asks, vec![(5, 5)]);
+    assert_eq!(book.spread, Some(1));
+    assert_eq!((book.bid_count, book.ask_count), (1, 1));
+    println!("Verified book state: {:?}", book);
+
+    scenario.assert_conservation();
 
     // Test 4: Cancel order
     println!("=== Test 4: Order Cancellation ===");
@@ -155,18 +159,16 @@ async fn test_orderbook_basic_matching() {
         "Bob's order 3 should be removed from bids after cancellation"
     );
 
-    // Verify orderbook counts after cancellation
-    let bids_count_after = market.get_orderbook_order_count(Side::Bid);
-    let asks_count_after = market.get_orderbook_order_count(Side::Ask);
-    assert_eq!(
-        bids_count_after, 0,
+    let book = market.get_book_state();
+    assert!(
+        book.bids.is_empty(),
         "Should have 0 bid orders after cancellation"
     );
-    assert_eq!(asks_count_after, 1, "Should still have 1 ask order");
-    println!(
-        "Verified order cancellation: {} bids, {} asks",
-        bids_count_after, asks_count_after
-    );
+    assert_eq!(book.asks, vec![(5, 5)], "Should still have 1 ask order");
+    assert_eq!(book.spread, None);
+    println!("Verified order cancellation: {:?}", book);
+
+    scenario.assert_conservation();
 
     println!("=== All Orderbook Tests Passed! ===");
 }
@@ -252,5 +254,91 @@ async fn test_partial_fills_and_price_time_priority() {
     assert_eq!(asks_count, 0, "Should have 0 ask orders");
     println!("Charlie's bid order (30 units) fully consumed Alice's remaining ask. Both orderbooks empty.");
 
+    scenario.assert_conservation();
+
     println!("=== Partial Fill Test Completed Successfully ===");
 }
+
+#[tokio::test]
+async fn test_initialize_wires_event_queue() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    // Initialize records the queue it zeroed, so has_one = event_queue holds
+    assert_eq!(market.get_market().event_queue, market.event_queue);
+    assert_eq!(market.event_queue_len(), 0);
+
+    market
+        .place_limit_order(alice, Side::Bid, 1_000_000, 1)
+        .await
+        .unwrap();
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 1);
+}
+
+#[tokio::test]
+async fn test_cancel_order_emits_order_cancelled() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+    let order_id = market.get_market().next_order_id - 1;
+    market
+        .place_limit_order(bob, Side::Bid, 1_000_000, 2)
+        .await
+        .unwrap();
+
+    let meta = market
+        .cancel_order(alice, order_id, Side::Ask)
+        .await
+        .unwrap();
+    let cancelled = parse_events::<OrderCancelled>(&meta.logs);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].order_id, order_id);
+    assert_eq!(cancelled[0].owner, alice.pubkey());
+    assert_eq!(cancelled[0].market, market.market);
+    assert_eq!(cancelled[0].side, Side::Ask);
+    // What was still unfilled, not the 5 originally placed
+    assert_eq!(cancelled[0].remaining_quantity, 3);
+}
+
+#[tokio::test]
+async fn test_failed_cancel_leaves_book_unchanged() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+    let order_id = market.get_market().next_order_id - 1;
+
+    assert_clob_error(
+        market.cancel_order(bob, order_id, Side::Ask).await,
+        ErrorCode::Unauthorized,
+    );
+    assert_clob_error(
+        market.cancel_order(alice, order_id + 1, Side::Ask).await,
+        ErrorCode::OrderNotFound,
+    );
+    // Looked up on the side given, not wherever the id happens to rest
+    assert_clob_error(
+        market.cancel_order(alice, order_id, Side::Bid).await,
+        ErrorCode::OrderNotFound,
+    );
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 1);
+    assert_eq!(
+        market
+            .find_order_in_asks(order_id)
+            .unwrap()
+            .remaining_quantity,
+        5
+    );
+}
@@ -0,0 +1,177 @@
+use clob::errors::ErrorCode;
+use clob::events::PauseFlagsUpdated;
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_deposit_and_withdraw_pause_independently() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+
+    // (deposits_paused, withdrawals_paused)
+    let matrix = [(false, false), (true, false), (false, true), (true, true)];
+
+    for (deposits_paused, withdrawals_paused) in matrix {
+        let meta = market
+            .set_pause_flags(None, deposits_paused, withdrawals_paused)
+            .await
+            .expect("Authority sets pause flags");
+        let updated = parse_events::<PauseFlagsUpdated>(&meta.logs);
+        assert_eq!(updated[0].deposits_paused, deposits_paused);
+        assert_eq!(updated[0].withdrawals_paused, withdrawals_paused);
+
+        let deposit = market
+            .deposit(&alice.keypair, market.base_mint, alice.base_account, 1_000)
+            .await;
+        let withdraw = market
+            .withdraw(&alice.keypair, market.base_mint, alice.base_account, 1_000)
+            .await;
+        let label =
+            format!("deposits_paused {deposits_paused}, withdrawals_paused {withdrawals_paused}");
+        if deposits_paused {
+            assert_clob_error(deposit, ErrorCode::MarketPaused);
+        } else {
+            assert!(deposit.is_ok(), "deposit with {label}");
+        }
+        if withdrawals_paused {
+            assert_clob_error(withdraw, ErrorCode::MarketPaused);
+        } else {
+            assert!(withdraw.is_ok(), "withdraw with {label}");
+        }
+    }
+
+    // Only the authority may pause
+    assert_clob_error(
+        market
+            .set_pause_flags(Some(&scenario.bob.keypair), true, true)
+            .await,
+        ErrorCode::Unauthorized,
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_empty_balance_closes_while_paused() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+
+    let balance = market.get_user_balance(&alice.keypair.pubkey());
+    market
+        .withdraw(
+            &alice.keypair,
+            market.base_mint,
+            alice.base_account,
+            balance.base_balance,
+        )
+        .await
+        .unwrap();
+    market
+        .withdraw(
+            &alice.keypair,
+            market.quote_mint,
+            alice.quote_account,
+            balance.quote_balance,
+        )
+        .await
+        .unwrap();
+
+    market.set_pause_flags(None, true, true).await.unwrap();
+    market
+        .close_user_balance(&alice.keypair)
+        .await
+        .expect("Closing an empty balance ignores the pause");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_cancel_to_wallet_obeys_withdrawal_pause() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 2_000, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 2_000, 5)
+        .await
+        .unwrap();
+
+    market.set_pause_flags(None, false, true).await.unwrap();
+    assert_clob_error(
+        market
+            .cancel_order_to_wallet(&alice.keypair, 1, Side::Bid, alice.quote_account)
+            .await,
+        ErrorCode::MarketPaused,
+    );
+    assert!(market.find_order_in_bids(1).is_some());
+
+    // A refund to UserBalance moves nothing out of the vault
+    market
+        .cancel_order(&alice.keypair, 2, Side::Bid)
+        .await
+        .expect("Cancel to balance ignores the pause");
+
+    market.set_pause_flags(None, true, false).await.unwrap();
+    market
+        .cancel_order_to_wallet(&alice.keypair, 1, Side::Bid, alice.quote_account)
+        .await
+        .expect("Only the withdrawal pause stops a wallet refund");
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_swap_obeys_both_pauses() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let charlie = &scenario.charlie;
+
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 5_000_000, 2)
+        .await
+        .unwrap();
+
+    for (deposits_paused, withdrawals_paused) in [(true, false), (false, true)] {
+        market
+            .set_pause_flags(None, deposits_paused, withdrawals_paused)
+            .await
+            .unwrap();
+        assert_clob_error(
+            market
+                .swap_exact_in(
+                    &charlie.keypair,
+                    charlie.base_account,
+                    charlie.quote_account,
+                    Side::Bid,
+                    5_000,
+                    1_000_000,
+                )
+                .await,
+            ErrorCode::MarketPaused,
+        );
+    }
+
+    market.set_pause_flags(None, false, false).await.unwrap();
+    let outcome = market
+        .swap_exact_in(
+            &charlie.keypair,
+            charlie.base_account,
+            charlie.quote_account,
+            Side::Bid,
+            5_000,
+            1_000_000,
+        )
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    scenario.assert_conservation();
+}
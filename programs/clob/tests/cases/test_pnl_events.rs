@@ -0,0 +1,69 @@
+use clob::events::{MakerFillSettled, OrderFilled};
+use clob::state::{Side, TimeInForce};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_fill_events_carry_quote_components() {
+    let scenario = TradingScenario::with_market_params(|params| {
+        params.maker_fee_bps = 10;
+        params.taker_fee_bps = 20;
+    })
+    .await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let params = market.get_market();
+
+    market
+        .place_limit_order(alice, Side::Bid, 2_100, 5)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Bid, 2_000, 5)
+        .await
+        .unwrap();
+
+    // An ask taker pays its fee out of the quote it receives
+    let bob_before = market.get_user_balance(&bob.pubkey()).quote_balance;
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Ask, 2_000, 8, TimeInForce::IOC)
+        .await;
+    assert!(outcome.success);
+    let bob_after = market.get_user_balance(&bob.pubkey()).quote_balance;
+
+    let fills = outcome.events::<OrderFilled>();
+    assert_eq!(fills.len(), 2);
+    for fill in &fills {
+        assert_eq!(
+            fill.gross_quote,
+            fill.price * fill.quantity * params.quote_tick_size / params.base_lot_size
+        );
+        assert!(fill.fee_quote > 0);
+        assert_eq!(fill.fee_base, 0);
+        assert_eq!(fill.net_quote, fill.gross_quote - fill.fee_quote);
+    }
+    assert_eq!(
+        bob_after - bob_before,
+        fills.iter().map(|fill| fill.net_quote).sum::<u64>(),
+        "The taker receives exactly the reported net quote"
+    );
+
+    // Settling the maker side reports the same fills from Alice's point of
+    // view; a bid maker pays its fee in base
+    let meta = market.consume_events(10, &[alice]).await.unwrap();
+    let settled = parse_events::<MakerFillSettled>(&meta.logs);
+    assert_eq!(settled.len(), 2);
+    for (settle, fill) in settled.iter().zip(&fills) {
+        assert_eq!(settle.maker_order_id, fill.maker_order_id);
+        assert_eq!(settle.maker_owner, alice.pubkey());
+        assert_eq!(settle.maker_side, Side::Bid);
+        assert_eq!(settle.gross_quote, fill.gross_quote);
+        assert!(settle.fee_base > 0);
+        assert_eq!(settle.fee_quote, 0);
+        assert_eq!(settle.net_quote, settle.gross_quote - settle.fee_quote);
+    }
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,98 @@
+use clob::errors::ErrorCode;
+use clob::instructions::PlaceLimitOrderResult;
+use clob::state::{FillStatus, OrderStatus, Side, TimeInForce};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+#[tokio::test]
+async fn test_post_only_rejects_crossing_and_rests_otherwise() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    // At or through the best ask it would take, so it is refused whole
+    for price in [1_000_000, 1_100_000] {
+        assert_clob_error(
+            market
+                .place_limit_order_meta(bob, Side::Bid, price, 2, TimeInForce::PostOnly)
+                .await,
+            ErrorCode::PostOnlyWouldCross,
+        );
+    }
+    assert_eq!(market.get_l2(Side::Ask), vec![(1_000_000, 5)]);
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.quote_balance, bob_before.quote_balance);
+    assert_eq!(bob_after.open_order_count, 0);
+
+    // One tick below rests like a GTC
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Bid, 999_999, 2, TimeInForce::PostOnly)
+        .await;
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!(result.status, OrderStatus::Resting);
+    assert_eq!(result.fill_status, FillStatus::Resting);
+    assert_eq!(market.get_l2(Side::Bid), vec![(999_999, 2)]);
+
+    // The same holds for asks against the bid just placed
+    assert_clob_error(
+        market
+            .place_limit_order_meta(alice, Side::Ask, 999_999, 1, TimeInForce::PostOnly)
+            .await,
+        ErrorCode::PostOnlyWouldCross,
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_post_only_rests_against_an_empty_book() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    let outcome = market
+        .place_limit_order_meta(alice, Side::Ask, 1, 3, TimeInForce::PostOnly)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    assert_eq!(market.get_l2(Side::Ask), vec![(1, 3)]);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_post_only_reserves_like_gtc() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let bob = &scenario.bob.keypair;
+
+    let before = market.get_user_balance(&bob.pubkey());
+    market
+        .place_limit_order_with_tif(bob, Side::Bid, 900_000, 4, TimeInForce::GTC)
+        .await
+        .unwrap();
+    let after_gtc = market.get_user_balance(&bob.pubkey());
+    market
+        .place_limit_order_with_tif(bob, Side::Bid, 900_000, 4, TimeInForce::PostOnly)
+        .await
+        .unwrap();
+    let after_post_only = market.get_user_balance(&bob.pubkey());
+
+    assert_eq!(before.quote_balance - after_gtc.quote_balance, 3_600);
+    assert_eq!(
+        after_gtc.quote_balance - after_post_only.quote_balance,
+        3_600
+    );
+    assert_eq!(after_post_only.open_order_count, 2);
+    assert_eq!(market.get_l2(Side::Bid), vec![(900_000, 8)]);
+
+    scenario.assert_conservation();
+}
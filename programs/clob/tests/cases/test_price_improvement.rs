@@ -0,0 +1,87 @@
+use clob::events::OrderFilled;
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::{parse_events, TradingScenario};
+
+const BASE_LOT: u64 = 1_000_000;
+
+#[tokio::test]
+async fn test_bid_above_ask_fills_at_ask_price() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 5)
+        .await
+        .unwrap();
+    // Bob would pay up to 6_000 quote; the ask asks for 5_000
+    let meta = market
+        .place_limit_order(bob, Side::Bid, 1_200_000, 5)
+        .await
+        .unwrap();
+    let fill = &parse_events::<OrderFilled>(&meta.logs)[0];
+    assert_eq!((fill.price, fill.gross_quote), (1_000_000, 5_000));
+    market.consume_events(10, &[alice]).await.unwrap();
+
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(
+        alice_before.base_balance - alice_after.base_balance,
+        5 * BASE_LOT
+    );
+    assert_eq!(
+        alice_after.quote_balance - alice_before.quote_balance,
+        5_000
+    );
+    assert_eq!(
+        bob_after.base_balance - bob_before.base_balance,
+        5 * BASE_LOT
+    );
+    assert_eq!(bob_before.quote_balance - bob_after.quote_balance, 5_000);
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_ask_below_bid_fills_at_bid_price() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    market
+        .place_limit_order(bob, Side::Bid, 1_000_000, 5)
+        .await
+        .unwrap();
+    // Alice would take 4_000 quote; the bid pays 5_000
+    let meta = market
+        .place_limit_order(alice, Side::Ask, 800_000, 5)
+        .await
+        .unwrap();
+    let fill = &parse_events::<OrderFilled>(&meta.logs)[0];
+    assert_eq!((fill.price, fill.gross_quote), (1_000_000, 5_000));
+    market.consume_events(10, &[bob]).await.unwrap();
+
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(
+        alice_before.base_balance - alice_after.base_balance,
+        5 * BASE_LOT
+    );
+    assert_eq!(
+        alice_after.quote_balance - alice_before.quote_balance,
+        5_000
+    );
+    assert_eq!(
+        bob_after.base_balance - bob_before.base_balance,
+        5 * BASE_LOT
+    );
+    assert_eq!(bob_before.quote_balance - bob_after.quote_balance, 5_000);
+    scenario.assert_conservation();
+}
@@ -0,0 +1,77 @@
+use clob::errors::ErrorCode;
+use clob::state::Side;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+const MAX_ORDERS_PER_LEVEL: u16 = 3;
+
+#[tokio::test]
+async fn test_price_level_rejects_orders_past_the_limit() {
+    let scenario = TradingScenario::with_market_params(|params| {
+        params.max_orders_per_level = MAX_ORDERS_PER_LEVEL
+    })
+    .await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    for _ in 0..MAX_ORDERS_PER_LEVEL {
+        market
+            .place_limit_order(alice, Side::Bid, 1_000_000, 1)
+            .await
+            .expect("Orders up to the limit rest");
+    }
+    assert_clob_error(
+        market
+            .place_limit_order(charlie, Side::Bid, 1_000_000, 1)
+            .await,
+        ErrorCode::PriceLevelFull,
+    );
+    // increase_order adds no order, so a full level does not stop it
+    market.increase_order(alice, 1, Side::Bid, 1).await.unwrap();
+
+    // Other prices are unaffected
+    market
+        .place_limit_order(charlie, Side::Bid, 999_000, 1)
+        .await
+        .expect("Another level has room");
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 4);
+
+    // A cancel frees a place at the level
+    market.cancel_order(alice, 1, Side::Bid).await.unwrap();
+    market
+        .place_limit_order(charlie, Side::Bid, 1_000_000, 1)
+        .await
+        .expect("Level has room again");
+    assert_eq!(market.get_l2(Side::Bid)[0], (1_000_000, 3));
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_full_level_still_matches_against_takers() {
+    let scenario = TradingScenario::with_market_params(|params| {
+        params.max_orders_per_level = MAX_ORDERS_PER_LEVEL
+    })
+    .await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    for _ in 0..MAX_ORDERS_PER_LEVEL {
+        market
+            .place_limit_order(alice, Side::Ask, 1_000_000, 1)
+            .await
+            .unwrap();
+    }
+
+    // Only resting is limited: a bid taking the whole level goes through
+    market
+        .place_limit_order(bob, Side::Bid, 1_000_000, 3)
+        .await
+        .expect("Taker fills against a full level");
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+    market.consume_events(10, &[alice]).await.unwrap();
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::Pubkey;
+use clob::instructions::{PriceLevelsPage, MAX_PRICE_LEVELS_PER_PAGE};
+use clob::state::{AskSide, Order, OrderBook, Side};
+
+use crate::svm::TradingScenario;
+
+#[test]
+fn test_distinct_price_levels_groups_and_sorts() {
+    let mut side = AskSide::default();
+    for (order_id, price) in [(1, 1_030), (2, 1_000), (3, 1_030), (4, 1_010)] {
+        side.orderbook
+            .insert_order(Order {
+                order_id,
+                owner: Pubkey::new_unique(),
+                price,
+                quantity: 1,
+                remaining_quantity: 1,
+                timestamp: order_id as i64,
+                ..Default::default()
+            })
+            .unwrap();
+    }
+    assert_eq!(
+        side.orderbook.distinct_price_levels(),
+        vec![1_000, 1_010, 1_030]
+    );
+}
+
+#[tokio::test]
+async fn test_query_price_levels_lists_each_price_once() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    for (user, price) in [
+        (alice, 1_200_000),
+        (bob, 1_000_000),
+        (alice, 1_100_000),
+        (bob, 1_200_000),
+        (alice, 1_300_000),
+        (bob, 1_000_000),
+    ] {
+        market
+            .place_limit_order(user, Side::Ask, price, 1)
+            .await
+            .unwrap();
+    }
+
+    let outcome = market.query_price_levels(Side::Ask, 0).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let page: PriceLevelsPage = outcome.return_value();
+    assert_eq!(
+        page.prices,
+        vec![1_000_000, 1_100_000, 1_200_000, 1_300_000]
+    );
+    assert!(!page.has_more);
+
+    let page: PriceLevelsPage = market
+        .query_price_levels(Side::Ask, 1_100_000)
+        .await
+        .return_value();
+    assert_eq!(page.prices, vec![1_200_000, 1_300_000]);
+
+    let page: PriceLevelsPage = market.query_price_levels(Side::Bid, 0).await.return_value();
+    assert!(page.prices.is_empty());
+}
+
+#[tokio::test]
+async fn test_query_price_levels_pages_past_the_return_data_bound() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    let count = MAX_PRICE_LEVELS_PER_PAGE as u64 + 1;
+    for price in 1_000..1_000 + count {
+        market
+            .place_limit_order(alice, Side::Bid, price, 1)
+            .await
+            .unwrap();
+    }
+
+    let first: PriceLevelsPage = market.query_price_levels(Side::Bid, 0).await.return_value();
+    assert_eq!(first.prices.len(), MAX_PRICE_LEVELS_PER_PAGE);
+    assert!(first.has_more);
+    let last_price = *first.prices.last().unwrap();
+    let second: PriceLevelsPage = market
+        .query_price_levels(Side::Bid, last_price)
+        .await
+        .return_value();
+    assert_eq!(second.prices, vec![1_000 + count - 1]);
+    assert!(!second.has_more);
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::Pubkey;
+use clob::instructions::QuoteOrderResult;
+use clob::state::{Side, TimeInForce};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{conservation::token_ledgers, TradingScenario};
+
+#[tokio::test]
+async fn test_parity_market_settles_exactly() {
+    // 6-decimal base and quote with one whole token per lot and per tick, so
+    // price 1 is exact 1:1 parity
+    let scenario = TradingScenario::with_market_params(|params| {
+        params.base_lot_size = 1_000_000;
+        params.quote_tick_size = 1_000_000;
+    })
+    .await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    market
+        .place_limit_order(alice, Side::Ask, 1, 7)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 1, 7)
+        .await
+        .unwrap();
+    market.consume_events(10, &[alice]).await.unwrap();
+
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(
+        alice_before.base_balance - alice_after.base_balance,
+        7_000_000
+    );
+    assert_eq!(
+        alice_after.quote_balance - alice_before.quote_balance,
+        7_000_000
+    );
+    assert_eq!(bob_after.base_balance - bob_before.base_balance, 7_000_000);
+    assert_eq!(
+        bob_before.quote_balance - bob_after.quote_balance,
+        7_000_000
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_large_notional_does_not_overflow_intermediate() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    // price * quantity * quote_tick_size = 2e19 exceeds u64, the result 2e13 does not
+    let price = 400_000_000_000_000;
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, price, 50)
+        .await
+        .unwrap();
+
+    let quote: QuoteOrderResult = market
+        .quote_order(Side::Bid, price, 50, None)
+        .await
+        .return_value();
+    assert_eq!(quote.fillable_base, 50);
+    assert_eq!(quote.quote_required, 20_000_000_000_000);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_rounding_fills_leave_no_dust() {
+    // 1.001 quote units per lot, so every quote amount rounds down
+    const PRICE: u64 = 1_001;
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let quote = |user: &Pubkey| market.get_user_balance(user).quote_balance;
+    let (alice_before, bob_before) = (quote(&alice.pubkey()), quote(&bob.pubkey()));
+
+    // Bob's bid reserves floor(7.007) quote; one-lot fills each round too
+    market
+        .place_limit_order(bob, Side::Bid, PRICE, 7)
+        .await
+        .unwrap();
+    for _ in 0..3 {
+        market
+            .place_limit_order_with_tif(alice, Side::Ask, PRICE, 1, TimeInForce::IOC)
+            .await
+            .unwrap();
+        scenario.assert_conservation();
+    }
+    market.consume_events(10, &[bob]).await.unwrap();
+    market.cancel_order(bob, 1, Side::Bid).await.unwrap();
+
+    // Every quote token Bob gave up went to Alice; none was left behind
+    // for the market to sweep
+    let bob_paid = bob_before - quote(&bob.pubkey());
+    assert_eq!(quote(&alice.pubkey()) - alice_before, bob_paid);
+    assert!(bob_paid > 0);
+    let users = [alice.pubkey(), bob.pubkey(), scenario.charlie.pubkey()];
+    let (base, quote_ledger) = token_ledgers(market, &users);
+    assert_eq!(base.vault, base.owed());
+    assert_eq!(quote_ledger.vault, quote_ledger.owed());
+    assert_eq!((quote_ledger.reserved, quote_ledger.unsettled), (0, 0));
+}
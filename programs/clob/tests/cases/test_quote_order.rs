@@ -0,0 +1,98 @@
+use clob::events::OrderFilled;
+use clob::instructions::QuoteOrderResult;
+use clob::state::{Side, TimeInForce};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{parse_events, TradingScenario};
+
+/// 1:1 lot/tick sizing so one lot at price p costs exactly p quote units
+async fn quote_scenario() -> TradingScenario {
+    TradingScenario::with_market_params(|params| params.base_lot_size = 1_000).await
+}
+
+async fn seed_asks(scenario: &TradingScenario) {
+    let market = &scenario.market;
+    // Three ask levels: 2@10 + 3@10, 4@11, 5@13
+    for (user, price, quantity) in [
+        (&scenario.alice, 10, 2),
+        (&scenario.bob, 10, 3),
+        (&scenario.alice, 11, 4),
+        (&scenario.bob, 13, 5),
+    ] {
+        market
+            .place_limit_order(&user.keypair, Side::Ask, price, quantity)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_quote_matches_realized_fills() {
+    let scenario = quote_scenario().await;
+    let market = &scenario.market;
+    let charlie = &scenario.charlie.keypair;
+    seed_asks(&scenario).await;
+
+    let hash_before = market.get_asks_orderbook().orderbook.book_hash();
+    let quote: QuoteOrderResult = market
+        .quote_order(Side::Bid, 12, 8, None)
+        .await
+        .return_value();
+    assert_eq!(
+        market.get_asks_orderbook().orderbook.book_hash(),
+        hash_before,
+        "Quoting leaves the book untouched"
+    );
+
+    assert_eq!(quote.fillable_base, 8);
+    assert_eq!(quote.worst_price, 11);
+    assert_eq!(quote.levels_touched, 2);
+    assert_eq!(quote.quote_required, 2 * 10 + 3 * 10 + 3 * 11);
+    assert_eq!(quote.avg_price, (2 * 10 + 3 * 10 + 3 * 11) / 8);
+
+    // The identical real order in the next transaction fills exactly as quoted
+    let quote_before = market.get_user_balance(&charlie.pubkey()).quote_balance;
+    let meta = market
+        .place_limit_order_with_tif(charlie, Side::Bid, 12, 8, TimeInForce::IOC)
+        .await
+        .unwrap();
+    let quote_after = market.get_user_balance(&charlie.pubkey()).quote_balance;
+
+    let fills = parse_events::<OrderFilled>(&meta.logs);
+    assert_eq!(
+        fills.iter().map(|f| f.quantity).sum::<u64>(),
+        quote.fillable_base
+    );
+    assert_eq!(fills.last().unwrap().price, quote.worst_price);
+    assert_eq!(quote_before - quote_after, quote.quote_required);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_quote_with_budget_and_empty_book() {
+    let scenario = quote_scenario().await;
+    let market = &scenario.market;
+
+    let quote: QuoteOrderResult = market
+        .quote_order(Side::Bid, 12, 8, None)
+        .await
+        .return_value();
+    assert_eq!(quote, QuoteOrderResult::default(), "Nothing to fill");
+
+    seed_asks(&scenario).await;
+
+    // A budget of 25 affords two lots at price 10 but nothing deeper
+    let quote: QuoteOrderResult = market
+        .quote_order(Side::Bid, u64::MAX, 100, Some(25))
+        .await
+        .return_value();
+    assert_eq!(quote.fillable_base, 2);
+    assert_eq!(quote.quote_required, 20);
+    assert_eq!(quote.levels_touched, 1);
+
+    // Budgets only make sense for buys
+    assert!(!market.quote_order(Side::Ask, 1, 1, Some(1)).await.success);
+
+    scenario.assert_conservation();
+}
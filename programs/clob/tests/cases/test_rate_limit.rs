@@ -0,0 +1,68 @@
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_orders_per_slot_cap() {
+    let scenario =
+        TradingScenario::with_market_params(|params| params.max_orders_per_slot_per_user = 3).await;
+    let market = &scenario.market;
+    let ctx = &scenario.fixture.ctx;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    // Three placements fit in one slot, the fourth is refused
+    for price in 1..=3 {
+        market
+            .place_limit_order(alice, Side::Bid, price, 1)
+            .await
+            .expect("Placement under the cap");
+    }
+    let outcome = market
+        .place_limit_order_meta(alice, Side::Bid, 4, 1, clob::state::TimeInForce::GTC)
+        .await;
+    assert!(!outcome.success);
+    assert!(outcome.logs_contain("RateLimited"));
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 3);
+
+    // The cap is per user
+    market
+        .place_limit_order(bob, Side::Ask, 100, 1)
+        .await
+        .expect("Other users are unaffected");
+
+    // A new slot resets the count
+    ctx.borrow_mut().advance_slot();
+    market
+        .place_limit_order(alice, Side::Bid, 4, 1)
+        .await
+        .expect("Placement in the next slot");
+
+    let balance = market.get_user_balance(&alice.pubkey());
+    assert_eq!(balance.last_order_slot, ctx.borrow().clock().slot);
+    assert_eq!(balance.orders_this_slot, 1);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_zero_cap_is_unlimited() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+
+    assert_eq!(market.get_market().max_orders_per_slot_per_user, 0);
+    for price in 1..=10 {
+        market
+            .place_limit_order(alice, Side::Bid, price, 1)
+            .await
+            .expect("No cap configured");
+    }
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).orders_this_slot,
+        10
+    );
+
+    scenario.assert_conservation();
+}
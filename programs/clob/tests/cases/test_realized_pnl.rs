@@ -0,0 +1,148 @@
+use anchor_lang::prelude::Pubkey;
+use clob::state::{Side, UserBalance};
+use solana_sdk::signature::Keypair;
+
+use crate::svm::{market::MarketFixture, TradingScenario};
+
+fn pnl_state(market: &MarketFixture, user: &Pubkey) -> (i64, i64, i64) {
+    let balance = market.get_user_balance(user);
+    (
+        balance.position_base_net,
+        balance.position_cost_quote,
+        balance.realized_pnl_quote,
+    )
+}
+
+/// `maker` rests `quantity` on `maker_side` at `price`, `taker` takes it
+/// and the crank settles the maker
+async fn trade(
+    market: &MarketFixture,
+    maker: &Keypair,
+    maker_side: Side,
+    taker: &Keypair,
+    price: u64,
+    quantity: u64,
+) {
+    let taker_side = match maker_side {
+        Side::Bid => Side::Ask,
+        Side::Ask => Side::Bid,
+    };
+    market
+        .place_limit_order(maker, maker_side, price, quantity)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(taker, taker_side, price, quantity)
+        .await
+        .unwrap();
+    market.consume_events(10, &[maker]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_realized_pnl_for_takers_and_makers() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+    let charlie = &scenario.charlie;
+    let bob_quote_before = market.get_user_balance(&bob.pubkey()).quote_balance;
+
+    // Prices are 1_000 quote per lot per million, so 5 is 5_000_000 and
+    // 10 lots (10_000_000 base) at 5 cost 50_000 quote
+    let lots = 10;
+    let base = 10_000_000;
+
+    // Bob buys 10 at 5 from Alice, then sells 10 at 7 to Charlie
+    trade(
+        market,
+        &alice.keypair,
+        Side::Ask,
+        &bob.keypair,
+        5_000_000,
+        lots,
+    )
+    .await;
+    assert_eq!(pnl_state(market, &bob.pubkey()), (base, 50_000, 0));
+    assert_eq!(pnl_state(market, &alice.pubkey()), (-base, -50_000, 0));
+
+    trade(
+        market,
+        &charlie.keypair,
+        Side::Bid,
+        &bob.keypair,
+        7_000_000,
+        lots,
+    )
+    .await;
+    assert_eq!(pnl_state(market, &bob.pubkey()), (0, 0, 20_000));
+    assert_eq!(pnl_state(market, &charlie.pubkey()), (base, 70_000, 0));
+
+    // Flat again, Bob's realized PnL is exactly his quote gain
+    assert_eq!(
+        market.get_user_balance(&bob.pubkey()).quote_balance,
+        bob_quote_before + 20_000
+    );
+
+    // Makers realize at settlement: Alice covers her short at 3 as a maker,
+    // Charlie dumps his long at 3 as the taker
+    trade(
+        market,
+        &alice.keypair,
+        Side::Bid,
+        &charlie.keypair,
+        3_000_000,
+        lots,
+    )
+    .await;
+    assert_eq!(pnl_state(market, &alice.pubkey()), (0, 0, 20_000));
+    assert_eq!(pnl_state(market, &charlie.pubkey()), (0, 0, -40_000));
+
+    scenario.assert_conservation();
+}
+
+#[test]
+fn test_average_cost_partial_close_and_flip() {
+    let mut balance = UserBalance {
+        owner: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        base_balance: 0,
+        quote_balance: 0,
+        bump: 0,
+        last_order_slot: 0,
+        orders_this_slot: 0,
+        next_intent_nonce: 0,
+        position_base_net: 0,
+        position_cost_quote: 0,
+        realized_pnl_quote: 0,
+        open_order_count: 0,
+        schema_version: 0,
+        session_expiry: 0,
+    };
+
+    // Two buys average the entry: 10 at 5 and 10 at 7 is 20 at 6
+    balance.record_fill(Side::Bid, 10, 50);
+    balance.record_fill(Side::Bid, 10, 70);
+    assert_eq!(balance.position_base_net, 20);
+    assert_eq!(balance.position_cost_quote, 120);
+
+    // Selling 5 at 8 realizes (8 - 6) * 5 and keeps the average
+    balance.record_fill(Side::Ask, 5, 40);
+    assert_eq!(balance.realized_pnl_quote, 10);
+    assert_eq!(balance.position_base_net, 15);
+    assert_eq!(balance.position_cost_quote, 90);
+
+    // Selling 20 at 4 closes 15 at a loss of 2 each and opens a 5 short at 4
+    balance.record_fill(Side::Ask, 20, 80);
+    assert_eq!(balance.realized_pnl_quote, 10 - 30);
+    assert_eq!(balance.position_base_net, -5);
+    assert_eq!(balance.position_cost_quote, -20);
+
+    // Covering the short at 3 makes 1 each
+    balance.record_fill(Side::Bid, 5, 15);
+    assert_eq!(balance.realized_pnl_quote, -20 + 5);
+    assert_eq!(balance.position_base_net, 0);
+    assert_eq!(balance.position_cost_quote, 0);
+
+    // Never touches the balances themselves
+    assert_eq!((balance.base_balance, balance.quote_balance), (0, 0));
+}
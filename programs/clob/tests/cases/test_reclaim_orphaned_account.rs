@@ -0,0 +1,78 @@
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction::create_account;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_reclaim_orphaned_book_account() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let ctx = &scenario.fixture.ctx;
+    let recipient = Keypair::new().pubkey();
+
+    // The first half of a market creation that never got its Initialize
+    let orphan = Keypair::new();
+    let size = 8 + std::mem::size_of::<clob::state::BidSide>();
+    let rent = ctx.borrow().minimum_balance_for_rent_exemption(size);
+    {
+        let mut ctx = ctx.borrow_mut();
+        let ix = create_account(
+            &ctx.payer.pubkey(),
+            &orphan.pubkey(),
+            rent,
+            size as u64,
+            &clob::ID,
+        );
+        ctx.submit_transaction(&[ix], &[&orphan])
+            .expect("Failed to create orphaned account");
+    }
+
+    // Even with a market supplied, an unrelated orphan is fine to close
+    market
+        .reclaim_orphaned_account(&orphan, recipient, true)
+        .await
+        .expect("Orphaned account should be reclaimable");
+
+    assert_eq!(ctx.borrow().lamports(&recipient), rent);
+    assert_eq!(ctx.borrow().lamports(&orphan.pubkey()), 0);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_reclaim_rejects_initialized_accounts() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let ctx = &scenario.fixture.ctx;
+    let recipient = Keypair::new().pubkey();
+    let bids_rent = ctx.borrow().lamports(&market.bids);
+
+    // A live market's bids account carries a discriminator
+    assert!(market
+        .reclaim_orphaned_account(&market.bids_keypair, recipient, false)
+        .await
+        .is_err());
+    assert!(market
+        .reclaim_orphaned_account(&market.bids_keypair, recipient, true)
+        .await
+        .is_err());
+
+    // Accounts of the wrong size are not book or queue accounts
+    let stray = Keypair::new();
+    {
+        let mut ctx = ctx.borrow_mut();
+        let rent = ctx.minimum_balance_for_rent_exemption(64);
+        let ix = create_account(&ctx.payer.pubkey(), &stray.pubkey(), rent, 64, &clob::ID);
+        ctx.submit_transaction(&[ix], &[&stray])
+            .expect("Failed to create stray account");
+    }
+    assert!(market
+        .reclaim_orphaned_account(&stray, recipient, false)
+        .await
+        .is_err());
+
+    assert_eq!(ctx.borrow().lamports(&market.bids), bids_rent);
+    assert_eq!(market.get_orderbook_order_count(clob::state::Side::Bid), 0);
+
+    scenario.assert_conservation();
+}
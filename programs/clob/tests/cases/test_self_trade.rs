@@ -0,0 +1,207 @@
+use anchor_lang::prelude::Pubkey;
+use clob::errors::ErrorCode;
+use clob::events::OrderCancelled;
+use clob::instructions::PlaceLimitOrderResult;
+use clob::state::{AskOrderBook, FillStatus, OrderBook, OrderStatus, SelfTradeBehavior, Side};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, order, TradingScenario};
+
+/// An ask book with `owner`'s 3 lots at 100 ahead of someone else's 2 at 101
+fn book(owner: Pubkey) -> AskOrderBook {
+    let mut book = AskOrderBook::new();
+    book.insert_order(order(1, 100, 3).owner(owner).build())
+        .unwrap();
+    book.insert_order(order(2, 101, 2).build()).unwrap();
+    book
+}
+
+#[test]
+fn test_book_applies_each_self_trade_behavior() {
+    let owner = Pubkey::new_unique();
+
+    // DecrementTake leaves the own ask and gives up the 3 lots it covered
+    let mut asks = book(owner);
+    let mut taker = order(3, 101, 4).owner(owner).build();
+    let mut evicted = Vec::new();
+    let fills = asks
+        .match_orders_evicting(
+            &mut taker,
+            &mut evicted,
+            Some(SelfTradeBehavior::DecrementTake),
+        )
+        .unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!((fills[0].maker_order_id, fills[0].quantity), (2, 1));
+    assert_eq!(taker.remaining_quantity, 0);
+    assert!(evicted.is_empty());
+    assert_eq!(asks.peek().unwrap().remaining_quantity, 3);
+    assert_eq!(asks.len(), 2);
+
+    // CancelProvide evicts the own ask and keeps matching behind it
+    let mut asks = book(owner);
+    let mut taker = order(3, 101, 4).owner(owner).build();
+    let mut evicted = Vec::new();
+    let fills = asks
+        .match_orders_evicting(
+            &mut taker,
+            &mut evicted,
+            Some(SelfTradeBehavior::CancelProvide),
+        )
+        .unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!((fills[0].maker_order_id, fills[0].quantity), (2, 2));
+    assert_eq!(taker.remaining_quantity, 2);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(evicted[0].order_id, 1);
+    assert!(asks.is_empty());
+
+    let mut asks = book(owner);
+    let mut taker = order(3, 101, 4).owner(owner).build();
+    let err = asks
+        .match_orders_evicting(
+            &mut taker,
+            &mut Vec::new(),
+            Some(SelfTradeBehavior::AbortTransaction),
+        )
+        .unwrap_err();
+    assert_eq!(err, ErrorCode::SelfTradeNotAllowed.into());
+
+    // Without a policy the taker fills against itself
+    let mut asks = book(owner);
+    let mut taker = order(3, 101, 4).owner(owner).build();
+    let fills = asks.match_orders(&mut taker).unwrap();
+    assert_eq!(fills[0].maker_owner, owner);
+    assert_eq!(taker.remaining_quantity, 0);
+}
+
+/// Alice's ask at 1.0 ahead of Bob's at 1.1; returns Alice's order id
+async fn seed_asks(scenario: &TradingScenario) -> u64 {
+    let market = &scenario.market;
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, 1_000_000, 3)
+        .await
+        .unwrap();
+    let own_order_id = market.get_market().next_order_id - 1;
+    market
+        .place_limit_order(&scenario.bob.keypair, Side::Ask, 1_100_000, 2)
+        .await
+        .unwrap();
+    own_order_id
+}
+
+#[tokio::test]
+async fn test_decrement_take_skips_own_order() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    seed_asks(&scenario).await;
+    let quote_before = market.get_user_balance(&alice.pubkey()).quote_balance;
+
+    let outcome = market
+        .place_limit_order_self_trade(
+            alice,
+            Side::Bid,
+            1_100_000,
+            4,
+            SelfTradeBehavior::DecrementTake,
+        )
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    // Her own 3 lots are given up, the last lot fills against Bob
+    assert_eq!((result.filled_quantity, result.resting_quantity), (1, 0));
+    assert_eq!(result.status, OrderStatus::Cancelled);
+    assert_eq!(result.fill_status, FillStatus::PartiallyFilledCancelled);
+    assert_eq!(
+        market.get_l2(Side::Ask),
+        vec![(1_000_000, 3), (1_100_000, 1)]
+    );
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+    assert_eq!(
+        quote_before - market.get_user_balance(&alice.pubkey()).quote_balance,
+        1_100
+    );
+
+    market.consume_events(10, &[bob]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_cancel_provide_refunds_own_order() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let own_order_id = seed_asks(&scenario).await;
+    let base_before = market.get_user_balance(&alice.pubkey()).base_balance;
+
+    let outcome = market
+        .place_limit_order_self_trade(
+            alice,
+            Side::Bid,
+            1_100_000,
+            4,
+            SelfTradeBehavior::CancelProvide,
+        )
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    assert_eq!((result.filled_quantity, result.resting_quantity), (2, 2));
+    assert_eq!(result.status, OrderStatus::PartiallyFilled);
+    let cancelled = outcome.events::<OrderCancelled>();
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].order_id, own_order_id);
+    assert_eq!(cancelled[0].remaining_quantity, 3);
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+    assert_eq!(market.get_l2(Side::Bid), vec![(1_100_000, 2)]);
+
+    // The cancelled ask's 3 base come back through the crank, on top of
+    // the 2 bought from Bob
+    market.consume_events(10, &[alice, bob]).await.unwrap();
+    assert_eq!(
+        market.get_user_balance(&alice.pubkey()).base_balance - base_before,
+        5_000_000
+    );
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_abort_transaction_rejects_self_trade() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    seed_asks(&scenario).await;
+    let balance_before = market.get_user_balance(&alice.pubkey());
+
+    let outcome = market
+        .place_limit_order_self_trade(
+            alice,
+            Side::Bid,
+            1_100_000,
+            4,
+            SelfTradeBehavior::AbortTransaction,
+        )
+        .await;
+    assert_clob_error(outcome, ErrorCode::SelfTradeNotAllowed);
+    assert_eq!(
+        market.get_l2(Side::Ask),
+        vec![(1_000_000, 3), (1_100_000, 2)]
+    );
+    let balance_after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(balance_after.quote_balance, balance_before.quote_balance);
+    assert_eq!(market.event_queue_len(), 0);
+
+    // Placing against someone else's order alone is unaffected
+    let outcome = market
+        .place_limit_order_self_trade(
+            &scenario.charlie.keypair,
+            Side::Bid,
+            1_000_000,
+            1,
+            SelfTradeBehavior::AbortTransaction,
+        )
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+}
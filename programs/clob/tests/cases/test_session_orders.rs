@@ -0,0 +1,100 @@
+use clob::errors::ErrorCode;
+use clob::state::Side;
+use clob::OrderCancelled;
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+const BOND: u64 = 5_000_000;
+const SESSION_SECS: i64 = 60;
+
+#[tokio::test]
+async fn test_lapsed_session_orders_are_swept_by_anyone() {
+    let scenario =
+        TradingScenario::with_market_params(|params| params.order_bond_lamports = BOND).await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let keeper = &scenario.charlie.keypair;
+    let ctx = &scenario.fixture.ctx;
+    let start = ctx.borrow().clock().unix_timestamp;
+    let before = market.get_user_balance(&alice.pubkey());
+
+    // No session yet
+    assert_clob_error(
+        market
+            .place_session_order(alice, Side::Bid, 900_000, 2)
+            .await,
+        ErrorCode::SessionNotActive,
+    );
+
+    market
+        .register_session(alice, start + SESSION_SECS)
+        .await
+        .unwrap();
+    market
+        .place_session_order(alice, Side::Bid, 900_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_session_order(alice, Side::Ask, 1_100_000, 3)
+        .await
+        .unwrap();
+    // A plain order is left alone by the sweep
+    market
+        .place_limit_order(alice, Side::Bid, 950_000, 1)
+        .await
+        .unwrap();
+
+    // The bot keeps refreshing, so the orders stay protected past the
+    // first expiry
+    ctx.borrow_mut().set_clock(start + SESSION_SECS - 10);
+    market
+        .register_session(alice, start + 2 * SESSION_SECS)
+        .await
+        .unwrap();
+    ctx.borrow_mut().set_clock(start + SESSION_SECS + 10);
+    assert_clob_error(
+        market
+            .cancel_expired_session_orders(keeper, &alice.pubkey(), 10)
+            .await,
+        ErrorCode::SessionNotExpired,
+    );
+    assert_eq!(market.get_open_order_count(&alice.pubkey()), 3);
+
+    // Then it stops, and the keeper clears both session orders for their bonds
+    ctx.borrow_mut().set_clock(start + 2 * SESSION_SECS);
+    let keeper_lamports = ctx.borrow().lamports(&keeper.pubkey());
+    for expected_remaining in [1u32, 0] {
+        let outcome = market
+            .cancel_expired_session_orders(keeper, &alice.pubkey(), 1)
+            .await;
+        assert!(outcome.success, "{:?}", outcome.logs);
+        assert_eq!(outcome.return_value::<u32>(), expected_remaining);
+        assert_eq!(outcome.events::<OrderCancelled>().len(), 1);
+    }
+    assert_eq!(
+        ctx.borrow().lamports(&keeper.pubkey()) - keeper_lamports,
+        2 * BOND
+    );
+
+    // Reservations came back; only the plain bid still rests
+    let after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(after.base_balance, before.base_balance);
+    assert_eq!(
+        before.quote_balance - after.quote_balance,
+        market.get_market().quote_amount(950_000, 1).unwrap()
+    );
+    assert_eq!(after.open_order_count, 1);
+    assert_eq!(market.get_l2(Side::Bid), vec![(950_000, 1)]);
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+
+    // Nor can session orders be placed until the session is renewed
+    assert_clob_error(
+        market
+            .place_session_order(alice, Side::Bid, 900_000, 2)
+            .await,
+        ErrorCode::SessionNotActive,
+    );
+
+    scenario.assert_conservation();
+}
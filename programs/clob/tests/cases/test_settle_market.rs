@@ -0,0 +1,50 @@
+use clob::errors::ErrorCode;
+use clob::events::SettlementTick;
+use clob::state::Side;
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_settle_market_marks_at_last_trade() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let start = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+
+    // Before any trade the mark is 0
+    let meta = market.settle_market(charlie).await.unwrap();
+    let tick = &parse_events::<SettlementTick>(&meta.logs)[0];
+    assert_eq!((tick.mark_price, tick.previous_settlement_ts), (0, 0));
+    assert_eq!(tick.settled_by, charlie.pubkey());
+    assert_eq!(market.get_market().last_settlement_ts, start);
+
+    // Once per timestamp
+    assert_clob_error(
+        market.settle_market(charlie).await,
+        ErrorCode::SettlementNotDue,
+    );
+
+    market
+        .place_limit_order(alice, Side::Ask, 1_100_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 1_100_000, 1)
+        .await
+        .unwrap();
+    assert_eq!(market.get_market().last_trade_price, 1_100_000);
+
+    scenario.fixture.ctx.borrow_mut().set_clock(start + 60);
+    let meta = market.settle_market(bob).await.unwrap();
+    let tick = &parse_events::<SettlementTick>(&meta.logs)[0];
+    assert_eq!(tick.market, market.market);
+    assert_eq!(tick.mark_price, 1_100_000);
+    assert_eq!(
+        (tick.previous_settlement_ts, tick.timestamp),
+        (start, start + 60)
+    );
+    assert_eq!(market.get_market().last_settlement_ts, start + 60);
+}
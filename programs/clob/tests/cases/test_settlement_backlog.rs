@@ -0,0 +1,62 @@
+use clob::errors::ErrorCode;
+use clob::state::Side;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+const MAX_EVENT_AGE_SECS: u32 = 60;
+
+#[tokio::test]
+async fn test_stalled_crank_blocks_matching_until_consumed() {
+    let scenario = TradingScenario::with_market_params(|params| {
+        params.max_event_age_secs = MAX_EVENT_AGE_SECS
+    })
+    .await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let ctx = &scenario.fixture.ctx;
+    let start = ctx.borrow().clock().unix_timestamp;
+
+    market
+        .place_limit_order(alice, Side::Ask, 10_000_000, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 10_000_000, 1)
+        .await
+        .unwrap();
+
+    // Within the window the unsettled fill does not get in the way
+    ctx.borrow_mut()
+        .set_clock(start + MAX_EVENT_AGE_SECS as i64);
+    market
+        .place_limit_order(bob, Side::Bid, 10_000_000, 1)
+        .await
+        .unwrap();
+
+    // The crank stalls past it: nothing may match
+    ctx.borrow_mut()
+        .set_clock(start + MAX_EVENT_AGE_SECS as i64 + 1);
+    assert_clob_error(
+        market
+            .place_limit_order(bob, Side::Bid, 10_000_000, 1)
+            .await,
+        ErrorCode::SettlementBacklog,
+    );
+
+    // An order that does not cross still rests
+    market
+        .place_limit_order(bob, Side::Bid, 9_000_000, 1)
+        .await
+        .unwrap();
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 1);
+
+    market.consume_events(10, &[alice]).await.unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 10_000_000, 1)
+        .await
+        .unwrap();
+    assert_eq!(market.get_orderbook_order_count(Side::Ask), 0);
+
+    scenario.assert_conservation();
+}
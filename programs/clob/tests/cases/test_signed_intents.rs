@@ -0,0 +1,147 @@
+use clob::errors::ErrorCode;
+use clob::events::SignedIntentExecuted;
+use clob::instructions::PlaceLimitOrderResult;
+use clob::state::{CancelIntent, OrderIntent, Side, TimeInForce};
+use solana_sdk::signer::Signer;
+
+use crate::svm::market::ed25519_signature_ix;
+use crate::svm::{assert_clob_error, TradingScenario};
+
+fn order_intent(scenario: &TradingScenario, nonce: u64) -> OrderIntent {
+    let now = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+    OrderIntent {
+        market: scenario.market.market,
+        side: Side::Bid,
+        price: 2_000,
+        quantity: 5,
+        time_in_force: TimeInForce::GTC,
+        client_order_id: 42,
+        expiry: now + 60,
+        nonce,
+    }
+}
+
+#[tokio::test]
+async fn test_relayer_places_and_cancels_for_signer() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let relayer = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+
+    let before = market.get_user_balance(&alice.pubkey());
+    let outcome = market
+        .place_signed_order(&relayer, &alice.keypair, order_intent(&scenario, 0))
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    let result: PlaceLimitOrderResult = outcome.return_value();
+    let order = market.find_order_in_bids(result.order_id).unwrap();
+    assert_eq!(order.owner, alice.pubkey());
+    let executed = &outcome.events::<SignedIntentExecuted>()[0];
+    assert_eq!(executed.client_order_id, 42);
+    assert_eq!(executed.relayer, relayer.pubkey());
+
+    // 5 lots at 2000 ticks reserve 10 quote from Alice's balance
+    let placed = market.get_user_balance(&alice.pubkey());
+    assert_eq!(placed.quote_balance, before.quote_balance - 10);
+    assert_eq!(placed.next_intent_nonce, 1);
+
+    let now = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+    let cancel = CancelIntent {
+        market: market.market,
+        order_id: result.order_id,
+        side: Side::Bid,
+        expiry: now + 60,
+        nonce: 1,
+    };
+    let outcome = market
+        .cancel_signed_order(&relayer, &alice.keypair, cancel)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+    assert!(market.find_order_in_bids(result.order_id).is_none());
+    let after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(after.quote_balance, before.quote_balance);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_replayed_nonce_is_rejected() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let relayer = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+
+    let outcome = market
+        .place_signed_order(&relayer, alice, order_intent(&scenario, 7))
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    // The same intent again, and an older nonce after skipping ahead
+    for nonce in [7, 3] {
+        assert_clob_error(
+            market
+                .place_signed_order(&relayer, alice, order_intent(&scenario, nonce))
+                .await,
+            ErrorCode::IntentNonceUsed,
+        );
+    }
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 1);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_expired_intent_is_rejected() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let relayer = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+
+    let intent = order_intent(&scenario, 0);
+    let expiry = intent.expiry;
+    scenario.fixture.ctx.borrow_mut().set_clock(expiry + 1);
+
+    assert_clob_error(
+        market
+            .place_signed_order(&relayer, &scenario.alice.keypair, intent)
+            .await,
+        ErrorCode::IntentExpired,
+    );
+    assert!(market.orderbooks_are_empty());
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_tampered_intent_is_rejected() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let relayer = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+
+    // Alice signed a bid at 2_000; the relayer submits it at 3_000
+    let signed = order_intent(&scenario, 0);
+    let signature_ix = ed25519_signature_ix(alice, &signed.message());
+    let tampered = OrderIntent {
+        price: 3_000,
+        ..signed.clone()
+    };
+    assert_clob_error(
+        market
+            .place_signed_order_with(&relayer, &alice.pubkey(), signature_ix, tampered)
+            .await,
+        ErrorCode::InvalidIntentSignature,
+    );
+
+    // A valid signature by someone else does not authorise Alice's balance
+    let signature_ix = ed25519_signature_ix(&relayer, &signed.message());
+    assert_clob_error(
+        market
+            .place_signed_order_with(&relayer, &alice.pubkey(), signature_ix, signed)
+            .await,
+        ErrorCode::InvalidIntentSignature,
+    );
+    assert!(market.orderbooks_are_empty());
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,196 @@
+use clob::errors::ErrorCode;
+use clob::events::{BalanceChanged, OrderFilled, BALANCE_REASON_CROSS};
+use clob::instructions::CrossTrade;
+use clob::state::Side;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+fn cross(buyer: &Keypair, seller: &Keypair, price: u64, quantity: u64) -> CrossTrade {
+    CrossTrade {
+        buyer: buyer.pubkey(),
+        seller: seller.pubkey(),
+        price,
+        quantity,
+    }
+}
+
+#[tokio::test]
+async fn test_cross_settles_both_sides_and_queues_a_cross_event() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let venue = Keypair::new();
+
+    market
+        .set_cross_authority(&venue.pubkey(), 0)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Ask, 12_000_000, 1)
+        .await
+        .unwrap();
+    let book_before = market.get_book_state();
+    let bob_before = market.get_user_balance(&bob.pubkey());
+    let charlie_before = market.get_user_balance(&charlie.pubkey());
+
+    // 2 lots at 10_000 quote each: 2_000_000 base for 20_000 quote
+    let meta = market
+        .submit_cross(
+            &venue,
+            vec![cross(bob, charlie, 10_000_000, 2)],
+            &[bob, charlie],
+        )
+        .await
+        .expect("Cross inside the book settles");
+
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    let charlie_after = market.get_user_balance(&charlie.pubkey());
+    assert_eq!(bob_after.base_balance, bob_before.base_balance + 2_000_000);
+    assert_eq!(bob_after.quote_balance, bob_before.quote_balance - 20_000);
+    assert_eq!(
+        charlie_after.base_balance,
+        charlie_before.base_balance - 2_000_000
+    );
+    assert_eq!(
+        charlie_after.quote_balance,
+        charlie_before.quote_balance + 20_000
+    );
+
+    let fills = parse_events::<OrderFilled>(&meta.logs);
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].maker_owner, charlie.pubkey());
+    assert_eq!(fills[0].taker_owner, bob.pubkey());
+    assert_eq!(fills[0].taker_side, Side::Bid);
+    assert_eq!(fills[0].gross_quote, 20_000);
+    let changes = parse_events::<BalanceChanged>(&meta.logs);
+    assert_eq!(changes.len(), 2);
+    assert!(changes
+        .iter()
+        .all(|change| change.reason == BALANCE_REASON_CROSS));
+
+    // The book is untouched; the queue carries the cross
+    let book_after = market.get_book_state();
+    assert_eq!(book_after.asks, book_before.asks);
+    assert_eq!(book_after.event_queue_len, 1);
+
+    // Already settled, so cranking needs no balance accounts and moves nothing
+    market.consume_events(10, &[]).await.unwrap();
+    assert_eq!(market.event_queue_len(), 0);
+    let bob_cranked = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_cranked.base_balance, bob_after.base_balance);
+    assert_eq!(bob_cranked.quote_balance, bob_after.quote_balance);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_cross_trading_through_the_book_is_rejected() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let venue = Keypair::new();
+
+    market
+        .set_cross_authority(&venue.pubkey(), 500)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Ask, 10_000, 1)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(alice, Side::Bid, 9_000, 1)
+        .await
+        .unwrap();
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    // The buyer would pay 600 ticks over the resting ask
+    let result = market
+        .submit_cross(
+            &venue,
+            vec![cross(bob, charlie, 10_600, 1)],
+            &[bob, charlie],
+        )
+        .await;
+    assert_clob_error(result, ErrorCode::CrossTradesThrough);
+
+    // The seller would accept 600 ticks under the resting bid
+    let result = market
+        .submit_cross(&venue, vec![cross(bob, charlie, 8_400, 1)], &[bob, charlie])
+        .await;
+    assert_clob_error(result, ErrorCode::CrossTradesThrough);
+
+    // One bad cross fails the whole batch
+    let result = market
+        .submit_cross(
+            &venue,
+            vec![
+                cross(bob, charlie, 9_500, 1),
+                cross(bob, charlie, 10_600, 1),
+            ],
+            &[bob, charlie],
+        )
+        .await;
+    assert_clob_error(result, ErrorCode::CrossTradesThrough);
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.base_balance, bob_before.base_balance);
+    assert_eq!(bob_after.quote_balance, bob_before.quote_balance);
+    assert_eq!(market.event_queue_len(), 0);
+
+    // Exactly at the bound on either side is allowed
+    market
+        .submit_cross(
+            &venue,
+            vec![
+                cross(bob, charlie, 10_500, 1),
+                cross(charlie, bob, 8_500, 1),
+            ],
+            &[bob, charlie],
+        )
+        .await
+        .expect("Crosses within the bound settle");
+    assert_eq!(market.event_queue_len(), 2);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_cross_requires_the_cross_authority() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let venue = Keypair::new();
+
+    // Disabled until the market authority names a cross authority
+    let result = market
+        .submit_cross(
+            &venue,
+            vec![cross(bob, charlie, 10_000, 1)],
+            &[bob, charlie],
+        )
+        .await;
+    assert_clob_error(result, ErrorCode::Unauthorized);
+
+    market
+        .set_cross_authority(&venue.pubkey(), 0)
+        .await
+        .unwrap();
+    let result = market
+        .submit_cross(bob, vec![cross(bob, charlie, 10_000, 1)], &[bob, charlie])
+        .await;
+    assert_clob_error(result, ErrorCode::Unauthorized);
+
+    // Both traders' balances must be passed
+    let result = market
+        .submit_cross(&venue, vec![cross(bob, charlie, 10_000, 1)], &[bob])
+        .await;
+    assert_clob_error(result, ErrorCode::CrossAccountMissing);
+
+    scenario.assert_conservation();
+}
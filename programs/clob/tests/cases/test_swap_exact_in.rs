@@ -0,0 +1,158 @@
+use clob::errors::ErrorCode;
+use clob::events::{OrderFilled, SwapExecuted};
+use clob::state::Side;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+#[tokio::test]
+async fn test_swap_quote_for_base_returns_unspent_input() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let base_mint = &scenario.fixture.base_mint;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let alice = &scenario.alice;
+    let charlie = &scenario.charlie;
+
+    // 5_000 and 6_000 quote tokens per lot
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 5_000_000, 2)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 6_000_000, 2)
+        .await
+        .unwrap();
+
+    let alice_before = market.get_user_balance(&alice.pubkey());
+    let charlie_before = market.get_user_balance(&charlie.pubkey());
+    let base_before = base_mint.balance(charlie.base_account).await;
+    let quote_before = quote_mint.balance(charlie.quote_account).await;
+
+    // Two lots at 5_000 and one at 6_000; the last 500 buys nothing
+    let outcome = market
+        .swap_exact_in(
+            &charlie.keypair,
+            charlie.base_account,
+            charlie.quote_account,
+            Side::Bid,
+            16_500,
+            3_000_000,
+        )
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    let swap = &outcome.events::<SwapExecuted>()[0];
+    assert_eq!(swap.amount_in, 16_500);
+    assert_eq!(swap.amount_spent, 16_000);
+    assert_eq!(swap.amount_out, 3_000_000);
+    assert_eq!(swap.filled_quantity, 3);
+    assert_eq!(outcome.events::<OrderFilled>().len(), 2);
+
+    assert_eq!(
+        base_mint.balance(charlie.base_account).await,
+        base_before + 3_000_000
+    );
+    assert_eq!(
+        quote_mint.balance(charlie.quote_account).await,
+        quote_before - 16_000
+    );
+
+    // No UserBalance footprint for the swapper
+    let charlie_after = market.get_user_balance(&charlie.pubkey());
+    assert_eq!(charlie_after.base_balance, charlie_before.base_balance);
+    assert_eq!(charlie_after.quote_balance, charlie_before.quote_balance);
+
+    // The maker is paid through the crank as usual
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+    let alice_after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(
+        alice_after.quote_balance,
+        alice_before.quote_balance + 16_000
+    );
+    assert_eq!(market.find_order_in_asks(2).unwrap().remaining_quantity, 1);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_swap_base_for_quote_returns_partial_lot() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let base_mint = &scenario.fixture.base_mint;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let bob = &scenario.bob;
+    let charlie = &scenario.charlie;
+
+    // 4_000 quote tokens per lot
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 4_000_000, 3)
+        .await
+        .unwrap();
+
+    let base_before = base_mint.balance(charlie.base_account).await;
+    let quote_before = quote_mint.balance(charlie.quote_account).await;
+
+    let outcome = market
+        .swap_exact_in(
+            &charlie.keypair,
+            charlie.base_account,
+            charlie.quote_account,
+            Side::Ask,
+            2_500_000,
+            8_000,
+        )
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+
+    // Two whole lots sold, the half lot handed back
+    assert_eq!(
+        base_mint.balance(charlie.base_account).await,
+        base_before - 2_000_000
+    );
+    assert_eq!(
+        quote_mint.balance(charlie.quote_account).await,
+        quote_before + 8_000
+    );
+    assert_eq!(market.find_order_in_bids(1).unwrap().remaining_quantity, 1);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_swap_below_min_amount_out_fails() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let alice = &scenario.alice;
+    let charlie = &scenario.charlie;
+
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 5_000_000, 1)
+        .await
+        .unwrap();
+
+    let quote_before = quote_mint.balance(charlie.quote_account).await;
+
+    // Only one lot is on offer
+    assert_clob_error(
+        market
+            .swap_exact_in(
+                &charlie.keypair,
+                charlie.base_account,
+                charlie.quote_account,
+                Side::Bid,
+                10_000,
+                2_000_000,
+            )
+            .await,
+        ErrorCode::SlippageExceeded,
+    );
+
+    assert_eq!(
+        quote_mint.balance(charlie.quote_account).await,
+        quote_before
+    );
+    assert_eq!(market.find_order_in_asks(1).unwrap().remaining_quantity, 1);
+
+    scenario.assert_conservation();
+}
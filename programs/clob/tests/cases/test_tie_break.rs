@@ -0,0 +1,123 @@
+use clob::state::{AskOrderBook, BidOrderBook, OrderBook, Side, TieBreakMode};
+
+use crate::svm::{order, TradingScenario};
+
+/// Fill a 12-lot taker against small-then-large asks at one price and
+/// return (maker_order_id, quantity) per fill
+fn allocate(mode: TieBreakMode) -> Vec<(u64, u64)> {
+    let mut book = AskOrderBook::with_tie_break(mode);
+    book.insert_order(order(1, 100, 4).build()).unwrap();
+    book.insert_order(order(2, 100, 10).build()).unwrap();
+    book.insert_order(order(3, 100, 6).build()).unwrap();
+    // A better price always wins regardless of mode
+    book.insert_order(order(4, 99, 1).build()).unwrap();
+
+    let mut taker = order(5, 100, 12).build();
+    book.match_orders(&mut taker)
+        .unwrap()
+        .iter()
+        .map(|fill| (fill.maker_order_id, fill.quantity))
+        .collect()
+}
+
+#[test]
+fn test_time_priority_allocation() {
+    assert_eq!(
+        allocate(TieBreakMode::Time),
+        vec![(4, 1), (1, 4), (2, 7)],
+        "Equal-price orders fill oldest first"
+    );
+}
+
+#[test]
+fn test_size_priority_allocation() {
+    assert_eq!(
+        allocate(TieBreakMode::Size),
+        vec![(4, 1), (2, 10), (3, 1)],
+        "Equal-price orders fill largest first"
+    );
+}
+
+#[test]
+fn test_size_priority_falls_back_to_time() {
+    let mut book = BidOrderBook::with_tie_break(TieBreakMode::Size);
+    book.insert_order(order(1, 50, 5).timestamp(2).build())
+        .unwrap();
+    book.insert_order(order(2, 50, 5).timestamp(1).build())
+        .unwrap();
+    // Same timestamp as order 2, so order id decides
+    book.insert_order(order(3, 50, 5).timestamp(1).build())
+        .unwrap();
+
+    assert_eq!(book.pop().unwrap().order_id, 2);
+    assert_eq!(book.pop().unwrap().order_id, 3);
+    assert_eq!(book.pop().unwrap().order_id, 1);
+}
+
+#[tokio::test]
+async fn test_market_size_priority() {
+    let scenario =
+        TradingScenario::with_market_params(|params| params.tie_break_mode = TieBreakMode::Size)
+            .await;
+    let market = &scenario.market;
+
+    assert_eq!(market.get_market().tie_break_mode, TieBreakMode::Size);
+    assert_eq!(
+        market.get_asks_orderbook().orderbook.tie_break(),
+        TieBreakMode::Size
+    );
+
+    // Alice rests a small ask, Bob a larger one later at the same price
+    market
+        .place_limit_order(&scenario.alice.keypair, Side::Ask, 10, 3)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&scenario.bob.keypair, Side::Ask, 10, 8)
+        .await
+        .unwrap();
+
+    // Charlie's bid should hit Bob's larger order first
+    market
+        .place_limit_order(&scenario.charlie.keypair, Side::Bid, 10, 8)
+        .await
+        .unwrap();
+
+    assert!(market.find_order_in_asks(2).is_none(), "Bob filled first");
+    assert_eq!(
+        market.find_order_in_asks(1).unwrap().remaining_quantity,
+        3,
+        "Alice's smaller order is untouched"
+    );
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_earlier_timestamp_fills_first() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+    let start = scenario.fixture.ctx.borrow().clock().unix_timestamp;
+
+    // Alice gets the lower id but Bob the earlier timestamp
+    market
+        .place_limit_order_at_time(alice, Side::Ask, 1_000_000, 2, start + 20)
+        .await
+        .unwrap();
+    market
+        .place_limit_order_at_time(bob, Side::Ask, 1_000_000, 2, start + 10)
+        .await
+        .unwrap();
+    assert_eq!(market.find_order_in_asks(1).unwrap().timestamp, start + 20);
+    assert_eq!(market.find_order_in_asks(2).unwrap().timestamp, start + 10);
+
+    market
+        .place_limit_order_at_time(charlie, Side::Bid, 1_000_000, 2, start + 30)
+        .await
+        .unwrap();
+    assert!(market.find_order_in_asks(2).is_none());
+    assert_eq!(market.find_order_in_asks(1).unwrap().remaining_quantity, 2);
+}
@@ -1,4 +1,5 @@
 use clob::state::{Side, TimeInForce};
+use solana_sdk::{signature::Signer, transaction::TransactionError};
 
 use crate::svm::{TradingScenario, TwoUserScenario};
 
@@ -50,6 +51,8 @@ async fn test_gtc_orders() {
     let bob_order = market.find_order_in_bids(2);
     assert!(bob_order.is_none(), "Bob's order should be fully filled");
 
+    scenario.assert_conservation();
+
     println!("GTC orders work correctly - remaining quantity stays in orderbook");
 }
 
@@ -107,6 +110,9 @@ async fn test_ioc_orders() {
         bids_count_after, 0,
         "IOC order with no match should not create resting order"
     );
+
+    scenario.assert_conservation();
+
     println!("IOC with no match correctly creates no resting orders");
 }
 
@@ -156,13 +162,22 @@ async fn test_fok_orders() {
         .unwrap();
 
     // Bob tries FOK for more than available - should fail
-    let result = market
-        .place_limit_order_with_tif(bob, Side::Bid, 10, 50, TimeInForce::FOK)
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Bid, 10, 50, TimeInForce::FOK)
         .await;
     assert!(
-        result.is_err(),
+        !outcome.success,
         "FOK order that cannot be completely filled should fail"
     );
+    assert!(
+        outcome.logs_contain("FillOrKillNotFilled"),
+        "Failure logs should name the FOK error"
+    );
+    assert!(matches!(
+        outcome.error,
+        Some(TransactionError::InstructionError(0, _))
+    ));
+    assert!(outcome.compute_units_consumed > 0);
 
     // Verify Alice's order is still there (no partial execution occurred)
     let alice_order_after = market.find_order_in_asks(3);
@@ -184,6 +199,8 @@ async fn test_fok_orders() {
         .await;
     assert!(result.is_err(), "FOK order with no match should fail");
 
+    scenario.assert_conservation();
+
     println!("FOK order with no match correctly rejected");
 }
 
@@ -248,5 +265,71 @@ async fn test_mixed_time_in_force_scenarios() {
         "Orderbooks should be empty after mixed scenario"
     );
 
+    scenario.assert_conservation();
+
     println!("Mixed time-in-force scenarios work correctly");
 }
+
+#[tokio::test]
+async fn test_ioc_sweep_below_limit_reserves_nothing() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    market
+        .place_limit_order(alice, Side::Ask, 10_000_000, 5)
+        .await
+        .unwrap();
+    let bob_before = market.get_user_balance(&bob.pubkey());
+
+    // Limit 12, filled entirely at the maker's 10
+    market
+        .place_limit_order_with_tif(bob, Side::Bid, 12_000_000, 5, TimeInForce::IOC)
+        .await
+        .unwrap();
+
+    // Bob paid 5 lots at 10_000 quote each, not at his limit, and nothing
+    // is left reserved: no order of his rests
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.quote_balance, bob_before.quote_balance - 50_000);
+    assert_eq!(bob_after.base_balance, bob_before.base_balance + 5_000_000);
+    assert_eq!(market.get_orderbook_order_count(Side::Bid), 0);
+    assert_eq!(bob_after.open_order_count, 0);
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_rejected_fok_changes_nothing() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+
+    for price in [10_000_000, 11_000_000] {
+        market
+            .place_limit_order(alice, Side::Ask, price, 2)
+            .await
+            .unwrap();
+    }
+    let bob_before = market.get_user_balance(&bob.pubkey());
+    let next_order_id = market.get_market().next_order_id;
+
+    // Matching takes both levels before finding 1 lot short; the failure
+    // unwinds every fill along with it
+    let outcome = market
+        .place_limit_order_meta(bob, Side::Bid, 11_000_000, 5, TimeInForce::FOK)
+        .await;
+    assert!(outcome.logs_contain("FillOrKillNotFilled"));
+
+    assert_eq!(
+        market.get_l2(Side::Ask),
+        vec![(10_000_000, 2), (11_000_000, 2)]
+    );
+    let bob_after = market.get_user_balance(&bob.pubkey());
+    assert_eq!(bob_after.quote_balance, bob_before.quote_balance);
+    assert_eq!(bob_after.base_balance, bob_before.base_balance);
+    assert_eq!(market.event_queue_len(), 0);
+    assert_eq!(market.get_market().next_order_id, next_order_id);
+}
@@ -0,0 +1,186 @@
+use bytemuck::Zeroable;
+use clob::instructions::ReadTradesResult;
+use clob::state::{
+    EventQueue, FillEvent, Side, TradeCursor, FILL_EVENT_KIND_FILL, FILL_EVENT_KIND_REFUND,
+};
+
+use crate::svm::TradingScenario;
+
+fn queue(capacity: u64) -> EventQueue {
+    let mut queue = EventQueue::zeroed();
+    queue.capacity = capacity;
+    queue
+}
+
+fn fill(taker_order_id: u64, kind: u8) -> FillEvent {
+    FillEvent {
+        taker_order_id,
+        quantity: 1,
+        kind,
+        ..FillEvent::zeroed()
+    }
+}
+
+fn cursor(next_seq: u64) -> TradeCursor {
+    TradeCursor {
+        owner: Default::default(),
+        event_queue: Default::default(),
+        next_seq,
+        bump: 0,
+    }
+}
+
+/// Push `count` fills numbered from the queue's lifetime count, cranking as
+/// it goes so the ring keeps wrapping
+fn push_fills(q: &mut EventQueue, count: u64) {
+    for _ in 0..count {
+        if q.is_full() {
+            q.pop_event().unwrap();
+        }
+        let seq = q.total_events_pushed;
+        q.push_event(fill(seq, FILL_EVENT_KIND_FILL)).unwrap();
+    }
+}
+
+fn taker_ids(trades: &[clob::state::TradeRecord]) -> Vec<u64> {
+    trades.iter().map(|trade| trade.taker_order_id).collect()
+}
+
+#[test]
+fn test_cursor_reads_each_trade_once_across_drains() {
+    let mut q = queue(8);
+    let mut c = cursor(0);
+
+    push_fills(&mut q, 5);
+    let (trades, gap) = c.read(&q, 3);
+    assert_eq!(taker_ids(&trades), vec![0, 1, 2]);
+    assert!(!gap);
+    let (trades, gap) = c.read(&q, 10);
+    assert_eq!(taker_ids(&trades), vec![3, 4]);
+    assert!(!gap);
+    assert_eq!(c.next_seq, 5);
+
+    // Cranked events stay readable, and the reads resume across the wrap
+    while !q.is_empty() {
+        q.pop_event().unwrap();
+    }
+    push_fills(&mut q, 6);
+    let (trades, gap) = c.read(&q, 10);
+    assert_eq!(taker_ids(&trades), (5..11).collect::<Vec<_>>());
+    assert!(!gap);
+    assert!(c.read(&q, 10).0.is_empty());
+}
+
+#[test]
+fn test_cursor_steps_over_refunds() {
+    let mut q = queue(8);
+    let mut c = cursor(0);
+    q.push_event(fill(0, FILL_EVENT_KIND_FILL)).unwrap();
+    q.push_event(fill(1, FILL_EVENT_KIND_REFUND)).unwrap();
+    q.push_event(fill(2, FILL_EVENT_KIND_FILL)).unwrap();
+
+    let (trades, _) = c.read(&q, 10);
+    assert_eq!(taker_ids(&trades), vec![0, 2]);
+    assert_eq!(trades[1].seq, 2);
+    assert_eq!(c.next_seq, 3);
+}
+
+#[test]
+fn test_cursor_steps_over_requeued_fills() {
+    let mut q = queue(8);
+    let mut c = cursor(0);
+    q.push_event(fill(0, FILL_EVENT_KIND_FILL)).unwrap();
+    q.push_event(fill(1, FILL_EVENT_KIND_FILL)).unwrap();
+    // The crank moves the first fill behind the second
+    let mut requeued = q.pop_event().unwrap();
+    requeued.retry_count = 1;
+    q.requeue_event(requeued).unwrap();
+    // Counted apart from pushes, but still numbered in the stream
+    assert_eq!((q.total_events_pushed, q.total_events_requeued), (2, 1));
+    assert_eq!(q.next_seq(), 3);
+
+    let (trades, _) = c.read(&q, 10);
+    assert_eq!(taker_ids(&trades), vec![0, 1]);
+    assert_eq!(c.next_seq, 3);
+    assert!(c.read(&q, 10).0.is_empty());
+}
+
+#[test]
+fn test_lapped_cursor_reports_gap_and_resumes_at_oldest() {
+    let mut q = queue(8);
+    let mut c = cursor(0);
+    push_fills(&mut q, 2);
+    c.read(&q, 10);
+
+    // 10 more pushes overwrite seqs 2 and 3 before the cursor reads them
+    push_fills(&mut q, 10);
+    assert_eq!(q.oldest_retained_seq(), 4);
+    let (trades, gap) = c.read(&q, 10);
+    assert!(gap);
+    assert_eq!(taker_ids(&trades), (4..12).collect::<Vec<_>>());
+
+    // Caught up again, so the next read has no gap
+    push_fills(&mut q, 1);
+    let (trades, gap) = c.read(&q, 10);
+    assert!(!gap);
+    assert_eq!(taker_ids(&trades), vec![12]);
+}
+
+#[tokio::test]
+async fn test_read_trades_resumes_without_duplicates() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let charlie = &scenario.charlie.keypair;
+
+    // Trades made before the cursor opens are not part of its stream
+    market
+        .place_limit_order(alice, Side::Ask, 1_000_000, 1)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(bob, Side::Bid, 1_000_000, 1)
+        .await
+        .unwrap();
+    market.open_trade_cursor(charlie).await.unwrap();
+
+    for price in [1_000_000, 1_100_000, 1_200_000] {
+        market
+            .place_limit_order(alice, Side::Ask, price, 2)
+            .await
+            .unwrap();
+    }
+    market
+        .place_limit_order(bob, Side::Bid, 1_200_000, 6)
+        .await
+        .unwrap();
+
+    let outcome = market.read_trades(charlie, 2).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let first: ReadTradesResult = outcome.return_value();
+    assert_eq!(
+        first.trades.iter().map(|t| t.price).collect::<Vec<_>>(),
+        vec![1_000_000, 1_100_000]
+    );
+    assert!(!first.gap && !first.caught_up);
+
+    // Cranking in between does not replay or lose anything
+    market.consume_events(10, &[alice]).await.unwrap();
+    let second: ReadTradesResult = market.read_trades(charlie, 10).await.return_value();
+    assert_eq!(
+        second.trades.iter().map(|t| t.price).collect::<Vec<_>>(),
+        vec![1_200_000]
+    );
+    assert!(!second.gap && second.caught_up);
+    assert!(second.trades[0].seq > first.trades[1].seq);
+    assert_eq!(second.trades[0].maker_side, Side::Ask);
+
+    let third: ReadTradesResult = market.read_trades(charlie, 10).await.return_value();
+    assert!(third.trades.is_empty());
+    assert_eq!(third.next_seq, second.next_seq);
+
+    // Only the owner can move a cursor
+    let outcome = market.read_trades(bob, 10).await;
+    assert!(!outcome.success);
+}
@@ -0,0 +1,87 @@
+use crate::svm::{
+    assert_conservation, market::MarketFixture, spl::MintFixture, test::TestFixture, SvmContext,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account, Mint},
+};
+use solana_sdk::signature::{Keypair, Signer};
+use std::rc::Rc;
+
+fn vault_withheld(ctx: &SvmContext, vault: &Pubkey) -> u64 {
+    let account = ctx.svm.get_account(vault).unwrap();
+    let state = StateWithExtensions::<Account>::unpack(&account.data).unwrap();
+    state
+        .get_extension::<TransferFeeAmount>()
+        .unwrap()
+        .withheld_amount
+        .into()
+}
+
+fn mint_withheld(ctx: &SvmContext, mint: &Pubkey) -> u64 {
+    let account = ctx.svm.get_account(mint).unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    state
+        .get_extension::<TransferFeeConfig>()
+        .unwrap()
+        .withheld_amount
+        .into()
+}
+
+#[tokio::test]
+async fn test_harvest_withheld_fees_keeps_vault_invariant() {
+    let fixture = TestFixture::new().await;
+    let ctx = Rc::clone(&fixture.ctx);
+
+    // 1% transfer fee on the base mint
+    let base_mint =
+        MintFixture::new_with_transfer_fee(ctx.clone(), Keypair::new(), 6, 100, 10_000_000).await;
+    let market = MarketFixture::new(ctx.clone(), &base_mint, &fixture.quote_mint).await;
+
+    let user = ctx.borrow_mut().gen_and_fund_key();
+    let user_base = base_mint
+        .create_and_mint(&user.pubkey(), 1_000_000_000)
+        .await;
+
+    market
+        .deposit(&user, base_mint.mint, user_base, 100_000_000)
+        .await
+        .expect("Deposit through a transfer-fee mint");
+
+    // The ledger only credits what reached the vault
+    let ledger = market.get_user_balance(&user.pubkey()).base_balance;
+    assert_eq!(ledger, 99_000_000);
+    assert_eq!(base_mint.balance(market.base_vault).await, ledger);
+    assert_eq!(vault_withheld(&ctx.borrow(), &market.base_vault), 1_000_000);
+
+    market
+        .harvest_withheld_fees()
+        .await
+        .expect("Harvest is permissionless");
+
+    assert_eq!(vault_withheld(&ctx.borrow(), &market.base_vault), 0);
+    assert_eq!(mint_withheld(&ctx.borrow(), &base_mint.mint), 1_000_000);
+    assert_eq!(
+        base_mint.balance(market.base_vault).await,
+        ledger,
+        "Harvesting does not move vault funds"
+    );
+
+    // Withdrawals debit the full amount from the vault; the fee is the user's
+    market
+        .withdraw(&user, base_mint.mint, user_base, 9_000_000)
+        .await
+        .unwrap();
+    let ledger = market.get_user_balance(&user.pubkey()).base_balance;
+    assert_eq!(ledger, 90_000_000);
+    assert_eq!(base_mint.balance(market.base_vault).await, ledger);
+
+    // Nothing withheld on the vaults: harvest is a no-op but still succeeds
+    market.harvest_withheld_fees().await.unwrap();
+
+    assert_conservation(&market, &[user.pubkey()]);
+}
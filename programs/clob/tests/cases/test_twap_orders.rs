@@ -0,0 +1,212 @@
+use clob::errors::ErrorCode;
+use clob::instructions::PlaceTwapOrderParams;
+use clob::state::{Side, TwapStatus};
+use clob::{TwapSliceExecuted, TwapStatusChanged};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{assert_clob_error, TradingScenario};
+
+const PRICE: u64 = 10_000_000;
+const INTERVAL_SECS: u32 = 60;
+const REWARD_LAMPORTS: u64 = 5_000;
+
+fn twap_params(side: Side, total_quantity: u64, slice_quantity: u64) -> PlaceTwapOrderParams {
+    PlaceTwapOrderParams {
+        twap_id: 1,
+        side,
+        limit_price: PRICE,
+        total_quantity,
+        slice_quantity,
+        interval_secs: INTERVAL_SECS,
+        expiry: 0,
+        reward_lamports: REWARD_LAMPORTS,
+    }
+}
+
+#[tokio::test]
+async fn test_twap_fills_across_slices_and_pays_executor() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice.keypair;
+    let bob = &scenario.bob.keypair;
+    let executor = &scenario.charlie.keypair;
+    let ctx = &scenario.fixture.ctx;
+    let start = ctx.borrow().clock().unix_timestamp;
+
+    market
+        .place_limit_order(alice, Side::Ask, PRICE, 10)
+        .await
+        .unwrap();
+    let bob_base_before = market.get_user_balance(&bob.pubkey()).base_balance;
+    market
+        .place_twap_order(bob, twap_params(Side::Bid, 6, 2))
+        .await
+        .unwrap();
+    let executor_lamports_before = ctx.borrow().lamports(&executor.pubkey());
+
+    // The first slice is due at once, the next only after the interval
+    let outcome = market.execute_twap_slice(executor, &bob.pubkey(), 1).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let slice = &outcome.events::<TwapSliceExecuted>()[0];
+    assert_eq!((slice.slice_quantity, slice.filled_quantity), (2, 2));
+    assert_clob_error(
+        market.execute_twap_slice(executor, &bob.pubkey(), 1).await,
+        ErrorCode::TwapSliceNotDue,
+    );
+
+    for slice in 1..3 {
+        ctx.borrow_mut()
+            .set_clock(start + (slice * INTERVAL_SECS) as i64);
+        let outcome = market.execute_twap_slice(executor, &bob.pubkey(), 1).await;
+        assert!(outcome.success, "{:?}", outcome.logs);
+        assert_eq!(
+            outcome.events::<TwapSliceExecuted>()[0].total_filled,
+            2 * (slice as u64 + 1)
+        );
+    }
+
+    let twap = market.get_twap_order(&bob.pubkey(), 1);
+    assert_eq!(twap.status, TwapStatus::Finished);
+    assert_eq!((twap.filled_quantity, twap.slices_executed), (6, 3));
+    assert_eq!(
+        market.get_user_balance(&bob.pubkey()).base_balance - bob_base_before,
+        market.get_market().base_lot_size * 6
+    );
+    assert_eq!(market.get_l2(Side::Ask), vec![(PRICE, 4)]);
+    assert_eq!(
+        ctx.borrow().lamports(&executor.pubkey()) - executor_lamports_before,
+        3 * REWARD_LAMPORTS
+    );
+
+    // Nothing more runs once finished
+    ctx.borrow_mut().set_clock(start + 3 * INTERVAL_SECS as i64);
+    assert_clob_error(
+        market.execute_twap_slice(executor, &bob.pubkey(), 1).await,
+        ErrorCode::TwapNotActive,
+    );
+    market.close_twap_order(bob, 1).await.unwrap();
+
+    market.consume_events(10, &[alice]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_twap_pauses_when_balance_runs_out_and_resumes() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob.keypair;
+    let executor = &scenario.charlie.keypair;
+    let ctx = &scenario.fixture.ctx;
+    let start = ctx.borrow().clock().unix_timestamp;
+    let lot = market.get_market().base_lot_size;
+    let base_mint = market.base_mint;
+
+    market
+        .place_limit_order(bob, Side::Bid, PRICE, 60)
+        .await
+        .unwrap();
+    market
+        .place_twap_order(&alice.keypair, twap_params(Side::Ask, 60, 20))
+        .await
+        .unwrap();
+    let outcome = market
+        .execute_twap_slice(executor, &alice.pubkey(), 1)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+
+    // Alice keeps only 10 lots, short of the next 20-lot slice
+    let withdrawn = market.get_user_balance(&alice.pubkey()).base_balance - 10 * lot;
+    market
+        .withdraw(&alice.keypair, base_mint, alice.base_account, withdrawn)
+        .await
+        .unwrap();
+    ctx.borrow_mut().set_clock(start + INTERVAL_SECS as i64);
+    let outcome = market
+        .execute_twap_slice(executor, &alice.pubkey(), 1)
+        .await;
+    assert!(outcome.success, "pausing does not fail: {:?}", outcome.logs);
+    assert!(outcome.events::<TwapSliceExecuted>().is_empty());
+    assert_eq!(
+        outcome.events::<TwapStatusChanged>()[0].status,
+        TwapStatus::Paused
+    );
+    let twap = market.get_twap_order(&alice.pubkey(), 1);
+    assert_eq!(
+        (twap.status, twap.filled_quantity),
+        (TwapStatus::Paused, 20)
+    );
+    assert_clob_error(
+        market
+            .execute_twap_slice(executor, &alice.pubkey(), 1)
+            .await,
+        ErrorCode::TwapSliceNotDue,
+    );
+
+    // Topped up, the next due slice resumes the schedule
+    market
+        .deposit(&alice.keypair, base_mint, alice.base_account, withdrawn)
+        .await
+        .unwrap();
+    ctx.borrow_mut().set_clock(start + 2 * INTERVAL_SECS as i64);
+    let outcome = market
+        .execute_twap_slice(executor, &alice.pubkey(), 1)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    assert_eq!(outcome.events::<TwapSliceExecuted>()[0].filled_quantity, 20);
+    assert_eq!(
+        outcome.events::<TwapStatusChanged>()[0].status,
+        TwapStatus::Active
+    );
+    assert_eq!(
+        market.get_twap_order(&alice.pubkey(), 1).filled_quantity,
+        40
+    );
+
+    market.consume_events(10, &[bob]).await.unwrap();
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_twap_expires_without_liquidity() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let bob = &scenario.bob.keypair;
+    let executor = &scenario.charlie.keypair;
+    let ctx = &scenario.fixture.ctx;
+    let start = ctx.borrow().clock().unix_timestamp;
+
+    let mut params = twap_params(Side::Bid, 6, 2);
+    params.expiry = start + 100;
+    market.place_twap_order(bob, params).await.unwrap();
+
+    // Slices with nothing to fill against still count, and never rest
+    for slice in 0..2 {
+        ctx.borrow_mut()
+            .set_clock(start + (slice * INTERVAL_SECS) as i64);
+        let outcome = market.execute_twap_slice(executor, &bob.pubkey(), 1).await;
+        assert!(outcome.success, "{:?}", outcome.logs);
+        assert_eq!(outcome.events::<TwapSliceExecuted>()[0].filled_quantity, 0);
+    }
+    assert!(market.orderbooks_are_empty());
+
+    ctx.borrow_mut().set_clock(start + 2 * INTERVAL_SECS as i64);
+    let outcome = market.execute_twap_slice(executor, &bob.pubkey(), 1).await;
+    assert!(outcome.success, "{:?}", outcome.logs);
+    let twap = market.get_twap_order(&bob.pubkey(), 1);
+    assert_eq!(
+        (twap.status, twap.filled_quantity, twap.slices_executed),
+        (TwapStatus::Expired, 0, 2)
+    );
+    assert_clob_error(
+        market.execute_twap_slice(executor, &bob.pubkey(), 1).await,
+        ErrorCode::TwapNotActive,
+    );
+
+    // Closing returns the unspent reward escrow with the rent
+    let bob_lamports = ctx.borrow().lamports(&bob.pubkey());
+    market.close_twap_order(bob, 1).await.unwrap();
+    assert!(ctx.borrow().lamports(&bob.pubkey()) > bob_lamports + REWARD_LAMPORTS);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,91 @@
+use clob::errors::ErrorCode;
+use clob::events::MarketParamsUpdated;
+use clob::state::Side;
+
+use crate::svm::{assert_clob_error, parse_events, TradingScenario};
+
+#[tokio::test]
+async fn test_update_market_params_preconditions() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let bob = &scenario.bob;
+
+    assert_clob_error(
+        market.update_market_params(0, 1_000).await,
+        ErrorCode::InvalidParameter,
+    );
+
+    // An unsettled fill would be priced under the wrong params
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 5_000, 1)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 5_000, 1)
+        .await
+        .unwrap();
+    assert_clob_error(
+        market.update_market_params(2_000_000, 1_000).await,
+        ErrorCode::EventQueueNotEmpty,
+    );
+    market.consume_events(10, &[&alice.keypair]).await.unwrap();
+
+    // A resting order on either book would be mispriced
+    market
+        .place_limit_order(&alice.keypair, Side::Ask, 6_000, 1)
+        .await
+        .unwrap();
+    assert_clob_error(
+        market.update_market_params(2_000_000, 1_000).await,
+        ErrorCode::OrderbookNotEmpty,
+    );
+    market
+        .cancel_order(&alice.keypair, 3, Side::Ask)
+        .await
+        .unwrap();
+    market
+        .place_limit_order(&bob.keypair, Side::Bid, 4_000, 1)
+        .await
+        .unwrap();
+    assert_clob_error(
+        market.update_market_params(2_000_000, 1_000).await,
+        ErrorCode::OrderbookNotEmpty,
+    );
+    market
+        .cancel_order(&bob.keypair, 4, Side::Bid)
+        .await
+        .unwrap();
+
+    market.update_market_params(2_000_000, 1_000).await.unwrap();
+
+    scenario.assert_conservation();
+}
+
+#[tokio::test]
+async fn test_update_market_params_on_empty_book() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+
+    assert_clob_error(
+        market.update_market_params(1_000_000, 0).await,
+        ErrorCode::InvalidParameter,
+    );
+
+    let meta = market.update_market_params(500_000, 2_000).await.unwrap();
+    let updated = &parse_events::<MarketParamsUpdated>(&meta.logs)[0];
+    assert_eq!(
+        (updated.base_lot_size, updated.quote_tick_size),
+        (500_000, 2_000)
+    );
+    assert_eq!(
+        (updated.prev_base_lot_size, updated.prev_quote_tick_size),
+        (1_000_000, 1_000)
+    );
+
+    let state = market.get_market();
+    assert_eq!(
+        (state.base_lot_size, state.quote_tick_size),
+        (500_000, 2_000)
+    );
+}
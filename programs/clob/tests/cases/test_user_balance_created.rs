@@ -0,0 +1,115 @@
+use anchor_lang::prelude::Pubkey;
+use clob::events::{UserBalanceCreated, UserDeposit};
+use solana_sdk::signer::Signer;
+
+use crate::svm::{market::get_user_balance_pda, TradingScenario};
+
+#[tokio::test]
+async fn test_created_event_only_on_first_deposit() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let quote_mint = &scenario.fixture.quote_mint;
+    let delegate = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+    let custodied = Pubkey::new_unique();
+
+    quote_mint
+        .approve(
+            &alice.quote_account,
+            &alice.keypair,
+            &delegate.pubkey(),
+            2_000_000,
+        )
+        .await;
+
+    let outcome = market
+        .deposit_for_meta(
+            &delegate,
+            &custodied,
+            quote_mint.mint,
+            alice.quote_account,
+            1_000_000,
+        )
+        .await;
+    assert!(outcome.success, "{:#?}", outcome.logs);
+    let created = outcome.events::<UserBalanceCreated>();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].user, custodied);
+    assert_eq!(created[0].market, market.market);
+    assert_eq!(created[0].payer, delegate.pubkey());
+
+    let outcome = market
+        .deposit_for_meta(
+            &delegate,
+            &custodied,
+            quote_mint.mint,
+            alice.quote_account,
+            1_000_000,
+        )
+        .await;
+    assert!(outcome.success, "{:#?}", outcome.logs);
+    assert!(outcome.events::<UserBalanceCreated>().is_empty());
+    assert_eq!(outcome.events::<UserDeposit>().len(), 1);
+    assert_eq!(market.get_user_balance(&custodied).quote_balance, 2_000_000);
+
+    // Alice's balance was opened by the scenario's own deposits
+    let outcome = market
+        .deposit_for_meta(
+            &alice.keypair,
+            &alice.pubkey(),
+            quote_mint.mint,
+            alice.quote_account,
+            1_000_000,
+        )
+        .await;
+    assert!(outcome.success);
+    assert!(outcome.events::<UserBalanceCreated>().is_empty());
+
+    scenario.assert_conservation_with(&[custodied]);
+}
+
+#[tokio::test]
+async fn test_prefunded_balance_address_is_still_created_for_its_owner() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let bob = &scenario.bob;
+
+    // An outsider can send lamports to a balance address before it exists,
+    // but cannot write or assign it; the deposit still creates it
+    let newcomer = scenario.fixture.ctx.borrow_mut().gen_and_fund_key();
+    let (newcomer_pda, _) = get_user_balance_pda(&newcomer.pubkey(), &market.market);
+    scenario
+        .fixture
+        .ctx
+        .borrow_mut()
+        .svm
+        .airdrop(&newcomer_pda, 1_000_000);
+
+    let quote_mint = &scenario.fixture.quote_mint;
+    quote_mint
+        .approve(
+            &bob.quote_account,
+            &bob.keypair,
+            &newcomer.pubkey(),
+            1_000_000,
+        )
+        .await;
+    let outcome = market
+        .deposit_for_meta(
+            &newcomer,
+            &newcomer.pubkey(),
+            quote_mint.mint,
+            bob.quote_account,
+            1_000_000,
+        )
+        .await;
+    assert!(outcome.success, "{:#?}", outcome.logs);
+    assert_eq!(outcome.events::<UserBalanceCreated>().len(), 1);
+
+    let balance = market.get_user_balance(&newcomer.pubkey());
+    assert_eq!(balance.owner, newcomer.pubkey());
+    assert_eq!(balance.market, market.market);
+    assert_eq!(balance.quote_balance, 1_000_000);
+
+    scenario.assert_conservation_with(&[newcomer.pubkey()]);
+}
@@ -0,0 +1,65 @@
+use crate::svm::TwoUserScenario;
+use anchor_lang::{prelude::Pubkey, AccountSerialize, Discriminator};
+use clob::state::{
+    UserBalance, USER_BALANCE_MARKET_OFFSET, USER_BALANCE_OWNER_OFFSET, USER_BALANCE_SIZE,
+};
+use solana_sdk::signature::Signer;
+
+#[test]
+fn test_user_balance_filter_offsets() {
+    let owner = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let balance = UserBalance {
+        owner,
+        market,
+        base_balance: 1,
+        quote_balance: 2,
+        bump: 255,
+        last_order_slot: 3,
+        orders_this_slot: 4,
+        next_intent_nonce: 5,
+        position_base_net: -6,
+        position_cost_quote: -7,
+        realized_pnl_quote: 8,
+        open_order_count: 9,
+        schema_version: 10,
+        session_expiry: 11,
+    };
+
+    let mut data = Vec::new();
+    balance.try_serialize(&mut data).unwrap();
+
+    assert_eq!(data.len(), USER_BALANCE_SIZE, "dataSize filter is stale");
+    assert_eq!(&data[..8], UserBalance::DISCRIMINATOR);
+    assert_eq!(
+        &data[USER_BALANCE_OWNER_OFFSET..USER_BALANCE_OWNER_OFFSET + 32],
+        owner.as_ref(),
+        "owner memcmp offset is stale"
+    );
+    assert_eq!(
+        &data[USER_BALANCE_MARKET_OFFSET..USER_BALANCE_MARKET_OFFSET + 32],
+        market.as_ref(),
+        "market memcmp offset is stale"
+    );
+}
+
+#[tokio::test]
+async fn test_user_balance_offsets_match_on_chain_account() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+    let alice = scenario.alice.keypair.pubkey();
+
+    let data = market.get_user_balance_data(&alice);
+
+    assert_eq!(data.len(), USER_BALANCE_SIZE);
+    assert_eq!(
+        &data[USER_BALANCE_OWNER_OFFSET..USER_BALANCE_OWNER_OFFSET + 32],
+        alice.as_ref()
+    );
+    assert_eq!(
+        &data[USER_BALANCE_MARKET_OFFSET..USER_BALANCE_MARKET_OFFSET + 32],
+        market.market.as_ref()
+    );
+
+    scenario.assert_conservation();
+}
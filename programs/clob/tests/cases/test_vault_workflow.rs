@@ -1,4 +1,9 @@
-use crate::svm::{market::MarketFixture, test::TestFixture, TradingUser};
+use crate::svm::{
+    assert_conservation,
+    market::{get_vault_pda, MarketFixture},
+    test::TestFixture,
+    TradingUser,
+};
 use std::rc::Rc;
 
 #[tokio::test]
@@ -14,6 +19,17 @@ async fn test_vault_workflow() {
     let market = MarketFixture::new(ctx.clone(), &fixture.base_mint, &fixture.quote_mint).await;
     println!("Market initialized successfully at: {}", market.market);
 
+    // Vault bumps are stored so deposit and withdraw skip the PDA search
+    let state = market.get_market();
+    assert_eq!(
+        state.base_vault_bump,
+        get_vault_pda(&market.market, &fixture.base_mint.mint).1
+    );
+    assert_eq!(
+        state.quote_vault_bump,
+        get_vault_pda(&market.market, &fixture.quote_mint.mint).1
+    );
+
     // Step 2: Test deposits
     println!("\n=== Testing Deposits ===");
 
@@ -81,6 +97,8 @@ async fn test_vault_workflow() {
         ),
     }
 
+    assert_conservation(&market, &[user.pubkey()]);
+
     // Step 3: Test withdrawals
     println!("\n=== Testing Withdrawals ===");
 
@@ -130,6 +148,8 @@ async fn test_vault_workflow() {
         ),
     }
 
+    assert_conservation(&market, &[user.pubkey()]);
+
     // Step 4: Test close user balance
     println!("\n=== Testing Close User Balance ===");
 
@@ -185,5 +205,7 @@ async fn test_vault_workflow() {
         Err(err) => panic!("Expected close to succeed: {:?}", err),
     }
 
+    assert_conservation(&market, &[user.pubkey()]);
+
     println!("\n=== All tests completed successfully! ===");
 }
@@ -0,0 +1,40 @@
+use clob::state::Side;
+
+use crate::svm::TradingScenario;
+
+#[tokio::test]
+async fn test_withdraw_returns_remaining_free_balance() {
+    let scenario = TradingScenario::new().await;
+    let market = &scenario.market;
+    let alice = &scenario.alice;
+    let base_mint = scenario.fixture.base_mint.mint;
+    let quote_mint = scenario.fixture.quote_mint.mint;
+
+    // Reserve some quote so the free balance differs from what was deposited
+    market
+        .place_limit_order(&alice.keypair, Side::Bid, 5_000, 2)
+        .await
+        .unwrap();
+    let before = market.get_user_balance(&alice.pubkey());
+
+    let outcome = market
+        .withdraw_meta(&alice.keypair, quote_mint, alice.quote_account, 1_000)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+    assert_eq!(outcome.return_value::<u64>(), before.quote_balance - 1_000);
+
+    let outcome = market
+        .withdraw_meta(&alice.keypair, base_mint, alice.base_account, 3_000_000)
+        .await;
+    assert!(outcome.success, "{:?}", outcome.error);
+    assert_eq!(
+        outcome.return_value::<u64>(),
+        before.base_balance - 3_000_000
+    );
+
+    let after = market.get_user_balance(&alice.pubkey());
+    assert_eq!(after.base_balance, before.base_balance - 3_000_000);
+    assert_eq!(after.quote_balance, before.quote_balance - 1_000);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,38 @@
+use clob::state::Side;
+
+use crate::svm::TwoUserScenario;
+
+#[tokio::test]
+async fn test_with_bids_reads_top_of_book_in_place() {
+    let scenario = TwoUserScenario::new().await;
+    let market = &scenario.market;
+
+    for price in [10, 12, 11] {
+        market
+            .place_limit_order(&scenario.alice.keypair, Side::Bid, price, 1)
+            .await
+            .unwrap();
+    }
+    market
+        .place_limit_order(&scenario.bob.keypair, Side::Ask, 20, 1)
+        .await
+        .unwrap();
+
+    // Repeated reads borrow the account instead of copying a BidSide each time
+    for _ in 0..100 {
+        let best_bid = market.with_bids(|book| book.peek().map(|order| order.price));
+        assert_eq!(best_bid, Some(12));
+    }
+
+    let best_ask = market.with_asks(|book| book.peek().map(|order| order.price));
+    assert_eq!(best_ask, Some(20));
+
+    // Same view as the owned copy
+    assert_eq!(
+        market.with_bids(|book| book.book_hash()),
+        market.get_bids_orderbook().orderbook.book_hash()
+    );
+    assert_eq!(market.with_bids(|book| book.len()), 3);
+
+    scenario.assert_conservation();
+}
@@ -0,0 +1,258 @@
+use anchor_lang::solana_program::clock::Clock;
+use litesvm::{
+    types::{FailedTransactionMetadata, TransactionMetadata, TransactionResult},
+    LiteSVM,
+};
+use solana_program_test::{read_file, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    bpf_loader,
+    hash::Hash,
+    pubkey::Pubkey,
+    rent::Rent,
+    transaction::Transaction,
+};
+use std::{future::Future, time::Duration};
+use tokio::runtime::Runtime;
+
+const CLOB_PROGRAM_PATH: &str = "../../target/deploy/clob.so";
+
+/// Runtime a test binary's suites execute against
+// Each test binary constructs only the one it picks
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+pub enum Backend {
+    /// In-process LiteSVM: fast, but not the validator's runtime
+    LiteSvm,
+    /// solana-program-test's bank, the runtime a validator runs
+    Banks,
+}
+
+impl Backend {
+    /// A fresh chain with the clob program deployed
+    pub fn start(self) -> Box<dyn SvmBackend> {
+        match self {
+            Backend::LiteSvm => {
+                let mut svm = LiteSVM::new();
+                svm.add_program_from_file(clob::ID, CLOB_PROGRAM_PATH)
+                    .expect("Failed to add clob program");
+                Box::new(svm)
+            }
+            Backend::Banks => Box::new(BanksBackend::new()),
+        }
+    }
+}
+
+/// The chain operations the fixtures need, so the same suites run on either
+/// backend
+pub trait SvmBackend {
+    // TransactionResult is LiteSVM's, and its error carries the full metadata
+    #[allow(clippy::result_large_err)]
+    fn send_transaction(&mut self, tx: Transaction) -> TransactionResult;
+    fn latest_blockhash(&self) -> Hash;
+    /// Move to a blockhash no earlier transaction used, so an identical
+    /// transaction is not rejected as already processed
+    fn expire_blockhash(&mut self);
+    fn get_account(&self, address: &Pubkey) -> Option<Account>;
+    fn set_account(&mut self, address: Pubkey, account: Account);
+    fn airdrop(&mut self, address: &Pubkey, lamports: u64);
+    fn minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64;
+    fn clock(&self) -> Clock;
+    fn set_clock(&mut self, clock: &Clock);
+    fn warp_to_slot(&mut self, slot: u64);
+}
+
+impl SvmBackend for LiteSVM {
+    fn send_transaction(&mut self, tx: Transaction) -> TransactionResult {
+        LiteSVM::send_transaction(self, tx)
+    }
+
+    fn latest_blockhash(&self) -> Hash {
+        LiteSVM::latest_blockhash(self)
+    }
+
+    fn expire_blockhash(&mut self) {
+        LiteSVM::expire_blockhash(self)
+    }
+
+    fn get_account(&self, address: &Pubkey) -> Option<Account> {
+        LiteSVM::get_account(self, address)
+    }
+
+    fn set_account(&mut self, address: Pubkey, account: Account) {
+        LiteSVM::set_account(self, address, account).unwrap()
+    }
+
+    fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        LiteSVM::airdrop(self, address, lamports).unwrap();
+    }
+
+    fn minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
+        LiteSVM::minimum_balance_for_rent_exemption(self, data_len)
+    }
+
+    fn clock(&self) -> Clock {
+        self.get_sysvar::<Clock>()
+    }
+
+    fn set_clock(&mut self, clock: &Clock) {
+        self.set_sysvar(clock)
+    }
+
+    fn warp_to_slot(&mut self, slot: u64) {
+        LiteSVM::warp_to_slot(self, slot)
+    }
+}
+
+/// solana-program-test's bank behind its async BanksClient, driven by a
+/// runtime of its own
+pub struct BanksBackend {
+    // Only None while dropping
+    runtime: Option<Runtime>,
+    context: ProgramTestContext,
+}
+
+impl BanksBackend {
+    fn new() -> Self {
+        let data = read_file(CLOB_PROGRAM_PATH);
+        let mut program_test = ProgramTest::default();
+        program_test.add_account(
+            clob::ID,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: bpf_loader::id(),
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let context = block_on(&runtime, program_test.start_with_context());
+        Self {
+            runtime: Some(runtime),
+            context,
+        }
+    }
+
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        block_on(self.runtime.as_ref().unwrap(), future)
+    }
+}
+
+/// Run `future` to completion on `runtime`. The suites are themselves async
+/// tests, and tokio will not block on a runtime from a thread already inside
+/// one, so this drives it from a scoped thread.
+fn block_on<F>(runtime: &Runtime, future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| runtime.block_on(future))
+            .join()
+            .unwrap()
+    })
+}
+
+impl Drop for BanksBackend {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which tokio forbids inside the test's
+        // own runtime
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl SvmBackend for BanksBackend {
+    fn send_transaction(&mut self, tx: Transaction) -> TransactionResult {
+        let signature = tx.signatures[0];
+        let client = &self.context.banks_client;
+        let result = self
+            .block_on(client.process_transaction_with_metadata(tx))
+            .unwrap();
+        let meta = result
+            .metadata
+            .map(|meta| TransactionMetadata {
+                signature,
+                logs: meta.log_messages,
+                inner_instructions: Default::default(),
+                compute_units_consumed: meta.compute_units_consumed,
+                return_data: meta.return_data.unwrap_or_default(),
+            })
+            .unwrap_or_else(|| TransactionMetadata {
+                signature,
+                ..Default::default()
+            });
+        match result.result {
+            Ok(()) => Ok(meta),
+            Err(err) => Err(FailedTransactionMetadata { err, meta }),
+        }
+    }
+
+    fn latest_blockhash(&self) -> Hash {
+        self.context.last_blockhash
+    }
+
+    fn expire_blockhash(&mut self) {
+        // The bank registers a new blockhash every slot, a few milliseconds
+        // here; ProgramTestContext's own helper polls far more slowly
+        let client = &self.context.banks_client;
+        let previous = self.context.last_blockhash;
+        let blockhash = self.block_on(async {
+            loop {
+                let blockhash = client.get_latest_blockhash().await.unwrap();
+                if blockhash != previous {
+                    return blockhash;
+                }
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+        self.context.last_blockhash = blockhash;
+    }
+
+    fn get_account(&self, address: &Pubkey) -> Option<Account> {
+        let client = &self.context.banks_client;
+        self.block_on(client.get_account(*address)).unwrap()
+    }
+
+    fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.context
+            .set_account(&address, &AccountSharedData::from(account));
+    }
+
+    fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+        let mut account = self.get_account(address).unwrap_or_default();
+        account.lamports += lamports;
+        self.set_account(*address, account);
+    }
+
+    fn minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
+        let client = &self.context.banks_client;
+        self.block_on(client.get_rent())
+            .unwrap()
+            .minimum_balance(data_len)
+    }
+
+    fn clock(&self) -> Clock {
+        let client = &self.context.banks_client;
+        self.block_on(client.get_sysvar::<Clock>()).unwrap()
+    }
+
+    fn set_clock(&mut self, clock: &Clock) {
+        self.context.set_sysvar(clock)
+    }
+
+    fn warp_to_slot(&mut self, slot: u64) {
+        self.context.warp_to_slot(slot).unwrap()
+    }
+}
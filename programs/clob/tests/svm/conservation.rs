@@ -0,0 +1,118 @@
+//! Token conservation: every token a market's vault holds is owed to someone,
+//! as a free balance, a resting order's reservation or a fill or refund the
+//! crank has yet to settle, or to the market as an accrued fee.
+use anchor_lang::prelude::*;
+use clob::state::{
+    AskSide, BidSide, EventQueue, FillEvent, Market, Order, Side, UserBalance,
+    FILL_EVENT_KIND_FILL, FILL_EVENT_KIND_REFUND,
+};
+
+use super::market::{get_user_balance_pda, MarketFixture};
+
+/// Where one mint's tokens are held, as seen from the vault
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TokenLedger {
+    pub vault: u64,     // Token amount in the vault account
+    pub free: u64,      // Sum of the given users' free balances
+    pub reserved: u64,  // Held by resting orders on the book
+    pub unsettled: u64, // Owed to makers by events still in the queue, before fees
+    pub fees: u64,      // Fees accrued to the market
+}
+
+impl TokenLedger {
+    pub fn owed(&self) -> u64 {
+        self.free + self.reserved + self.unsettled + self.fees
+    }
+}
+
+/// Base and quote ledgers for `market`, counting the balances of `users`.
+/// Closed balances, books and queues count as empty.
+pub fn token_ledgers(market: &MarketFixture, users: &[Pubkey]) -> (TokenLedger, TokenLedger) {
+    let state = market.get_market();
+    let mut base = TokenLedger {
+        vault: token_amount(market, &market.base_vault),
+        fees: state.base_fees_accrued,
+        ..TokenLedger::default()
+    };
+    let mut quote = TokenLedger {
+        vault: token_amount(market, &market.quote_vault),
+        fees: state.quote_fees_accrued,
+        ..TokenLedger::default()
+    };
+
+    let mut users = users.to_vec();
+    users.sort();
+    users.dedup();
+    for user in &users {
+        let (address, _) = get_user_balance_pda(user, &market.market);
+        if let Some(data) = market.account_data(&address) {
+            let balance = UserBalance::try_deserialize(&mut data.as_slice()).unwrap();
+            base.free += balance.base_balance;
+            quote.free += balance.quote_balance;
+        }
+    }
+
+    if let Some(bids) = zero_copy::<BidSide>(market, &market.bids) {
+        quote.reserved += reserved(&state, Side::Bid, &bids.orderbook.orders());
+    }
+    if let Some(asks) = zero_copy::<AskSide>(market, &market.asks) {
+        base.reserved += reserved(&state, Side::Ask, &asks.orderbook.orders());
+    }
+
+    for shard in 0..state.event_queue_shard_count.max(1) as usize {
+        let Some(queue) = zero_copy::<EventQueue>(market, &market.event_queue_shard(shard)) else {
+            continue;
+        };
+        for offset in 0..queue.logical_len() {
+            let index = (queue.head + offset) % queue.capacity;
+            let event = &queue.events[index as usize];
+            let (base_owed, quote_owed) = unsettled(&state, event);
+            base.unsettled += base_owed;
+            quote.unsettled += quote_owed;
+        }
+    }
+
+    (base, quote)
+}
+
+/// Assert that each vault holds exactly what the market owes in its mint.
+/// `users` must name everyone with a balance in the market.
+pub fn assert_conservation(market: &MarketFixture, users: &[Pubkey]) {
+    let (base, quote) = token_ledgers(market, users);
+    assert_eq!(base.vault, base.owed(), "base not conserved: {base:?}");
+    assert_eq!(quote.vault, quote.owed(), "quote not conserved: {quote:?}");
+}
+
+fn reserved(state: &Market, side: Side, orders: &[Order]) -> u64 {
+    orders
+        .iter()
+        .map(|order| state.reserved_amount(side, order).unwrap())
+        .sum()
+}
+
+// (base, quote) the crank will credit when it settles `event`
+fn unsettled(state: &Market, event: &FillEvent) -> (u64, u64) {
+    match (event.kind, event.maker_side) {
+        (FILL_EVENT_KIND_FILL, 0) => (state.base_amount(event.quantity).unwrap(), 0),
+        (FILL_EVENT_KIND_FILL, _) => (0, state.quote_amount(event.price, event.quantity).unwrap()),
+        (FILL_EVENT_KIND_REFUND, 0) => (0, event.quantity),
+        (FILL_EVENT_KIND_REFUND, _) => (event.quantity, 0),
+        // Crosses settle both sides when submitted
+        _ => (0, 0),
+    }
+}
+
+// Token account amount, at the same offset under both token programs
+fn token_amount(market: &MarketFixture, address: &Pubkey) -> u64 {
+    market
+        .account_data(address)
+        .map(|data| u64::from_le_bytes(data[64..72].try_into().unwrap()))
+        .unwrap_or(0)
+}
+
+fn zero_copy<T: bytemuck::Pod>(market: &MarketFixture, address: &Pubkey) -> Option<T> {
+    let data = market.account_data(address)?;
+    Some(bytemuck::pod_read_unaligned(
+        &data[8..8 + std::mem::size_of::<T>()],
+    ))
+}
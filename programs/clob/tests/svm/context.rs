@@ -1,20 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
-use litesvm::{types::TransactionResult, LiteSVM};
+use anchor_lang::Discriminator;
+use clob::errors::ErrorCode;
+use litesvm::types::TransactionResult;
 use solana_sdk::{
-    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair,
-    signer::Signer, transaction::Transaction,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
 };
 
+use super::{backend::SvmBackend, events::parse_events};
+
 pub struct SvmContext {
-    pub svm: LiteSVM,
+    pub svm: Box<dyn SvmBackend>,
     pub payer: Keypair,
 }
 
 impl SvmContext {
     pub fn new() -> Self {
-        let mut svm = LiteSVM::new();
-        let payer = gen_and_fund_key(&mut svm);
+        let mut svm = crate::BACKEND.start();
+        let payer = gen_and_fund_key(svm.as_mut());
         Self { svm, payer }
     }
 
@@ -37,21 +45,44 @@ impl SvmContext {
         self.svm.send_transaction(tx)
     }
 
+    /// Like `submit_transaction`, but never loses the metadata of a failed
+    /// transaction, so callers can inspect logs and compute units either way
+    pub fn submit_transaction_with_meta(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionOutcome {
+        self.submit_transaction(ixs, signers).into()
+    }
+
     pub fn minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
         self.svm.minimum_balance_for_rent_exemption(data_len)
     }
 
     pub fn clock(&self) -> Clock {
-        self.svm.get_sysvar::<Clock>()
+        self.svm.clock()
     }
 
     pub fn set_clock(&mut self, unix_timestamp: i64) {
-        let clock = self.svm.get_sysvar::<Clock>();
+        let clock = self.svm.clock();
         let new_clock = Clock {
             unix_timestamp,
             ..clock
         };
-        self.svm.set_sysvar(&new_clock);
+        self.svm.set_clock(&new_clock);
+    }
+
+    /// Move to the next slot, e.g. to reset per-slot limits
+    pub fn advance_slot(&mut self) {
+        let slot = self.svm.clock().slot;
+        self.svm.warp_to_slot(slot + 1);
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| account.lamports)
+            .unwrap_or(0)
     }
 
     pub fn load_and_deserialize<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
@@ -60,13 +91,77 @@ impl SvmContext {
     }
 
     pub fn gen_and_fund_key(&mut self) -> Keypair {
-        gen_and_fund_key(&mut self.svm)
+        gen_and_fund_key(self.svm.as_mut())
+    }
+}
+
+/// Result and metadata of a submitted transaction, successful or not
+#[derive(Debug, Clone)]
+pub struct TransactionOutcome {
+    pub success: bool,
+    pub error: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub compute_units_consumed: u64,
+    pub return_data: Vec<u8>,
+}
+
+impl TransactionOutcome {
+    /// Decoded events of type `T`, in emission order
+    pub fn events<T: AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        parse_events(&self.logs)
+    }
+
+    /// Decoded return data of the last instruction that set any
+    pub fn return_value<T: AnchorDeserialize>(&self) -> T {
+        T::try_from_slice(&self.return_data).unwrap()
+    }
+
+    pub fn logs_contain(&self, needle: &str) -> bool {
+        self.logs.iter().any(|log| log.contains(needle))
+    }
+
+    /// The program's ErrorCode, if the transaction failed with one
+    pub fn clob_error(&self) -> Option<ErrorCode> {
+        match &self.error {
+            Some(TransactionError::InstructionError(_, error)) => {
+                ErrorCode::from_instruction_error(error)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Assert the transaction failed with exactly `expected`
+#[track_caller]
+pub fn assert_clob_error(outcome: impl Into<TransactionOutcome>, expected: ErrorCode) {
+    let outcome = outcome.into();
+    assert_eq!(
+        outcome.clob_error().map(|error| error.variant_name()),
+        Some(expected.variant_name()),
+        "logs: {:#?}",
+        outcome.logs
+    );
+}
+
+impl From<TransactionResult> for TransactionOutcome {
+    fn from(result: TransactionResult) -> Self {
+        let (meta, error) = match result {
+            Ok(meta) => (meta, None),
+            Err(failed) => (failed.meta, Some(failed.err)),
+        };
+        Self {
+            success: error.is_none(),
+            error,
+            logs: meta.logs,
+            compute_units_consumed: meta.compute_units_consumed,
+            return_data: meta.return_data.data,
+        }
     }
 }
 
-pub fn gen_and_fund_key(svm: &mut LiteSVM) -> Keypair {
+pub fn gen_and_fund_key(svm: &mut dyn SvmBackend) -> Keypair {
     let keypair = Keypair::new();
     let pubkey = keypair.pubkey();
-    svm.airdrop(&pubkey, 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&pubkey, 10 * LAMPORTS_PER_SOL);
     keypair
 }
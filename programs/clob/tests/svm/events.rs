@@ -0,0 +1,26 @@
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Raw `emit!` payloads (discriminator + borsh data) in emission order
+pub fn event_data(logs: &[String]) -> Vec<Vec<u8>> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix(PROGRAM_DATA))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .collect()
+}
+
+/// Whether an `emit!` payload is an event of type `T`
+pub fn is_event<T: Discriminator>(data: &[u8]) -> bool {
+    data.starts_with(T::DISCRIMINATOR)
+}
+
+/// Decode every event of type `T` from transaction logs, in emission order
+pub fn parse_events<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<T> {
+    event_data(logs)
+        .iter()
+        .filter(|data| is_event::<T>(data))
+        .map(|data| T::deserialize(&mut &data[T::DISCRIMINATOR.len()..]).unwrap())
+        .collect()
+}
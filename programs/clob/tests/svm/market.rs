@@ -2,13 +2,17 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::InstructionData;
 use clob::instructions::*;
-use clob::state::{orderbook::OrderBook, Side};
+use clob::state::{
+    orderbook::OrderBook, AskOrderBook, AskSide, BidOrderBook, BidSide, CancelIntent, EventQueue,
+    OrderIntent, SelfTradeBehavior, Side, TieBreakMode,
+};
 use litesvm::types::TransactionResult;
+use solana_sdk::ed25519_instruction::new_ed25519_instruction_with_signature;
 use solana_sdk::signature::{Keypair, Signer};
 use solana_sdk::system_instruction::create_account;
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
-use super::{spl::MintFixture, SvmContext};
+use super::{spl::MintFixture, SvmContext, TransactionOutcome};
 
 pub struct MarketFixture {
     ctx: Rc<RefCell<SvmContext>>,
@@ -20,6 +24,10 @@ pub struct MarketFixture {
     pub bids: Pubkey,
     pub asks: Pubkey,
     pub event_queue: Pubkey,
+    event_queue_shards: RefCell<Vec<Pubkey>>, // Added shards 1.., in order
+    pub bids_keypair: Keypair,
+    pub base_token_program: Pubkey,
+    pub quote_token_program: Pubkey,
 }
 
 impl MarketFixture {
@@ -28,6 +36,44 @@ impl MarketFixture {
         base_mint: &MintFixture,
         quote_mint: &MintFixture,
     ) -> Self {
+        Self::new_with_params(ctx, base_mint, quote_mint, None, |_| {}).await
+    }
+
+    /// Create a market whose book and queue accounts are funded by `rent_payer`
+    /// (defaults to the authority) with `configure` applied to the default params
+    pub async fn new_with_params(
+        ctx: Rc<RefCell<SvmContext>>,
+        base_mint: &MintFixture,
+        quote_mint: &MintFixture,
+        rent_payer: Option<&Keypair>,
+        configure: impl FnOnce(&mut InitializeParams),
+    ) -> Self {
+        let bids_size = 8 + std::mem::size_of::<clob::state::BidSide>();
+        let (fixture, result) =
+            Self::create(ctx, base_mint, quote_mint, rent_payer, configure, bids_size);
+        result.expect("Failed to initialize market");
+        fixture
+    }
+
+    /// Run Initialize with a bids account of `bids_size` bytes, for checking
+    /// how it treats a misconfigured book
+    pub async fn initialize_with_bids_size(
+        ctx: Rc<RefCell<SvmContext>>,
+        base_mint: &MintFixture,
+        quote_mint: &MintFixture,
+        bids_size: usize,
+    ) -> TransactionResult {
+        Self::create(ctx, base_mint, quote_mint, None, |_| {}, bids_size).1
+    }
+
+    fn create(
+        ctx: Rc<RefCell<SvmContext>>,
+        base_mint: &MintFixture,
+        quote_mint: &MintFixture,
+        rent_payer: Option<&Keypair>,
+        configure: impl FnOnce(&mut InitializeParams),
+        bids_size: usize,
+    ) -> (Self, TransactionResult) {
         let ctx_ref = ctx.clone();
         let mut ctx = ctx.borrow_mut();
 
@@ -40,13 +86,13 @@ impl MarketFixture {
         let (quote_vault, _) = get_vault_pda(&market, &quote_mint.mint);
 
         let authority = ctx.payer.pubkey();
+        let funder = rent_payer.map(|k| k.pubkey()).unwrap_or(authority);
 
         // Step 1: Create bids, asks, and event_queue accounts manually using fresh keypairs
         let bids_keypair = Keypair::new();
         let asks_keypair = Keypair::new();
         let event_queue_keypair = Keypair::new();
 
-        let bids_size = 8 + std::mem::size_of::<clob::state::BidSide>();
         let asks_size = 8 + std::mem::size_of::<clob::state::AskSide>();
         let event_queue_size = 8 + std::mem::size_of::<clob::state::EventQueue>();
 
@@ -55,7 +101,7 @@ impl MarketFixture {
         let event_queue_rent = ctx.minimum_balance_for_rent_exemption(event_queue_size);
 
         let create_bids_ix = create_account(
-            &authority,
+            &funder,
             &bids_keypair.pubkey(),
             bids_rent,
             bids_size as u64,
@@ -63,7 +109,7 @@ impl MarketFixture {
         );
 
         let create_asks_ix = create_account(
-            &authority,
+            &funder,
             &asks_keypair.pubkey(),
             asks_rent,
             asks_size as u64,
@@ -71,16 +117,18 @@ impl MarketFixture {
         );
 
         let create_event_queue_ix = create_account(
-            &authority,
+            &funder,
             &event_queue_keypair.pubkey(),
             event_queue_rent,
             event_queue_size as u64,
             &clob::ID,
         );
 
+        let mut signers = vec![&bids_keypair, &asks_keypair, &event_queue_keypair];
+        signers.extend(rent_payer);
         ctx.submit_transaction(
             &[create_bids_ix, create_asks_ix, create_event_queue_ix],
-            &[&bids_keypair, &asks_keypair, &event_queue_keypair],
+            &signers,
         )
         .expect("Failed to create orderbook accounts");
 
@@ -90,6 +138,23 @@ impl MarketFixture {
         let event_queue = event_queue_keypair.pubkey();
 
         // Step 2: Initialize market (with order books)
+        let mut params = InitializeParams {
+            base_mint: base_mint.mint,
+            quote_mint: quote_mint.mint,
+            base_lot_size: 1_000_000, // 1.0 base token
+            quote_tick_size: 1_000,   // 0.001 quote token
+            rent_payer: funder,
+            tie_break_mode: TieBreakMode::Time,
+            max_orders_per_slot_per_user: 0,
+            allow_locked_book: false,
+            order_bond_lamports: 0,
+            max_event_age_secs: 0,
+            max_orders_per_level: 0,
+            maker_fee_bps: 0,
+            taker_fee_bps: 0,
+        };
+        configure(&mut params);
+
         let init_ix = Instruction {
             program_id: clob::ID,
             accounts: clob::accounts::Initialize {
@@ -102,26 +167,17 @@ impl MarketFixture {
                 bids,
                 asks,
                 event_queue,
-                base_token_program: anchor_spl::token::ID,
-                quote_token_program: anchor_spl::token::ID,
+                base_token_program: base_mint.token_program,
+                quote_token_program: quote_mint.token_program,
                 system_program: solana_sdk::system_program::ID,
             }
             .to_account_metas(None),
-            data: clob::instruction::Initialize {
-                params: InitializeParams {
-                    base_mint: base_mint.mint,
-                    quote_mint: quote_mint.mint,
-                    base_lot_size: 1_000_000, // 1.0 base token
-                    quote_tick_size: 1_000,   // 0.001 quote token
-                },
-            }
-            .data(),
+            data: clob::instruction::Initialize { params }.data(),
         };
 
-        ctx.submit_transaction(&[init_ix], &[])
-            .expect("Failed to initialize market");
+        let result = ctx.submit_transaction(&[init_ix], &[]);
 
-        Self {
+        let fixture = Self {
             ctx: ctx_ref,
             market,
             base_mint: base_mint.mint,
@@ -131,9 +187,43 @@ impl MarketFixture {
             bids,
             asks,
             event_queue,
+            event_queue_shards: RefCell::new(Vec::new()),
+            bids_keypair,
+            base_token_program: base_mint.token_program,
+            quote_token_program: quote_mint.token_program,
+        };
+        (fixture, result)
+    }
+
+    fn token_program_for(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.base_mint {
+            self.base_token_program
+        } else {
+            self.quote_token_program
         }
     }
 
+    pub async fn harvest_withheld_fees(&self) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::HarvestWithheldFees {
+                market: self.market,
+                base_mint: self.base_mint,
+                quote_mint: self.quote_mint,
+                base_vault: self.base_vault,
+                quote_vault: self.quote_vault,
+                base_token_program: self.base_token_program,
+                quote_token_program: self.quote_token_program,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::HarvestWithheldFees {}.data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[])
+    }
+
     pub async fn deposit(
         &self,
         user: &Keypair,
@@ -141,20 +231,62 @@ impl MarketFixture {
         user_token_account: Pubkey,
         amount: u64,
     ) -> TransactionResult {
-        let mut ctx = self.ctx.borrow_mut();
+        self.deposit_for(user, &user.pubkey(), mint, user_token_account, amount)
+            .await
+    }
 
-        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+    /// Deposit signed by `authority` (the token account's owner or its
+    /// approved delegate), credited to `owner`'s balance
+    pub async fn deposit_for(
+        &self,
+        authority: &Keypair,
+        owner: &Pubkey,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        amount: u64,
+    ) -> TransactionResult {
+        let ix = self.deposit_ix(authority, owner, mint, user_token_account, amount);
+        self.ctx
+            .borrow_mut()
+            .submit_transaction(&[ix], &[authority])
+    }
+
+    /// `deposit_for` that keeps the metadata, for the events it emitted
+    pub async fn deposit_for_meta(
+        &self,
+        authority: &Keypair,
+        owner: &Pubkey,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        amount: u64,
+    ) -> TransactionOutcome {
+        let ix = self.deposit_ix(authority, owner, mint, user_token_account, amount);
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[authority])
+    }
+
+    fn deposit_ix(
+        &self,
+        authority: &Keypair,
+        owner: &Pubkey,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let (user_balance_pda, _) = get_user_balance_pda(owner, &self.market);
         let (vault_token_account, _) = get_vault_pda(&self.market, &mint);
-        let ix = Instruction {
+        Instruction {
             program_id: clob::ID,
             accounts: clob::accounts::Deposit {
-                user: user.pubkey(),
+                user: authority.pubkey(),
+                owner: *owner,
                 market: self.market,
                 user_balance: user_balance_pda,
                 user_token_account,
                 vault_token_account,
                 mint,
-                token_program: anchor_spl::token::ID,
+                token_program: self.token_program_for(&mint),
                 system_program: solana_sdk::system_program::ID,
             }
             .to_account_metas(None),
@@ -162,9 +294,7 @@ impl MarketFixture {
                 params: DepositParams { amount },
             }
             .data(),
-        };
-
-        ctx.submit_transaction(&[ix], &[user])
+        }
     }
 
     pub async fn withdraw(
@@ -174,11 +304,35 @@ impl MarketFixture {
         user_token_account: Pubkey,
         amount: u64,
     ) -> TransactionResult {
-        let mut ctx = self.ctx.borrow_mut();
+        let ix = self.withdraw_ix(user, mint, user_token_account, amount);
+        self.ctx.borrow_mut().submit_transaction(&[ix], &[user])
+    }
+
+    /// `withdraw` that keeps the metadata, whose return value is the free
+    /// balance left for `mint`
+    pub async fn withdraw_meta(
+        &self,
+        user: &Keypair,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        amount: u64,
+    ) -> TransactionOutcome {
+        let ix = self.withdraw_ix(user, mint, user_token_account, amount);
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
 
+    fn withdraw_ix(
+        &self,
+        user: &Keypair,
+        mint: Pubkey,
+        user_token_account: Pubkey,
+        amount: u64,
+    ) -> Instruction {
         let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
         let (vault_token_account, _) = get_vault_pda(&self.market, &mint);
-        let ix = Instruction {
+        Instruction {
             program_id: clob::ID,
             accounts: clob::accounts::Withdraw {
                 user: user.pubkey(),
@@ -187,16 +341,48 @@ impl MarketFixture {
                 user_token_account,
                 vault_token_account,
                 mint,
-                token_program: anchor_spl::token::ID,
+                token_program: self.token_program_for(&mint),
             }
             .to_account_metas(None),
             data: clob::instruction::Withdraw {
                 params: WithdrawParams { amount },
             }
             .data(),
+        }
+    }
+
+    /// Withdraw accrued fees in `mint` to `token_account`, signing as the
+    /// fixture authority unless another `authority` is given
+    pub async fn withdraw_fees(
+        &self,
+        authority: Option<&Keypair>,
+        mint: Pubkey,
+        token_account: Pubkey,
+        amount: u64,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+        let authority_key = authority.map_or(ctx.payer.pubkey(), |k| k.pubkey());
+        let (vault_token_account, _) = get_vault_pda(&self.market, &mint);
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::WithdrawFees {
+                authority: authority_key,
+                market: self.market,
+                authority_token_account: token_account,
+                vault_token_account,
+                mint,
+                token_program: self.token_program_for(&mint),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::WithdrawFees {
+                params: WithdrawFeesParams { amount },
+            }
+            .data(),
         };
 
-        ctx.submit_transaction(&[ix], &[user])
+        let signers: Vec<&Keypair> = authority.into_iter().collect();
+        ctx.submit_transaction(&[ix], &signers)
     }
 
     pub async fn close_user_balance(&self, user: &Keypair) -> TransactionResult {
@@ -218,6 +404,39 @@ impl MarketFixture {
         ctx.submit_transaction(&[ix], &[user])
     }
 
+    pub async fn migrate_user_balance(&self, user: &Keypair, owner: &Pubkey) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(owner, &self.market);
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::MigrateUserBalance {
+                user: user.pubkey(),
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::MigrateUserBalance {}.data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    /// Cut `user`'s UserBalance back to its first `len` bytes, funded at that
+    /// size's rent minimum, as an account written under an older layout
+    pub fn truncate_user_balance(&self, user: &Pubkey, len: usize) {
+        let mut ctx = self.ctx.borrow_mut();
+        let (user_balance_pda, _) = get_user_balance_pda(user, &self.market);
+        let mut account = ctx.svm.get_account(&user_balance_pda).unwrap();
+        account.data.truncate(len);
+        account.lamports = ctx.svm.minimum_balance_for_rent_exemption(len);
+        ctx.svm.set_account(user_balance_pda, account);
+    }
+
     pub async fn place_limit_order(
         &self,
         user: &Keypair,
@@ -229,6 +448,20 @@ impl MarketFixture {
             .await
     }
 
+    /// `place_limit_order` with the clock set to `unix_timestamp` first, so
+    /// the order rests with exactly that timestamp. The clock is left there.
+    pub async fn place_limit_order_at_time(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price: u64,
+        quantity: u64,
+        unix_timestamp: i64,
+    ) -> TransactionResult {
+        self.ctx.borrow_mut().set_clock(unix_timestamp);
+        self.place_limit_order(user, side, price, quantity).await
+    }
+
     pub async fn place_limit_order_with_tif(
         &self,
         user: &Keypair,
@@ -237,11 +470,256 @@ impl MarketFixture {
         quantity: u64,
         time_in_force: clob::state::TimeInForce,
     ) -> TransactionResult {
-        let mut ctx = self.ctx.borrow_mut();
+        let ix = self.place_limit_order_ix(
+            user,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force,
+                all_or_none: false,
+                session: false,
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id: 0,
+            },
+        );
+        self.ctx.borrow_mut().submit_transaction(&[ix], &[user])
+    }
+
+    /// `place_limit_order_with_tif` that keeps logs and compute units even
+    /// when the placement fails
+    pub async fn place_limit_order_meta(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price: u64,
+        quantity: u64,
+        time_in_force: clob::state::TimeInForce,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_ix(
+            user,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force,
+                all_or_none: false,
+                session: false,
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id: 0,
+            },
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    /// GTC placement tagged with the caller's own `client_order_id`
+    pub async fn place_limit_order_with_client_id(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price: u64,
+        quantity: u64,
+        client_order_id: u64,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_ix(
+            user,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force: clob::state::TimeInForce::GTC,
+                all_or_none: false,
+                session: false,
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id,
+            },
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    /// GTC placement with an explicit self-trade policy
+    pub async fn place_limit_order_self_trade(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price: u64,
+        quantity: u64,
+        self_trade: SelfTradeBehavior,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_ix(
+            user,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force: clob::state::TimeInForce::GTC,
+                all_or_none: false,
+                session: false,
+                self_trade,
+                client_order_id: 0,
+            },
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    pub async fn place_all_or_none_order(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> TransactionResult {
+        let ix = self.place_limit_order_ix(
+            user,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force: clob::state::TimeInForce::GTC,
+                all_or_none: true,
+                session: false,
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id: 0,
+            },
+        );
+        self.ctx.borrow_mut().submit_transaction(&[ix], &[user])
+    }
+
+    pub async fn modify_order(
+        &self,
+        user: &Keypair,
+        order_id: u64,
+        side: Side,
+        new_price: Option<u64>,
+        new_quantity: Option<u64>,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_accounts_ix(
+            user,
+            clob::instruction::ModifyOrder {
+                params: ModifyOrderParams {
+                    order_id,
+                    side,
+                    new_price,
+                    new_quantity,
+                },
+            }
+            .data(),
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    /// Sweep the opposite book for up to `quantity` lots at any price
+    pub async fn place_market_order(
+        &self,
+        user: &Keypair,
+        side: Side,
+        quantity: u64,
+        max_quote_to_spend: Option<u64>,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_accounts_ix(
+            user,
+            clob::instruction::PlaceMarketOrder {
+                params: PlaceMarketOrderParams {
+                    side,
+                    quantity,
+                    max_quote_to_spend,
+                },
+            }
+            .data(),
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    /// Place with price in quote tokens per lot and quantity in base tokens
+    pub async fn place_limit_order_native(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price_native_quote_per_lot: u64,
+        quantity_native_base: u64,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_accounts_ix(
+            user,
+            clob::instruction::PlaceLimitOrderNative {
+                params: PlaceLimitOrderNativeParams {
+                    side,
+                    price_native_quote_per_lot,
+                    quantity_native_base,
+                    time_in_force: clob::state::TimeInForce::GTC,
+                    all_or_none: false,
+                    session: false,
+                    self_trade: SelfTradeBehavior::default(),
+                    client_order_id: 0,
+                },
+            }
+            .data(),
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    fn place_limit_order_ix(&self, user: &Keypair, params: PlaceLimitOrderParams) -> Instruction {
+        self.place_limit_order_accounts_ix(
+            user,
+            clob::instruction::PlaceLimitOrder { params }.data(),
+        )
+    }
 
+    fn place_limit_order_accounts_ix(&self, user: &Keypair, data: Vec<u8>) -> Instruction {
         let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+        self.place_limit_order_accounts_ix_with(user, user_balance_pda, data)
+    }
 
-        let ix = Instruction {
+    /// Place on this market but pass `user_balance` as the balance account,
+    /// for tests that try to pair the book with some other balance
+    pub async fn place_limit_order_with_user_balance(
+        &self,
+        user: &Keypair,
+        user_balance: Pubkey,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> TransactionOutcome {
+        let ix = self.place_limit_order_accounts_ix_with(
+            user,
+            user_balance,
+            clob::instruction::PlaceLimitOrder {
+                params: PlaceLimitOrderParams {
+                    side,
+                    price,
+                    quantity,
+                    time_in_force: clob::state::TimeInForce::GTC,
+                    all_or_none: false,
+                    session: false,
+                    self_trade: SelfTradeBehavior::default(),
+                    client_order_id: 0,
+                },
+            }
+            .data(),
+        );
+        self.ctx
+            .borrow_mut()
+            .submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    fn place_limit_order_accounts_ix_with(
+        &self,
+        user: &Keypair,
+        user_balance_pda: Pubkey,
+        data: Vec<u8>,
+    ) -> Instruction {
+        Instruction {
             program_id: clob::ID,
             accounts: clob::accounts::PlaceLimitOrder {
                 market: self.market,
@@ -252,54 +730,618 @@ impl MarketFixture {
                 base_vault: self.base_vault,
                 quote_vault: self.quote_vault,
                 user: user.pubkey(),
-                base_token_program: anchor_spl::token::ID,
-                quote_token_program: anchor_spl::token::ID,
+                base_token_program: self.base_token_program,
+                quote_token_program: self.quote_token_program,
+                system_program: solana_sdk::system_program::ID,
             }
             .to_account_metas(None),
-            data: clob::instruction::PlaceLimitOrder {
-                params: PlaceLimitOrderParams {
-                    side,
-                    price,
-                    quantity,
-                    time_in_force,
-                },
-            }
-            .data(),
-        };
+            data,
+        }
+        .with_event_queue_shards(self)
+    }
 
-        ctx.submit_transaction(&[ix], &[user])
+    /// Submit `intent` from `relayer`, signed by `owner` in a preceding
+    /// ed25519 instruction; `owner` does not sign the transaction
+    pub async fn place_signed_order(
+        &self,
+        relayer: &Keypair,
+        owner: &Keypair,
+        intent: OrderIntent,
+    ) -> TransactionOutcome {
+        let signature_ix = ed25519_signature_ix(owner, &intent.message());
+        self.place_signed_order_with(relayer, &owner.pubkey(), signature_ix, intent)
+            .await
     }
 
-    pub async fn cancel_order(
+    /// `place_signed_order` with a caller-built signature instruction
+    pub async fn place_signed_order_with(
         &self,
-        user: &Keypair,
-        order_id: u64,
-        side: Side,
-    ) -> TransactionResult {
+        relayer: &Keypair,
+        owner: &Pubkey,
+        signature_ix: Instruction,
+        intent: OrderIntent,
+    ) -> TransactionOutcome {
         let mut ctx = self.ctx.borrow_mut();
 
-        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
-
+        let (user_balance_pda, _) = get_user_balance_pda(owner, &self.market);
         let ix = Instruction {
             program_id: clob::ID,
-            accounts: clob::accounts::CancelOrder {
+            accounts: clob::accounts::PlaceSignedOrder {
+                relayer: relayer.pubkey(),
+                owner: *owner,
                 market: self.market,
                 bids: self.bids,
                 asks: self.asks,
+                event_queue: self.event_queue,
                 user_balance: user_balance_pda,
-                user: user.pubkey(),
+                instructions: solana_sdk::sysvar::instructions::ID,
+                system_program: solana_sdk::system_program::ID,
             }
             .to_account_metas(None),
-            data: clob::instruction::CancelOrder {
-                params: CancelOrderParams { order_id, side },
+            data: clob::instruction::PlaceSignedOrder {
+                params: PlaceSignedOrderParams { intent },
             }
             .data(),
-        };
+        }
+        .with_event_queue_shards(self);
 
-        ctx.submit_transaction(&[ix], &[user])
+        ctx.submit_transaction_with_meta(&[signature_ix, ix], &[relayer])
     }
 
-    pub async fn consume_events(&self, limit: u8, maker_users: &[&Keypair]) -> TransactionResult {
+    pub async fn cancel_signed_order(
+        &self,
+        relayer: &Keypair,
+        owner: &Keypair,
+        intent: CancelIntent,
+    ) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let signature_ix = ed25519_signature_ix(owner, &intent.message());
+        let (user_balance_pda, _) = get_user_balance_pda(&owner.pubkey(), &self.market);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CancelSignedOrder {
+                relayer: relayer.pubkey(),
+                owner: owner.pubkey(),
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                instructions: solana_sdk::sysvar::instructions::ID,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CancelSignedOrder {
+                params: CancelSignedOrderParams { intent },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[signature_ix, ix], &[relayer])
+    }
+
+    pub async fn place_twap_order(
+        &self,
+        owner: &Keypair,
+        params: PlaceTwapOrderParams,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&owner.pubkey(), &self.market);
+        let (twap_order, _) = get_twap_order_pda(&self.market, &owner.pubkey(), params.twap_id);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::PlaceTwapOrder {
+                market: self.market,
+                twap_order,
+                user_balance: user_balance_pda,
+                owner: owner.pubkey(),
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::PlaceTwapOrder { params }.data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[owner])
+    }
+
+    /// Send `owner`'s TWAP `twap_id`'s next slice, signed by `executor`
+    pub async fn execute_twap_slice(
+        &self,
+        executor: &Keypair,
+        owner: &Pubkey,
+        twap_id: u64,
+    ) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(owner, &self.market);
+        let (twap_order, _) = get_twap_order_pda(&self.market, owner, twap_id);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::ExecuteTwapSlice {
+                executor: executor.pubkey(),
+                twap_order,
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                event_queue: self.event_queue,
+                user_balance: user_balance_pda,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::ExecuteTwapSlice {}.data(),
+        }
+        .with_event_queue_shards(self);
+
+        ctx.submit_transaction_with_meta(&[ix], &[executor])
+    }
+
+    pub async fn close_twap_order(&self, owner: &Keypair, twap_id: u64) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (twap_order, _) = get_twap_order_pda(&self.market, &owner.pubkey(), twap_id);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CloseTwapOrder {
+                twap_order,
+                owner: owner.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CloseTwapOrder {}.data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[owner])
+    }
+
+    pub fn get_twap_order(&self, owner: &Pubkey, twap_id: u64) -> clob::state::TwapOrder {
+        let (twap_order, _) = get_twap_order_pda(&self.market, owner, twap_id);
+        self.ctx.borrow().load_and_deserialize(&twap_order)
+    }
+
+    pub async fn query_next_order_id(&self) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::QueryNextOrderId {
+                market: self.market,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::QueryNextOrderId {}.data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[])
+    }
+
+    pub async fn query_price_levels(&self, side: Side, after_price: u64) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::QueryPriceLevels {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::QueryPriceLevels {
+                params: QueryPriceLevelsParams { side, after_price },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[])
+    }
+
+    pub async fn query_total_events_pushed(&self, event_queue: Pubkey) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::QueryTotalEventsPushed {
+                market: self.market,
+                event_queue,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::QueryTotalEventsPushed {}.data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[])
+    }
+
+    pub async fn open_trade_cursor(&self, owner: &Keypair) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::OpenTradeCursor {
+                market: self.market,
+                event_queue: self.event_queue,
+                trade_cursor: get_trade_cursor_pda(&self.event_queue, &owner.pubkey()).0,
+                owner: owner.pubkey(),
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::OpenTradeCursor {}.data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[owner])
+    }
+
+    pub async fn read_trades(&self, owner: &Keypair, max_trades: u8) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::ReadTrades {
+                event_queue: self.event_queue,
+                trade_cursor: get_trade_cursor_pda(&self.event_queue, &owner.pubkey()).0,
+                owner: owner.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::ReadTrades {
+                params: ReadTradesParams { max_trades },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[owner])
+    }
+
+    pub async fn quote_order(
+        &self,
+        side: Side,
+        price: u64,
+        quantity: u64,
+        quote_budget: Option<u64>,
+    ) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::QuoteOrder {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::QuoteOrder {
+                params: QuoteOrderParams {
+                    side,
+                    price,
+                    quantity,
+                    quote_budget,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[])
+    }
+
+    pub async fn swap_exact_in(
+        &self,
+        user: &Keypair,
+        user_base_account: Pubkey,
+        user_quote_account: Pubkey,
+        side: Side,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::SwapExactIn {
+                user: user.pubkey(),
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                event_queue: self.event_queue,
+                base_vault: self.base_vault,
+                quote_vault: self.quote_vault,
+                user_base_account,
+                user_quote_account,
+                base_mint: self.base_mint,
+                quote_mint: self.quote_mint,
+                base_token_program: self.base_token_program,
+                quote_token_program: self.quote_token_program,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::SwapExactIn {
+                params: SwapExactInParams {
+                    side,
+                    amount_in,
+                    min_amount_out,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    pub async fn cancel_order(
+        &self,
+        user: &Keypair,
+        order_id: u64,
+        side: Side,
+    ) -> TransactionResult {
+        self.submit_cancel_order(user, order_id, side, None).await
+    }
+
+    pub async fn cancel_order_by_client_id(
+        &self,
+        user: &Keypair,
+        client_order_id: u64,
+        side: Side,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CancelOrder {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                user: user.pubkey(),
+                vault: None,
+                wallet: None,
+                mint: None,
+                token_program: None,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CancelOrderByClientId {
+                params: CancelOrderByClientIdParams {
+                    client_order_id,
+                    side,
+                    refund_to_wallet: false,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[user])
+    }
+
+    /// Cancel up to `limit` of `user`'s orders; the return value is how many
+    /// are left
+    pub async fn cancel_all_my_orders(&self, user: &Keypair, limit: u8) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CancelAllMyOrders {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                user: user.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CancelAllMyOrders {
+                params: CancelAllMyOrdersParams { limit },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    pub async fn register_session(&self, user: &Keypair, expiry_ts: i64) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::RegisterSession {
+                market: self.market,
+                user_balance: user_balance_pda,
+                user: user.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::RegisterSession {
+                params: RegisterSessionParams { expiry_ts },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[user])
+    }
+
+    pub async fn place_session_order(
+        &self,
+        user: &Keypair,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> TransactionResult {
+        let ix = self.place_limit_order_ix(
+            user,
+            PlaceLimitOrderParams {
+                side,
+                price,
+                quantity,
+                time_in_force: clob::state::TimeInForce::GTC,
+                all_or_none: false,
+                session: true,
+                self_trade: SelfTradeBehavior::default(),
+                client_order_id: 0,
+            },
+        );
+        self.ctx.borrow_mut().submit_transaction(&[ix], &[user])
+    }
+
+    /// Sweep up to `limit` of `owner`'s session orders as `caller`; the
+    /// return value is how many are left
+    pub async fn cancel_expired_session_orders(
+        &self,
+        caller: &Keypair,
+        owner: &Pubkey,
+        limit: u8,
+    ) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(owner, &self.market);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CancelExpiredSessionOrders {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                caller: caller.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CancelExpiredSessionOrders {
+                params: CancelExpiredSessionOrdersParams {
+                    owner: *owner,
+                    limit,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[caller])
+    }
+
+    /// Cancel and pay the reservation straight out to `wallet`, a token
+    /// account for the quote mint (bids) or base mint (asks)
+    pub async fn cancel_order_to_wallet(
+        &self,
+        user: &Keypair,
+        order_id: u64,
+        side: Side,
+        wallet: Pubkey,
+    ) -> TransactionResult {
+        self.submit_cancel_order(user, order_id, side, Some(wallet))
+            .await
+    }
+
+    async fn submit_cancel_order(
+        &self,
+        user: &Keypair,
+        order_id: u64,
+        side: Side,
+        wallet: Option<Pubkey>,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+        let (mint, vault) = match side {
+            Side::Bid => (self.quote_mint, self.quote_vault),
+            Side::Ask => (self.base_mint, self.base_vault),
+        };
+        let refund_to_wallet = wallet.is_some();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CancelOrder {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                user: user.pubkey(),
+                vault: wallet.map(|_| vault),
+                wallet,
+                mint: wallet.map(|_| mint),
+                token_program: wallet.map(|_| self.token_program_for(&mint)),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CancelOrder {
+                params: CancelOrderParams {
+                    order_id,
+                    side,
+                    refund_to_wallet,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[user])
+    }
+
+    pub async fn increase_order(
+        &self,
+        user: &Keypair,
+        order_id: u64,
+        side: Side,
+        additional_quantity: u64,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::IncreaseOrder {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                user: user.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::IncreaseOrder {
+                params: IncreaseOrderParams {
+                    order_id,
+                    side,
+                    additional_quantity,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[user])
+    }
+
+    /// Amend `user`'s order up, resetting its priority; the return value is
+    /// the id it rests under afterwards
+    pub async fn increase_order_size(
+        &self,
+        user: &Keypair,
+        order_id: u64,
+        additional_quantity: u64,
+    ) -> TransactionOutcome {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let (user_balance_pda, _) = get_user_balance_pda(&user.pubkey(), &self.market);
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::IncreaseOrderSize {
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                user_balance: user_balance_pda,
+                user: user.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::IncreaseOrderSize {
+                params: IncreaseOrderSizeParams {
+                    order_id,
+                    additional_quantity,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction_with_meta(&[ix], &[user])
+    }
+
+    pub async fn consume_events(&self, limit: u8, maker_users: &[&Keypair]) -> TransactionResult {
+        self.consume_events_on(self.event_queue, limit, maker_users)
+            .await
+    }
+
+    /// Crank one event queue shard of a sharded market
+    pub async fn consume_events_on(
+        &self,
+        event_queue: Pubkey,
+        limit: u8,
+        maker_users: &[&Keypair],
+    ) -> TransactionResult {
         let mut ctx = self.ctx.borrow_mut();
 
         // Collect maker user balance PDAs
@@ -311,22 +1353,400 @@ impl MarketFixture {
 
         let ix = Instruction {
             program_id: clob::ID,
-            accounts: clob::accounts::ConsumeEvents {
+            accounts: clob::accounts::ConsumeEvents {
+                market: self.market,
+                event_queue,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::ConsumeEvents {
+                params: ConsumeEventsParams { limit },
+            }
+            .data(),
+        };
+
+        // Append remaining accounts for maker balance updates
+        let mut final_ix = ix;
+        final_ix.accounts.extend(remaining_accounts);
+
+        ctx.submit_transaction(&[final_ix], &[])
+    }
+
+    /// Close the market's book and queue accounts, signing as the fixture
+    /// authority unless another `authority` is given
+    pub async fn begin_market_close(
+        &self,
+        authority: Option<&Keypair>,
+        grace_period: i64,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+        let authority_key = authority.map_or(ctx.payer.pubkey(), |k| k.pubkey());
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::BeginMarketClose {
+                authority: authority_key,
+                market: self.market,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::BeginMarketClose {
+                params: BeginMarketCloseParams { grace_period },
+            }
+            .data(),
+        };
+
+        let signers: Vec<&Keypair> = authority.into_iter().collect();
+        ctx.submit_transaction(&[ix], &signers)
+    }
+
+    pub async fn set_market_flags(
+        &self,
+        authority: Option<&Keypair>,
+        flags: u8,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+        let authority_key = authority.map_or(ctx.payer.pubkey(), |k| k.pubkey());
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::SetMarketFlags {
+                authority: authority_key,
+                market: self.market,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::SetMarketFlags {
+                params: SetMarketFlagsParams { flags },
+            }
+            .data(),
+        };
+
+        let signers: Vec<&Keypair> = authority.into_iter().collect();
+        ctx.submit_transaction(&[ix], &signers)
+    }
+
+    pub async fn settle_market(&self, cranker: &Keypair) -> TransactionResult {
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::SettleMarket {
+                cranker: cranker.pubkey(),
+                market: self.market,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::SettleMarket {}.data(),
+        };
+        self.ctx.borrow_mut().submit_transaction(&[ix], &[cranker])
+    }
+
+    pub async fn set_pause_flags(
+        &self,
+        authority: Option<&Keypair>,
+        deposits_paused: bool,
+        withdrawals_paused: bool,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+        let authority_key = authority.map_or(ctx.payer.pubkey(), |k| k.pubkey());
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::SetPauseFlags {
+                authority: authority_key,
+                market: self.market,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::SetPauseFlags {
+                params: SetPauseFlagsParams {
+                    deposits_paused,
+                    withdrawals_paused,
+                },
+            }
+            .data(),
+        };
+
+        let signers: Vec<&Keypair> = authority.into_iter().collect();
+        ctx.submit_transaction(&[ix], &signers)
+    }
+
+    /// Cancel `owner`'s order as `authority` (the market authority if None),
+    /// crediting the refund to `recipient`'s balance
+    pub async fn cancel_and_credit(
+        &self,
+        authority: Option<&Keypair>,
+        owner: &Pubkey,
+        order_id: u64,
+        side: Side,
+        recipient: &Pubkey,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+        let authority_key = authority.map_or(ctx.payer.pubkey(), |k| k.pubkey());
+        let (owner_balance, _) = get_user_balance_pda(owner, &self.market);
+        let (recipient_balance, _) = get_user_balance_pda(recipient, &self.market);
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CancelAndCredit {
+                authority: authority_key,
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                owner_balance,
+                recipient_balance,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CancelAndCredit {
+                params: CancelAndCreditParams {
+                    owner: *owner,
+                    order_id,
+                    side,
+                },
+            }
+            .data(),
+        };
+
+        let signers: Vec<&Keypair> = authority.into_iter().collect();
+        ctx.submit_transaction(&[ix], &signers)
+    }
+
+    pub async fn force_cancel_all(&self, limit: u8, owners: &[&Keypair]) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let mut ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::ForceCancelAll {
+                authority: ctx.payer.pubkey(),
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::ForceCancelAll {
+                params: ForceCancelAllParams { limit },
+            }
+            .data(),
+        };
+        for owner in owners {
+            let (user_balance_pda, _) = get_user_balance_pda(&owner.pubkey(), &self.market);
+            ix.accounts.push(AccountMeta::new(user_balance_pda, false));
+        }
+
+        ctx.submit_transaction(&[ix], &[])
+    }
+
+    /// Clear up to `limit` resting orders as the market authority
+    pub async fn clear_book(&self, limit: u8) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::ClearBook {
+                authority: ctx.payer.pubkey(),
                 market: self.market,
+                bids: self.bids,
+                asks: self.asks,
                 event_queue: self.event_queue,
             }
             .to_account_metas(None),
-            data: clob::instruction::ConsumeEvents {
-                params: ConsumeEventsParams { limit },
+            data: clob::instruction::ClearBook {
+                params: ClearBookParams { limit },
             }
             .data(),
         };
 
-        // Append remaining accounts for maker balance updates
-        let mut final_ix = ix;
-        final_ix.accounts.extend(remaining_accounts);
+        ctx.submit_transaction(&[ix], &[])
+    }
 
-        ctx.submit_transaction(&[final_ix], &[])
+    /// Create and register another event queue shard, see
+    /// [`Self::event_queue_shard`]
+    pub async fn add_event_queue_shard(&self) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let shard_keypair = Keypair::new();
+        let size = 8 + std::mem::size_of::<clob::state::EventQueue>();
+        let create_ix = create_account(
+            &ctx.payer.pubkey(),
+            &shard_keypair.pubkey(),
+            ctx.minimum_balance_for_rent_exemption(size),
+            size as u64,
+            &clob::ID,
+        );
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::AddEventQueueShard {
+                authority: ctx.payer.pubkey(),
+                market: self.market,
+                event_queue: shard_keypair.pubkey(),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::AddEventQueueShard {}.data(),
+        };
+
+        let result = ctx.submit_transaction(&[create_ix, ix], &[&shard_keypair]);
+        if result.is_ok() {
+            self.event_queue_shards
+                .borrow_mut()
+                .push(shard_keypair.pubkey());
+        }
+        result
+    }
+
+    /// Event queue shard `shard` (0 is the market's own event_queue)
+    pub fn event_queue_shard(&self, shard: usize) -> Pubkey {
+        match shard {
+            0 => self.event_queue,
+            _ => self.event_queue_shards.borrow()[shard - 1],
+        }
+    }
+
+    pub async fn update_market_params(
+        &self,
+        base_lot_size: u64,
+        quote_tick_size: u64,
+    ) -> TransactionResult {
+        let order_bond_lamports = self.get_market().order_bond_lamports;
+        self.update_market_params_with_bond(base_lot_size, quote_tick_size, order_bond_lamports)
+            .await
+    }
+
+    pub async fn update_market_params_with_bond(
+        &self,
+        base_lot_size: u64,
+        quote_tick_size: u64,
+        order_bond_lamports: u64,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::UpdateMarketParams {
+                authority: ctx.payer.pubkey(),
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                event_queue: self.event_queue,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::UpdateMarketParams {
+                params: UpdateMarketParamsParams {
+                    base_lot_size,
+                    quote_tick_size,
+                    order_bond_lamports,
+                },
+            }
+            .data(),
+        }
+        .with_event_queue_shards(self);
+
+        ctx.submit_transaction(&[ix], &[])
+    }
+
+    /// Let `cross_authority` submit crosses, as the market authority
+    pub async fn set_cross_authority(
+        &self,
+        cross_authority: &Pubkey,
+        max_cross_trade_through: u64,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::SetCrossAuthority {
+                authority: ctx.payer.pubkey(),
+                market: self.market,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::SetCrossAuthority {
+                params: SetCrossAuthorityParams {
+                    cross_authority: *cross_authority,
+                    max_cross_trade_through,
+                },
+            }
+            .data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[])
+    }
+
+    /// Settle `crosses`, passing the UserBalance of each of `traders`
+    pub async fn submit_cross(
+        &self,
+        cross_authority: &Keypair,
+        crosses: Vec<CrossTrade>,
+        traders: &[&Keypair],
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let mut ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::SubmitCross {
+                cross_authority: cross_authority.pubkey(),
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                event_queue: self.event_queue,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::SubmitCross {
+                params: SubmitCrossParams { crosses },
+            }
+            .data(),
+        };
+        for trader in traders {
+            let (user_balance_pda, _) = get_user_balance_pda(&trader.pubkey(), &self.market);
+            ix.accounts.push(AccountMeta::new(user_balance_pda, false));
+        }
+
+        ctx.submit_transaction(&[ix], &[cross_authority])
+    }
+
+    pub async fn close_market(
+        &self,
+        authority: Option<&Keypair>,
+        rent_payer: Pubkey,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+        let authority_key = authority.map_or(ctx.payer.pubkey(), |k| k.pubkey());
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::CloseMarket {
+                authority: authority_key,
+                market: self.market,
+                bids: self.bids,
+                asks: self.asks,
+                event_queue: self.event_queue,
+                rent_payer,
+            }
+            .to_account_metas(None),
+            data: clob::instruction::CloseMarket {}.data(),
+        }
+        .with_event_queue_shards(self);
+
+        let signers: Vec<&Keypair> = authority.into_iter().collect();
+        ctx.submit_transaction(&[ix], &signers)
+    }
+
+    pub async fn reclaim_orphaned_account(
+        &self,
+        orphan: &Keypair,
+        recipient: Pubkey,
+        verify_market: bool,
+    ) -> TransactionResult {
+        let mut ctx = self.ctx.borrow_mut();
+
+        let ix = Instruction {
+            program_id: clob::ID,
+            accounts: clob::accounts::ReclaimOrphanedAccount {
+                orphan: orphan.pubkey(),
+                recipient,
+                market: verify_market.then_some(self.market),
+            }
+            .to_account_metas(None),
+            data: clob::instruction::ReclaimOrphanedAccount {}.data(),
+        };
+
+        ctx.submit_transaction(&[ix], &[orphan])
+    }
+
+    pub fn get_market(&self) -> clob::state::Market {
+        self.ctx.borrow().load_and_deserialize(&self.market)
     }
 
     pub fn get_user_balance(&self, user: &Pubkey) -> clob::state::UserBalance {
@@ -334,6 +1754,72 @@ impl MarketFixture {
         self.ctx.borrow().load_and_deserialize(&user_balance_pda)
     }
 
+    pub fn get_open_order_count(&self, user: &Pubkey) -> u32 {
+        self.get_user_balance(user).open_order_count
+    }
+
+    pub fn get_user_balance_data(&self, user: &Pubkey) -> Vec<u8> {
+        let (user_balance_pda, _) = get_user_balance_pda(user, &self.market);
+        self.ctx
+            .borrow()
+            .svm
+            .get_account(&user_balance_pda)
+            .unwrap()
+            .data
+    }
+
+    /// Raw data of `address`, or None once it is closed
+    pub fn account_data(&self, address: &Pubkey) -> Option<Vec<u8>> {
+        self.ctx
+            .borrow()
+            .svm
+            .get_account(address)
+            .filter(|account| account.lamports > 0)
+            .map(|account| account.data)
+    }
+
+    /// Borrow the bids book in place instead of copying the whole account into
+    /// an owned `BidSide`, for cheap repeated reads
+    pub fn with_bids<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&BidOrderBook) -> R,
+    {
+        self.with_zero_copy(&self.bids, |side: &BidSide| f(&side.orderbook))
+    }
+
+    /// Ask-side counterpart of [`Self::with_bids`]
+    pub fn with_asks<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&AskOrderBook) -> R,
+    {
+        self.with_zero_copy(&self.asks, |side: &AskSide| f(&side.orderbook))
+    }
+
+    fn with_zero_copy<T, F, R>(&self, address: &Pubkey, f: F) -> R
+    where
+        T: bytemuck::Pod,
+        F: FnOnce(&T) -> R,
+    {
+        let ctx = self.ctx.borrow();
+        let account = ctx.svm.get_account(address).unwrap();
+        // Skip the 8-byte discriminator; the remaining data stays 8-byte aligned
+        let data = &account.data[8..8 + std::mem::size_of::<T>()];
+        f(bytemuck::from_bytes(data))
+    }
+
+    /// Make the event queue look like `len` fills are waiting to be cranked,
+    /// without placing the orders that would produce them
+    pub fn set_event_queue_len(&self, len: u64) {
+        let mut ctx = self.ctx.borrow_mut();
+        let mut account = ctx.svm.get_account(&self.event_queue).unwrap();
+        let queue: &mut clob::state::EventQueue = bytemuck::from_bytes_mut(
+            &mut account.data[8..8 + std::mem::size_of::<clob::state::EventQueue>()],
+        );
+        queue.head = 0;
+        queue.tail = len;
+        ctx.svm.set_account(self.event_queue, account);
+    }
+
     pub fn get_bids_orderbook(&self) -> clob::state::BidSide {
         self.ctx.borrow().load_and_deserialize(&self.bids)
     }
@@ -343,19 +1829,37 @@ impl MarketFixture {
     }
 
     pub fn find_order_in_bids(&self, order_id: u64) -> Option<clob::state::Order> {
-        let bids = self.get_bids_orderbook();
-        bids.orderbook.find_order_by_id(order_id)
+        self.with_bids(|book| book.find_order_by_id(order_id))
     }
 
     pub fn find_order_in_asks(&self, order_id: u64) -> Option<clob::state::Order> {
-        let asks = self.get_asks_orderbook();
-        asks.orderbook.find_order_by_id(order_id)
+        self.with_asks(|book| book.find_order_by_id(order_id))
+    }
+
+    pub fn find_order_in_bids_by_client_id(
+        &self,
+        owner: &Pubkey,
+        client_order_id: u64,
+    ) -> Option<clob::state::Order> {
+        self.with_bids(|book| {
+            book.find(|order| order.owner == *owner && order.client_order_id == client_order_id)
+        })
+    }
+
+    pub fn find_order_in_asks_by_client_id(
+        &self,
+        owner: &Pubkey,
+        client_order_id: u64,
+    ) -> Option<clob::state::Order> {
+        self.with_asks(|book| {
+            book.find(|order| order.owner == *owner && order.client_order_id == client_order_id)
+        })
     }
 
     pub fn get_orderbook_order_count(&self, side: clob::state::Side) -> usize {
         match side {
-            clob::state::Side::Bid => self.get_bids_orderbook().orderbook.len(),
-            clob::state::Side::Ask => self.get_asks_orderbook().orderbook.len(),
+            clob::state::Side::Bid => self.with_bids(|book| book.len()),
+            clob::state::Side::Ask => self.with_asks(|book| book.len()),
         }
     }
 
@@ -363,6 +1867,104 @@ impl MarketFixture {
         self.get_orderbook_order_count(Side::Bid) == 0
             && self.get_orderbook_order_count(Side::Ask) == 0
     }
+
+    /// Aggregated `(price, quantity)` levels for `side`, best price first
+    pub fn get_l2(&self, side: Side) -> Vec<(u64, u64)> {
+        let mut levels = BTreeMap::new();
+        let orders = match side {
+            Side::Bid => self.with_bids(|book| book.orders()),
+            Side::Ask => self.with_asks(|book| book.orders()),
+        };
+        for order in orders {
+            *levels.entry(order.price).or_insert(0) += order.remaining_quantity;
+        }
+        match side {
+            Side::Bid => levels.into_iter().rev().collect(),
+            Side::Ask => levels.into_iter().collect(),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<u64> {
+        self.with_bids(|book| book.peek().map(|order| order.price))
+    }
+
+    pub fn best_ask(&self) -> Option<u64> {
+        self.with_asks(|book| book.peek().map(|order| order.price))
+    }
+
+    pub fn event_queue_len(&self) -> u64 {
+        self.event_queue_shard_len(0)
+    }
+
+    /// Events waiting in the event queue, head first
+    pub fn pending_events(&self) -> Vec<clob::state::FillEvent> {
+        self.with_zero_copy(&self.event_queue, |queue: &EventQueue| {
+            (0..queue.logical_len())
+                .map(|offset| queue.events[((queue.head + offset) % queue.capacity) as usize])
+                .collect()
+        })
+    }
+
+    /// Events waiting in event queue shard `shard`
+    pub fn event_queue_shard_len(&self, shard: usize) -> u64 {
+        self.with_zero_copy(&self.event_queue_shard(shard), |queue: &EventQueue| {
+            queue.logical_len()
+        })
+    }
+
+    /// Snapshot of both books and the event queue in one call
+    pub fn get_book_state(&self) -> BookState {
+        let best_bid = self.best_bid();
+        let best_ask = self.best_ask();
+        BookState {
+            bids: self.get_l2(Side::Bid),
+            asks: self.get_l2(Side::Ask),
+            best_bid,
+            best_ask,
+            spread: best_bid
+                .zip(best_ask)
+                .map(|(bid, ask)| ask.saturating_sub(bid)),
+            event_queue_len: self.event_queue_len(),
+            bid_count: self.get_orderbook_order_count(Side::Bid),
+            ask_count: self.get_orderbook_order_count(Side::Ask),
+        }
+    }
+}
+
+/// Everything a test usually asserts about a market's books, see
+/// [`MarketFixture::get_book_state`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookState {
+    pub bids: Vec<(u64, u64)>, // (price, quantity) levels, best first
+    pub asks: Vec<(u64, u64)>,
+    pub best_bid: Option<u64>,
+    pub best_ask: Option<u64>,
+    pub spread: Option<u64>, // Only when both sides have orders
+    pub event_queue_len: u64,
+    pub bid_count: usize,
+    pub ask_count: usize,
+}
+
+/// Appends a sharded market's extra event queues, which instructions that
+/// can fill makers take as their leading remaining accounts
+trait WithEventQueueShards {
+    fn with_event_queue_shards(self, market: &MarketFixture) -> Self;
+}
+
+impl WithEventQueueShards for Instruction {
+    fn with_event_queue_shards(mut self, market: &MarketFixture) -> Self {
+        for shard in market.event_queue_shards.borrow().iter() {
+            self.accounts.push(AccountMeta::new(*shard, false));
+        }
+        self
+    }
+}
+
+/// The ed25519 program instruction verifying `signer`'s signature over
+/// `message`, to precede a signed-intent instruction
+pub fn ed25519_signature_ix(signer: &Keypair, message: &[u8]) -> Instruction {
+    let signature: [u8; 64] = signer.sign_message(message).into();
+    new_ed25519_instruction_with_signature(message, &signature, &signer.pubkey().to_bytes())
 }
 
 pub fn get_user_balance_pda(user: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
@@ -372,6 +1974,25 @@ pub fn get_user_balance_pda(user: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
     )
 }
 
+pub fn get_twap_order_pda(market: &Pubkey, owner: &Pubkey, twap_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"twap",
+            market.as_ref(),
+            owner.as_ref(),
+            &twap_id.to_le_bytes(),
+        ],
+        &clob::ID,
+    )
+}
+
+pub fn get_trade_cursor_pda(event_queue: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"trade_cursor", event_queue.as_ref(), owner.as_ref()],
+        &clob::ID,
+    )
+}
+
 pub fn get_vault_pda(market: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[b"vault", market.as_ref(), mint.as_ref()], &clob::ID)
 }
@@ -1,7 +1,15 @@
+pub mod backend;
+pub mod conservation;
 pub mod context;
+pub mod events;
 pub mod market;
+pub mod order_builder;
 pub mod spl;
 pub mod test;
 
+pub use backend::Backend;
+pub use conservation::assert_conservation;
 pub use context::*;
+pub use events::*;
+pub use order_builder::order;
 pub use test::{TradingScenario, TradingUser, TwoUserScenario};
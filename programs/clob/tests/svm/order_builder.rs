@@ -0,0 +1,49 @@
+use anchor_lang::prelude::Pubkey;
+use clob::state::Order;
+
+/// Resting order for book-level tests: a fresh owner, timestamp equal to
+/// the id and nothing filled yet, unless set otherwise
+pub fn order(order_id: u64, price: u64, quantity: u64) -> OrderBuilder {
+    OrderBuilder(Order {
+        order_id,
+        owner: Pubkey::new_unique(),
+        price,
+        quantity,
+        remaining_quantity: quantity,
+        timestamp: order_id as i64,
+        ..Default::default()
+    })
+}
+
+pub struct OrderBuilder(Order);
+
+impl OrderBuilder {
+    pub fn owner(mut self, owner: Pubkey) -> Self {
+        self.0.owner = owner;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.0.timestamp = timestamp;
+        self
+    }
+
+    pub fn flags(mut self, flags: u8) -> Self {
+        self.0.flags = flags;
+        self
+    }
+
+    pub fn remaining_quantity(mut self, remaining_quantity: u64) -> Self {
+        self.0.remaining_quantity = remaining_quantity;
+        self
+    }
+
+    pub fn client_order_id(mut self, client_order_id: u64) -> Self {
+        self.0.client_order_id = client_order_id;
+        self
+    }
+
+    pub fn build(self) -> Order {
+        self.0
+    }
+}
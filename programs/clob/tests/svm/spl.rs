@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::{get_associated_token_address, spl_associated_token_account},
-    token::{spl_token, Mint, TokenAccount},
+    associated_token::{
+        get_associated_token_address_with_program_id, spl_associated_token_account,
+    },
+    token::{spl_token, Mint},
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+    },
+    token_interface::TokenAccount,
 };
 use solana_sdk::{signature::Keypair, signer::Signer, system_instruction::create_account};
 use std::{cell::RefCell, rc::Rc};
@@ -17,10 +24,13 @@ pub struct MintFixture {
 }
 
 impl MintFixture {
-    pub async fn new(
+    /// Extension-free mint owned by `token_program` (classic SPL Token or
+    /// Token-2022)
+    pub async fn new_with_program(
         ctx: Rc<RefCell<SvmContext>>,
         mint_keypair: Keypair,
         mint_decimals: u8,
+        token_program: Pubkey,
     ) -> Self {
         let ctx_ref = Rc::clone(&ctx);
         {
@@ -30,10 +40,11 @@ impl MintFixture {
                 &mint_keypair.pubkey(),
                 ctx.svm.minimum_balance_for_rent_exemption(Mint::LEN),
                 Mint::LEN as u64,
-                &spl_token::ID,
+                &token_program,
             );
+            // Token-2022 accepts the classic instruction layout
             let init_mint_ix = spl_token::instruction::initialize_mint(
-                &spl_token::ID,
+                &token_program,
                 &mint_keypair.pubkey(),
                 &ctx.payer.pubkey(),
                 None,
@@ -49,7 +60,65 @@ impl MintFixture {
             ctx: ctx_ref,
             mint: mint_keypair.pubkey(),
             decimals: mint_decimals,
-            token_program: spl_token::ID,
+            token_program,
+        }
+    }
+
+    /// Token-2022 mint with the TransferFee extension; the payer is both mint and
+    /// fee authority
+    pub async fn new_with_transfer_fee(
+        ctx: Rc<RefCell<SvmContext>>,
+        mint_keypair: Keypair,
+        mint_decimals: u8,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) -> Self {
+        let ctx_ref = Rc::clone(&ctx);
+        {
+            let mut ctx = ctx_ref.borrow_mut();
+            let payer = ctx.payer.pubkey();
+            let mint_len =
+                ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                    ExtensionType::TransferFeeConfig,
+                ])
+                .unwrap();
+            let init_account_ix = create_account(
+                &payer,
+                &mint_keypair.pubkey(),
+                ctx.svm.minimum_balance_for_rent_exemption(mint_len),
+                mint_len as u64,
+                &spl_token_2022::ID,
+            );
+            let init_fee_ix = initialize_transfer_fee_config(
+                &spl_token_2022::ID,
+                &mint_keypair.pubkey(),
+                Some(&payer),
+                Some(&payer),
+                transfer_fee_basis_points,
+                maximum_fee,
+            )
+            .unwrap();
+            let init_mint_ix = spl_token_2022::instruction::initialize_mint(
+                &spl_token_2022::ID,
+                &mint_keypair.pubkey(),
+                &payer,
+                None,
+                mint_decimals,
+            )
+            .unwrap();
+
+            ctx.submit_transaction(
+                &[init_account_ix, init_fee_ix, init_mint_ix],
+                &[&mint_keypair],
+            )
+            .unwrap();
+        }
+
+        MintFixture {
+            ctx: ctx_ref,
+            mint: mint_keypair.pubkey(),
+            decimals: mint_decimals,
+            token_program: spl_token_2022::ID,
         }
     }
 
@@ -62,7 +131,7 @@ impl MintFixture {
 
     // Get the Associated Token Account address for this mint and owner
     pub fn get_ata_address(&self, owner: &Pubkey) -> Pubkey {
-        get_associated_token_address(owner, &self.mint)
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
     }
 
     // Create an Associated Token Account for this mint
@@ -83,7 +152,7 @@ impl MintFixture {
                 &ctx.payer.pubkey(), // payer
                 owner,               // wallet
                 &self.mint,          // mint
-                &spl_token::ID,      // token program
+                &self.token_program, // token program
             );
 
         ctx.submit_transaction(&[create_ata_ix], &[]).unwrap();
@@ -95,8 +164,9 @@ impl MintFixture {
     pub async fn mint_to(&self, token_account: &Pubkey, amount: u64) {
         let mut ctx = self.ctx.borrow_mut();
 
-        let mint_to_ix = spl_token::instruction::mint_to(
-            &spl_token::ID,
+        // The Token-2022 builder accepts either token program id
+        let mint_to_ix = spl_token_2022::instruction::mint_to(
+            &self.token_program,
             &self.mint,
             token_account,
             &ctx.payer.pubkey(),
@@ -108,6 +178,30 @@ impl MintFixture {
         ctx.submit_transaction(&[mint_to_ix], &[]).unwrap();
     }
 
+    /// Let `delegate` move up to `amount` out of `owner`'s token account
+    pub async fn approve(
+        &self,
+        token_account: &Pubkey,
+        owner: &Keypair,
+        delegate: &Pubkey,
+        amount: u64,
+    ) {
+        let mut ctx = self.ctx.borrow_mut();
+
+        // As with mint_to, the Token-2022 builder accepts either program id
+        let approve_ix = spl_token_2022::instruction::approve(
+            &self.token_program,
+            token_account,
+            delegate,
+            &owner.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+
+        ctx.submit_transaction(&[approve_ix], &[owner]).unwrap();
+    }
+
     pub async fn create_and_mint(&self, owner: &Pubkey, amount: u64) -> Pubkey {
         let ata_address = self.create_token_account(owner).await;
         self.mint_to(&ata_address, amount).await;
@@ -1,5 +1,7 @@
-use super::{market::MarketFixture, spl::MintFixture, SvmContext};
+use super::{assert_conservation, market::MarketFixture, spl::MintFixture, SvmContext};
 use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use clob::instructions::InitializeParams;
 use solana_sdk::{signature::Keypair, signer::Signer};
 use std::{cell::RefCell, rc::Rc};
 
@@ -11,28 +13,34 @@ pub struct TestFixture {
 
 impl TestFixture {
     pub async fn new() -> Self {
-        let mut ctx = SvmContext::new();
-        ctx.svm
-            .add_program_from_file(clob::ID, "../../target/deploy/clob.so")
-            .expect("Failed to add clob program");
+        Self::new_with_token_programs(spl_token::ID, spl_token::ID).await
+    }
 
-        let ctx = Rc::new(RefCell::new(ctx));
+    /// Base and quote mints owned by the given token programs, e.g. a classic
+    /// SPL base with a Token-2022 quote
+    pub async fn new_with_token_programs(
+        base_token_program: Pubkey,
+        quote_token_program: Pubkey,
+    ) -> Self {
+        let ctx = Rc::new(RefCell::new(SvmContext::new()));
 
         // Create base mint (6 decimals for typical token)
         let base_mint_keypair = Keypair::new();
-        let base_mint = MintFixture::new(
+        let base_mint = MintFixture::new_with_program(
             ctx.clone(),
             base_mint_keypair,
             6, // decimals
+            base_token_program,
         )
         .await;
 
         // Create quote mint (6 decimals for typical token)
         let quote_mint_keypair = Keypair::new();
-        let quote_mint = MintFixture::new(
+        let quote_mint = MintFixture::new_with_program(
             ctx.clone(),
             quote_mint_keypair,
             6, // decimals
+            quote_token_program,
         )
         .await;
 
@@ -55,11 +63,30 @@ pub struct TradingScenario {
 
 impl TradingScenario {
     pub async fn new() -> Self {
-        let fixture = TestFixture::new().await;
+        Self::with_market_params(|_| {}).await
+    }
+
+    /// Same as `new` but with `configure` applied to the market's InitializeParams
+    pub async fn with_market_params(configure: impl FnOnce(&mut InitializeParams)) -> Self {
+        Self::with_fixture(TestFixture::new().await, configure).await
+    }
+
+    /// Build the market and users on an existing `fixture`'s mints
+    pub async fn with_fixture(
+        fixture: TestFixture,
+        configure: impl FnOnce(&mut InitializeParams),
+    ) -> Self {
         let ctx = Rc::clone(&fixture.ctx);
 
         // Initialize market
-        let market = MarketFixture::new(ctx.clone(), &fixture.base_mint, &fixture.quote_mint).await;
+        let market = MarketFixture::new_with_params(
+            ctx.clone(),
+            &fixture.base_mint,
+            &fixture.quote_mint,
+            None,
+            configure,
+        )
+        .await;
 
         // Create pre-configured users
         let alice = TradingUser::new(ctx.clone(), &fixture, &market, "alice").await;
@@ -74,6 +101,22 @@ impl TradingScenario {
             charlie,
         }
     }
+
+    /// `assert_conservation` over the three users plus any `others` the test
+    /// opened balances for
+    pub fn assert_conservation_with(&self, others: &[Pubkey]) {
+        let mut users = vec![
+            self.alice.pubkey(),
+            self.bob.pubkey(),
+            self.charlie.pubkey(),
+        ];
+        users.extend_from_slice(others);
+        assert_conservation(&self.market, &users);
+    }
+
+    pub fn assert_conservation(&self) {
+        self.assert_conservation_with(&[]);
+    }
 }
 
 /// Simplified two-user trading scenario
@@ -81,6 +124,7 @@ pub struct TwoUserScenario {
     pub market: MarketFixture,
     pub alice: TradingUser,
     pub bob: TradingUser,
+    charlie: Pubkey, // Still holds the deposits TradingScenario made for him
 }
 
 impl TwoUserScenario {
@@ -90,8 +134,16 @@ impl TwoUserScenario {
             market: scenario.market,
             alice: scenario.alice,
             bob: scenario.bob,
+            charlie: scenario.charlie.pubkey(),
         }
     }
+
+    pub fn assert_conservation(&self) {
+        assert_conservation(
+            &self.market,
+            &[self.alice.pubkey(), self.bob.pubkey(), self.charlie],
+        );
+    }
 }
 
 /// Pre-configured user with tokens and market deposits ready
@@ -1,2 +1,4 @@
 mod cases;
 mod svm;
+
+const BACKEND: svm::Backend = svm::Backend::LiteSvm;